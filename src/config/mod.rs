@@ -53,6 +53,69 @@ impl Config {
         env::var("MAIL_FROM").unwrap_or_else(|_| "Mento Services <noreply@mentoservices.com>".to_string())
     }
 
+    /// "razorpay" (default) or "mock" for offline development and tests
+    pub fn payment_gateway() -> String {
+        env::var("PAYMENT_GATEWAY").unwrap_or_else(|_| "razorpay".to_string()).to_lowercase()
+    }
+
+    pub fn company_name() -> String {
+        env::var("COMPANY_NAME").unwrap_or_else(|_| "Mento Services".to_string())
+    }
+
+    pub fn company_gstin() -> String {
+        env::var("COMPANY_GSTIN").unwrap_or_default()
+    }
+
+    pub fn company_address() -> String {
+        env::var("COMPANY_ADDRESS").unwrap_or_default()
+    }
+
+    /// State the company is registered in, decides CGST+SGST vs IGST
+    pub fn company_state() -> String {
+        env::var("COMPANY_STATE").unwrap_or_else(|_| "Gujarat".to_string())
+    }
+
+    pub fn invoice_prefix() -> String {
+        env::var("INVOICE_PREFIX").unwrap_or_else(|_| "MS".to_string())
+    }
+
+    pub fn invoice_sac_code() -> String {
+        env::var("INVOICE_SAC_CODE").unwrap_or_else(|_| "998439".to_string())
+    }
+
+    pub fn gst_rate() -> f64 {
+        env::var("GST_RATE")
+            .unwrap_or_else(|_| "18".to_string())
+            .parse()
+            .unwrap_or(18.0)
+    }
+
+    /// Average urban travel speed used for ETA estimates
+    pub fn travel_speed_kmph() -> f64 {
+        env::var("TRAVEL_SPEED_KMPH")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse()
+            .unwrap_or(20.0)
+    }
+
+    /// Public base URL of this API, used for links in emails
+    pub fn public_api_url() -> String {
+        env::var("PUBLIC_API_URL")
+            .unwrap_or_else(|_| "http://localhost:8000".to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Path to the Firebase service-account key (JSON) used to send push notifications
+    pub fn fcm_service_account_file() -> String {
+        env::var("FCM_SERVICE_ACCOUNT_FILE").unwrap_or_default()
+    }
+
+    /// Firebase project to send through, defaults to the one in the service-account key
+    pub fn fcm_project_id() -> String {
+        env::var("FCM_PROJECT_ID").unwrap_or_default()
+    }
+
     pub fn is_development() -> bool {
         env::var("ROCKET_ENV").unwrap_or_default() == "development"
     }
@@ -1,4 +1,6 @@
-use mongodb::{Client, Database};
+use mongodb::{Client, Database, IndexModel};
+use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
 use rocket::{Rocket, Build};
 use rocket::fairing::AdHoc;
 
@@ -26,8 +28,364 @@ async fn connect() -> Result<Database, mongodb::error::Error> {
         .database("admin")
         .run_command(mongodb::bson::doc! {"ping": 1}, None)
         .await?;
-    
-    Ok(client.database("mento-services"))
+
+    let database = client.database("mento-services");
+    ensure_indexes(&database).await;
+    migrate(&database).await;
+
+    Ok(database)
+}
+
+/// Indexes that queries depend on to work at all, not just to be fast
+async fn ensure_indexes(db: &Database) {
+    // Backs `$text` in worker search
+    let worker_text = IndexModel::builder()
+        .keys(doc! { "description": "text", "categories": "text", "service_areas": "text" })
+        .options(
+            IndexOptions::builder()
+                .name("worker_search_text".to_string())
+                .weights(doc! { "categories": 5, "service_areas": 3, "description": 1 })
+                .build(),
+        )
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("worker_profiles").create_index(worker_text, None).await {
+        warn!("Failed to create worker search text index: {}", e);
+    }
+
+    // Backs `$geoNear` in nearby search
+    let worker_location = IndexModel::builder()
+        .keys(doc! { "location": "2dsphere" })
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("worker_profiles").create_index(worker_location, None).await {
+        warn!("Failed to create worker location index: {}", e);
+    }
+
+    // Backs `$geoIntersects` on service coverage
+    let worker_coverage = IndexModel::builder()
+        .keys(doc! { "coverage_area": "2dsphere" })
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("worker_profiles").create_index(worker_coverage, None).await {
+        warn!("Failed to create worker coverage index: {}", e);
+    }
+
+    // Live location trail, read back per booking in time order
+    let booking_trail = IndexModel::builder()
+        .keys(doc! { "booking_id": 1, "recorded_at": 1 })
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("booking_locations").create_index(booking_trail, None).await {
+        warn!("Failed to create booking location index: {}", e);
+    }
+
+    // One favourite per customer and worker, so counts stay right under racing saves
+    let favorite_unique = IndexModel::builder()
+        .keys(doc! { "customer_id": 1, "worker_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("favorite_workers").create_index(favorite_unique, None).await {
+        warn!("Failed to create favourite workers index: {}", e);
+    }
+
+    let badge_code = IndexModel::builder()
+        .keys(doc! { "code": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("badge_rules").create_index(badge_code, None).await {
+        warn!("Failed to create badge rule index: {}", e);
+    }
+
+    // Backs `q` in the public job feed
+    let job_text = IndexModel::builder()
+        .keys(doc! { "title": "text", "skills": "text", "description": "text" })
+        .options(
+            IndexOptions::builder()
+                .name("job_search_text".to_string())
+                .weights(doc! { "title": 10, "skills": 5, "description": 1 })
+                .build(),
+        )
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("jobs").create_index(job_text, None).await {
+        warn!("Failed to create job search text index: {}", e);
+    }
+
+    // One application per applicant and job
+    let application_unique = IndexModel::builder()
+        .keys(doc! { "job_id": 1, "applicant_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("job_applications").create_index(application_unique, None).await {
+        warn!("Failed to create job application index: {}", e);
+    }
+
+    // Backs the per-profile application count
+    let application_profile = IndexModel::builder()
+        .keys(doc! { "job_seeker_profile_id": 1 })
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("job_applications").create_index(application_profile, None).await {
+        warn!("Failed to create job application profile index: {}", e);
+    }
+
+    // Looked up by the unsubscribe link in alert emails
+    let unsubscribe_token = IndexModel::builder()
+        .keys(doc! { "unsubscribe_token": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("saved_searches").create_index(unsubscribe_token, None).await {
+        warn!("Failed to create saved search index: {}", e);
+    }
+
+    // One counted profile view per viewer per day
+    let profile_view_unique = IndexModel::builder()
+        .keys(doc! { "profile_id": 1, "viewer_key": 1, "date": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("profile_views").create_index(profile_view_unique, None).await {
+        warn!("Failed to create profile view index: {}", e);
+    }
+
+    // One company profile per user, and a GSTIN or CIN registers only one company
+    let employer_owner = IndexModel::builder()
+        .keys(doc! { "owner_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+    let employer_gstin = IndexModel::builder()
+        .keys(doc! { "gstin": 1 })
+        .options(
+            IndexOptions::builder()
+                .unique(true)
+                .partial_filter_expression(doc! { "gstin": { "$type": "string" } })
+                .build(),
+        )
+        .build();
+    let employer_cin = IndexModel::builder()
+        .keys(doc! { "cin": 1 })
+        .options(
+            IndexOptions::builder()
+                .unique(true)
+                .partial_filter_expression(doc! { "cin": { "$type": "string" } })
+                .build(),
+        )
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("employers")
+        .create_indexes(vec![employer_owner, employer_gstin, employer_cin], None)
+        .await
+    {
+        warn!("Failed to create employer indexes: {}", e);
+    }
+
+    // Renewal webhooks are deduplicated on the gateway payment id
+    let payment_gateway_id = IndexModel::builder()
+        .keys(doc! { "razorpay_payment_id": 1 })
+        .options(
+            IndexOptions::builder()
+                .unique(true)
+                .partial_filter_expression(doc! { "razorpay_payment_id": { "$type": "string" } })
+                .build(),
+        )
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("payments").create_index(payment_gateway_id, None).await {
+        warn!("Failed to create payment gateway id index: {}", e);
+    }
+
+    // One invoice per payment
+    let invoice_payment = IndexModel::builder()
+        .keys(doc! { "payment_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("invoices").create_index(invoice_payment, None).await {
+        warn!("Failed to create invoice payment index: {}", e);
+    }
+
+    // Coupon codes are looked up by code at checkout
+    let coupon_code = IndexModel::builder()
+        .keys(doc! { "code": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    if let Err(e) = db.collection::<mongodb::bson::Document>("coupons").create_index(coupon_code, None).await {
+        warn!("Failed to create coupon code index: {}", e);
+    }
+}
+
+/// One-off data fixes. Each is recorded in `migrations` once it succeeds, so it runs once per
+/// database even with several instances starting together.
+async fn migrate(db: &Database) {
+    run_migration(db, "payments_refunded_amount", backfill_refunded_amount(db)).await;
+    run_migration(db, "seed_badge_rules", seed_badge_rules(db)).await;
+    run_migration(db, "jobs_typed_status", normalize_job_fields(db)).await;
+    run_migration(db, "jobs_employer_id", link_jobs_to_employers(db)).await;
+}
+
+async fn run_migration(
+    db: &Database,
+    name: &str,
+    migration: impl std::future::Future<Output = Result<(), mongodb::error::Error>>,
+) {
+    let markers = db.collection::<mongodb::bson::Document>("migrations");
+    match markers.insert_one(doc! { "_id": name, "applied_at": mongodb::bson::DateTime::now() }, None).await {
+        Ok(_) => {}
+        Err(e) if crate::utils::is_duplicate_key(&e) => return,
+        Err(e) => {
+            warn!("Failed to claim migration {}: {}", name, e);
+            return;
+        }
+    }
+
+    match migration.await {
+        Ok(()) => info!("Applied migration {}", name),
+        Err(e) => {
+            warn!("Migration {} failed, it will be retried on next start: {}", name, e);
+            if let Err(e) = markers.delete_one(doc! { "_id": name }, None).await {
+                warn!("Failed to release migration {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Default badge rules, seeded once so admins can edit or remove them afterwards
+async fn seed_badge_rules(db: &Database) -> Result<(), mongodb::error::Error> {
+    let rules = db.collection::<crate::models::BadgeRule>("badge_rules");
+    if rules.count_documents(doc! {}, None).await? > 0 {
+        return Ok(());
+    }
+
+    // The unique `code` index makes a rule seeded by another instance a no-op
+    for rule in crate::models::default_badge_rules() {
+        match rules.insert_one(rule, None).await {
+            Ok(_) => {}
+            Err(e) if crate::utils::is_duplicate_key(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// `refunded_amount` replaced summing the refund ledger on every read
+async fn backfill_refunded_amount(db: &Database) -> Result<(), mongodb::error::Error> {
+    let pipeline = vec![doc! {
+        "$set": {
+            "refunded_amount": {
+                "$sum": {
+                    "$map": {
+                        "input": {
+                            "$filter": {
+                                "input": { "$ifNull": ["$refunds", []] },
+                                "cond": { "$ne": ["$$this.status", "failed"] }
+                            }
+                        },
+                        "in": "$$this.amount"
+                    }
+                }
+            }
+        }
+    }];
+
+    db.collection::<mongodb::bson::Document>("payments")
+        .update_many(doc! { "refunded_amount": { "$exists": false } }, pipeline, None)
+        .await?;
+    Ok(())
+}
+
+/// Job `status` and `job_type` used to be free strings; map them onto the enums
+async fn normalize_job_fields(db: &Database) -> Result<(), mongodb::error::Error> {
+    let jobs = db.collection::<mongodb::bson::Document>("jobs");
+    let statuses = ["pending", "approved", "rejected", "closed"];
+    let job_types = ["fulltime", "parttime", "contract", "freelance"];
+
+    // Legacy postings went live as "active"
+    jobs.update_many(
+        doc! { "status": { "$in": ["active", "open", "live", "published"] } },
+        vec![doc! {
+            "$set": {
+                "status": "approved",
+                "published_at": { "$ifNull": ["$published_at", "$created_at"] }
+            }
+        }],
+        None,
+    ).await?;
+
+    jobs.update_many(
+        doc! { "status": { "$in": ["inactive", "expired", "filled", "archived"] } },
+        vec![doc! {
+            "$set": {
+                "status": "closed",
+                "closed_at": { "$ifNull": ["$closed_at", "$updated_at"] }
+            }
+        }],
+        None,
+    ).await?;
+
+    // Anything else goes back through moderation
+    jobs.update_many(
+        doc! { "status": { "$nin": statuses.as_slice() } },
+        doc! { "$set": { "status": "pending" } },
+        None,
+    ).await?;
+
+    // "Full-time", "part_time" and the like; unrecognised types are dropped
+    let squashed = doc! {
+        "$replaceAll": {
+            "input": {
+                "$replaceAll": {
+                    "input": {
+                        "$replaceAll": { "input": { "$toLower": "$job_type" }, "find": "-", "replacement": "" }
+                    },
+                    "find": "_",
+                    "replacement": ""
+                }
+            },
+            "find": " ",
+            "replacement": ""
+        }
+    };
+    jobs.update_many(
+        doc! { "job_type": { "$ne": null, "$nin": job_types.as_slice() } },
+        vec![
+            doc! { "$set": { "job_type": squashed } },
+            doc! {
+                "$set": {
+                    "job_type": { "$cond": [{ "$in": ["$job_type", job_types.as_slice()] }, "$job_type", null] }
+                }
+            },
+        ],
+        None,
+    ).await?;
+
+    Ok(())
+}
+
+/// Jobs posted before employer profiles existed only carry `posted_by`
+async fn link_jobs_to_employers(db: &Database) -> Result<(), mongodb::error::Error> {
+    let jobs = db.collection::<mongodb::bson::Document>("jobs");
+    let mut employers = db.collection::<mongodb::bson::Document>("employers")
+        .find(doc! {}, None)
+        .await?;
+
+    while employers.advance().await? {
+        let employer = employers.deserialize_current()?;
+        let (Ok(id), Ok(owner_id)) = (employer.get_object_id("_id"), employer.get_object_id("owner_id")) else {
+            continue;
+        };
+        jobs.update_many(
+            doc! { "posted_by": owner_id, "employer_id": null },
+            doc! { "$set": { "employer_id": id, "company": employer.get_str("company_name").ok() } },
+            None,
+        ).await?;
+    }
+    Ok(())
 }
 
 pub type DbConn = Database;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use rocket::request::{self, Request, FromRequest, Outcome};
+use rocket::http::Status;
+use rocket::State;
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use mongodb::bson::doc;
+use rocket_okapi::request::OpenApiFromRequest;
+use rocket_okapi::r#gen::OpenApiGenerator;
+use rocket_okapi::request::RequestHeaderInput;
+
+/// Signed-in user with `is_admin` set on their account
+pub struct AdminGuard {
+    pub auth: AuthGuard,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminGuard {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let auth = match req.guard::<AuthGuard>().await {
+            Outcome::Success(auth) => auth,
+            Outcome::Error(e) => return Outcome::Error(e),
+            Outcome::Forward(f) => return Outcome::Forward(f),
+        };
+
+        let Outcome::Success(db) = req.guard::<&State<DbConn>>().await else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+
+        let user = db.collection::<crate::models::User>("users")
+            .find_one(doc! { "_id": &auth.user_id, "is_admin": true, "is_active": true }, None)
+            .await;
+
+        match user {
+            Ok(Some(_)) => Outcome::Success(AdminGuard { auth }),
+            Ok(None) => Outcome::Error((Status::Forbidden, ())),
+            Err(e) => {
+                error!("Admin guard lookup failed: {}", e);
+                Outcome::Error((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for AdminGuard {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
@@ -1,5 +1,9 @@
+pub mod admin;
 pub mod auth;
 pub mod kyc;
+pub mod razorpay;
 
+pub use admin::AdminGuard;
 pub use auth::AuthGuard;
-pub use kyc::KycGuard;
\ No newline at end of file
+pub use kyc::KycGuard;
+pub use razorpay::RazorpaySignature;
@@ -0,0 +1,29 @@
+use rocket::request::{self, FromRequest, Request, Outcome};
+use rocket::http::Status;
+use rocket_okapi::request::{OpenApiFromRequest, RequestHeaderInput};
+use rocket_okapi::r#gen::OpenApiGenerator;
+
+/// Raw `X-Razorpay-Signature` header sent with webhook deliveries
+pub struct RazorpaySignature(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RazorpaySignature {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match req.headers().get_one("X-Razorpay-Signature") {
+            Some(signature) => Outcome::Success(RazorpaySignature(signature.to_string())),
+            None => Outcome::Error((Status::BadRequest, ())),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for RazorpaySignature {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
@@ -7,6 +7,7 @@ mod guards;
 mod models;
 mod routes;
 mod services;
+mod tasks;
 mod utils;
 
 use dotenvy::dotenv;
@@ -96,7 +97,9 @@ fn rocket() -> Rocket<Build> {
 
     rocket::build()
         .attach(db::init())
+        .attach(tasks::init())
         .attach(CORS)
+        .manage(routes::tracking::LocationHub::default())
         .mount("/", routes![options_handler])
         .mount(
             "/api/v1",
@@ -122,6 +125,15 @@ fn rocket() -> Rocket<Build> {
                 routes::worker::create_subscription,
                 routes::worker::verify_subscription_payment,
                 routes::worker::get_subscription_status,
+                routes::subscription::cancel_subscription,
+                routes::subscription::update_auto_renew,
+                routes::subscription::change_plan,
+                routes::subscription::verify_plan_change,
+                routes::subscription::razorpay_webhook,
+                routes::subscription::mock_checkout,
+                routes::invoice::get_my_invoices,
+                routes::invoice::get_invoice_html,
+                routes::invoice::get_invoice_pdf,
                 // Worker
                 routes::worker::create_worker_profile,
                 routes::worker::get_worker_profile,
@@ -130,6 +142,22 @@ fn rocket() -> Rocket<Build> {
                 routes::worker::search_workers,
                 routes::worker::find_nearby_workers,
                 routes::worker::update_worker_location,
+                routes::worker::update_worker_schedule,
+                routes::worker::get_worker_schedule,
+                routes::worker::delete_worker_schedule,
+                routes::worker::get_worker_slots,
+                routes::worker::add_portfolio_item,
+                routes::worker::reorder_portfolio,
+                routes::worker::pair_portfolio_items,
+                routes::worker::update_portfolio_item,
+                routes::worker::delete_portfolio_item,
+                routes::worker::unpair_portfolio_item,
+                routes::worker::update_worker_coverage,
+                routes::worker::delete_worker_coverage,
+                routes::worker::find_serving_workers,
+                routes::dashboard::get_worker_dashboard,
+                routes::onboarding::get_onboarding,
+                routes::onboarding::submit_onboarding,
                 // Categories
                 routes::category::get_all_categories,
                 routes::category::get_subcategories,
@@ -143,6 +171,42 @@ fn rocket() -> Rocket<Build> {
                 routes::file_upload::upload_image,
                 routes::file_upload::upload_document,
                 routes::file_upload::upload_document_base64,
+                // Bookings
+                routes::booking::create_booking,
+                routes::booking::get_customer_bookings,
+                routes::booking::get_worker_bookings,
+                routes::booking::get_booking,
+                routes::booking::accept_booking,
+                routes::booking::decline_booking,
+                routes::booking::schedule_booking,
+                routes::booking::start_booking,
+                routes::booking::complete_booking,
+                routes::booking::cancel_booking,
+                // Live location
+                routes::tracking::set_booking_en_route,
+                routes::tracking::update_booking_location,
+                routes::tracking::stream_booking_location,
+                routes::tracking::get_booking_location_trail,
+                // Favourites
+                routes::favorite::save_favorite,
+                routes::favorite::remove_favorite,
+                routes::favorite::get_favorites,
+                routes::favorite::rebook_favorite,
+                // Badges
+                routes::badge::get_my_badges,
+                // Quotes
+                routes::quote::request_quote,
+                routes::quote::counter_quote,
+                routes::quote::respond_to_quote,
+                routes::quote::accept_quote,
+                routes::quote::reject_quote,
+                routes::quote::get_customer_quotes,
+                routes::quote::get_worker_quotes,
+                routes::quote::get_quote,
+                // Notifications
+                routes::notification::get_notifications,
+                routes::notification::mark_notification_read,
+                routes::notification::mark_all_notifications_read,
                 // Reviews
                 routes::review::create_review,
                 routes::review::get_worker_reviews,
@@ -158,9 +222,48 @@ fn rocket() -> Rocket<Build> {
                 routes::job::update_job_seeker_profile,
                 routes::job::search_job_seekers,
                 routes::job::delete_job_seeker_profile,
+                // Job Posting
+                routes::job::create_job,
+                routes::job::update_job,
+                routes::job::close_job,
+                routes::job::get_my_jobs,
+                routes::job::search_jobs,
+                routes::job::get_job,
+                // Employers
+                routes::employer::create_employer_profile,
+                routes::employer::get_employer_profile,
+                routes::employer::update_employer_profile,
+                routes::employer::submit_employer_verification,
+                routes::employer::get_company_page,
+                // Job Applications
+                routes::job_application::apply_to_job,
+                routes::job_application::withdraw_application,
+                routes::job_application::get_my_applications,
+                routes::job_application::get_application,
+                routes::job_application::get_job_applications,
+                routes::job_application::update_application_stage,
+                routes::job_application::add_application_note,
+                // Job Matching
+                routes::matching::get_recommended_jobs,
+                routes::matching::get_recommended_candidates,
+                // Saved Searches & Alerts
+                routes::saved_search::create_saved_search,
+                routes::saved_search::get_saved_searches,
+                routes::saved_search::update_saved_search,
+                routes::saved_search::delete_saved_search,
+                routes::saved_search::confirm_unsubscribe_saved_search,
+                routes::saved_search::unsubscribe_saved_search,
+                // Profile Views
+                routes::profile_view::get_job_seeker_profile_views,
+                routes::profile_view::get_worker_profile_views,
                 // Admin Routes - Workers
                 routes::admin::get_all_workers,
                 routes::admin::verify_worker,
+                routes::admin::get_worker_onboarding,
+                routes::admin::update_worker_onboarding,
+                // Admin Routes - Employers
+                routes::admin::get_all_employers,
+                routes::admin::update_employer_verification,
                 // Admin Routes - Job Seekers
                 routes::admin::get_all_job_seekers,
                 routes::admin::verify_job_seeker,
@@ -176,6 +279,28 @@ fn rocket() -> Rocket<Build> {
                 routes::admin::get_all_jobs,
                 routes::admin::update_job_status,
                 routes::admin::delete_job,
+                // Admin Routes - Payments
+                routes::admin::get_all_payments,
+                routes::admin::refund_payment,
+                routes::admin::sync_payment_refund,
+                routes::admin::get_gateway_payment,
+                // Admin Routes - Analytics
+                routes::analytics::get_revenue_report,
+                routes::analytics::export_revenue_report,
+                routes::analytics::get_subscription_report,
+                routes::analytics::export_subscription_report,
+                routes::analytics::export_plan_report,
+                // Admin Routes - Coupons
+                routes::admin::create_coupon,
+                routes::admin::get_all_coupons,
+                routes::admin::update_coupon,
+                routes::admin::get_coupon_redemptions,
+                // Admin Routes - Badges
+                routes::admin::get_badge_rules,
+                routes::admin::create_badge_rule,
+                routes::admin::update_badge_rule,
+                routes::admin::evaluate_badges_now,
+                routes::admin::get_worker_badge_history,
             ],
         )
         .mount("/uploads", FileServer::from("uploads"))
@@ -0,0 +1,309 @@
+use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone, Timelike};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// Schedules are entered and evaluated in Indian Standard Time
+pub const IST_OFFSET_SECS: i32 = 5 * 3600 + 30 * 60;
+pub const DEFAULT_SLOT_MINUTES: i32 = 60;
+
+pub fn ist() -> FixedOffset {
+    FixedOffset::east_opt(IST_OFFSET_SECS).unwrap()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<chrono::Weekday> for Weekday {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+/// "HH:MM" to "HH:MM" in IST, end exclusive. "24:00" is allowed as an end.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TimeWindow {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct DaySchedule {
+    pub day: Weekday,
+    pub hours: Vec<TimeWindow>,
+    #[serde(default)]
+    pub breaks: Vec<TimeWindow>,
+}
+
+/// A full day off
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Holiday {
+    pub date: String, // "YYYY-MM-DD"
+    pub name: Option<String>,
+}
+
+/// A one-off unavailable period; the whole day when no times are given
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct BlackoutDate {
+    pub date: String, // "YYYY-MM-DD"
+    pub window: Option<TimeWindow>,
+    pub reason: Option<String>,
+}
+
+/// Weekly working hours embedded in the worker profile
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkerSchedule {
+    pub weekly: Vec<DaySchedule>,
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutDate>,
+    pub slot_minutes: i32,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateScheduleDto {
+    pub weekly: Vec<DaySchedule>,
+    pub holidays: Option<Vec<Holiday>>,
+    pub blackouts: Option<Vec<BlackoutDate>>,
+    /// Length of a bookable slot, 15 to 480 minutes. Defaults to 60.
+    pub slot_minutes: Option<i32>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct SlotsQuery {
+    /// "YYYY-MM-DD" in IST
+    pub date: String,
+}
+
+/// Minutes since midnight, `[start, end)`
+pub type MinuteRange = (i32, i32);
+
+fn parse_minutes(value: &str) -> Result<i32, String> {
+    let (h, m) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid time '{}', expected HH:MM", value))?;
+    let (h, m): (i32, i32) = match (h.parse(), m.parse()) {
+        (Ok(h), Ok(m)) => (h, m),
+        _ => return Err(format!("Invalid time '{}', expected HH:MM", value)),
+    };
+    if !(0..=24).contains(&h) || !(0..60).contains(&m) || (h == 24 && m != 0) {
+        return Err(format!("Invalid time '{}'", value));
+    }
+    Ok(h * 60 + m)
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+}
+
+impl TimeWindow {
+    pub fn minutes(&self) -> Result<MinuteRange, String> {
+        let (start, end) = (parse_minutes(&self.start)?, parse_minutes(&self.end)?);
+        if end <= start {
+            return Err(format!("Window {}-{} ends before it starts", self.start, self.end));
+        }
+        Ok((start, end))
+    }
+}
+
+/// Remove `cut` from every range in `ranges`
+fn subtract(ranges: Vec<MinuteRange>, cut: MinuteRange) -> Vec<MinuteRange> {
+    let mut result = Vec::new();
+    for (start, end) in ranges {
+        if cut.1 <= start || cut.0 >= end {
+            result.push((start, end));
+            continue;
+        }
+        if cut.0 > start {
+            result.push((start, cut.0));
+        }
+        if cut.1 < end {
+            result.push((cut.1, end));
+        }
+    }
+    result
+}
+
+/// Sort and merge overlapping ranges
+fn merge(mut ranges: Vec<MinuteRange>) -> Vec<MinuteRange> {
+    ranges.sort();
+    let mut merged: Vec<MinuteRange> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+impl WorkerSchedule {
+    /// Check every time and date in the schedule parses
+    pub fn validate(&self) -> Result<(), String> {
+        if !(15..=480).contains(&self.slot_minutes) {
+            return Err("Slot length must be between 15 and 480 minutes".to_string());
+        }
+        for day in &self.weekly {
+            for window in day.hours.iter().chain(day.breaks.iter()) {
+                window.minutes()?;
+            }
+        }
+        for holiday in &self.holidays {
+            parse_date(&holiday.date)?;
+        }
+        for blackout in &self.blackouts {
+            parse_date(&blackout.date)?;
+            if let Some(ref window) = blackout.window {
+                window.minutes()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Working time left on `date` after breaks, holidays and blackouts
+    pub fn working_ranges(&self, date: NaiveDate) -> Vec<MinuteRange> {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        if self.holidays.iter().any(|h| h.date == date_str) {
+            return Vec::new();
+        }
+
+        let weekday = Weekday::from(date.weekday());
+        let mut ranges = Vec::new();
+        let mut cuts = Vec::new();
+        for day in self.weekly.iter().filter(|d| d.day == weekday) {
+            ranges.extend(day.hours.iter().filter_map(|w| w.minutes().ok()));
+            cuts.extend(day.breaks.iter().filter_map(|w| w.minutes().ok()));
+        }
+
+        for blackout in self.blackouts.iter().filter(|b| b.date == date_str) {
+            match blackout.window {
+                Some(ref window) => cuts.extend(window.minutes().ok()),
+                None => return Vec::new(),
+            }
+        }
+
+        cuts.into_iter().fold(merge(ranges), subtract)
+    }
+
+    /// Whether the schedule has the worker working at `at`
+    pub fn is_working_at(&self, at: DateTime) -> bool {
+        let local = ist().timestamp_millis_opt(at.timestamp_millis()).unwrap();
+        let minute = (local.hour() * 60 + local.minute()) as i32;
+        self.working_ranges(local.date_naive())
+            .iter()
+            .any(|(start, end)| (*start..*end).contains(&minute))
+    }
+
+    /// Free slots on `date`, with `busy` ranges (existing bookings) and
+    /// anything before `not_before` (minutes) removed
+    pub fn free_slots(&self, date: NaiveDate, busy: &[MinuteRange], not_before: i32) -> Vec<MinuteRange> {
+        let free = busy
+            .iter()
+            .copied()
+            .chain(std::iter::once((0, not_before)))
+            .fold(self.working_ranges(date), subtract);
+
+        let length = self.slot_minutes;
+        free.into_iter()
+            .flat_map(|(start, end)| {
+                (0..)
+                    .map(move |i| start + i * length)
+                    .take_while(move |slot_start| slot_start + length <= end)
+                    .map(move |slot_start| (slot_start, slot_start + length))
+            })
+            .collect()
+    }
+}
+
+/// UTC instant for a minute of a day in IST
+pub fn ist_instant(date: NaiveDate, minute: i32) -> DateTime {
+    let midnight = ist()
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .unwrap();
+    DateTime::from_millis(midnight.timestamp_millis() + minute as i64 * 60 * 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str) -> TimeWindow {
+        TimeWindow { start: start.to_string(), end: end.to_string() }
+    }
+
+    /// Mondays 09:00 to 13:00 with a break 11:00 to 11:30, hour slots
+    fn schedule() -> WorkerSchedule {
+        WorkerSchedule {
+            weekly: vec![DaySchedule {
+                day: Weekday::Monday,
+                hours: vec![window("09:00", "13:00")],
+                breaks: vec![window("11:00", "11:30")],
+            }],
+            holidays: Vec::new(),
+            blackouts: Vec::new(),
+            slot_minutes: DEFAULT_SLOT_MINUTES,
+            updated_at: DateTime::now(),
+        }
+    }
+
+    fn monday() -> NaiveDate {
+        parse_date("2026-10-19").unwrap()
+    }
+
+    #[test]
+    fn slots_fill_working_hours_around_breaks() {
+        assert_eq!(schedule().free_slots(monday(), &[], 0), vec![(540, 600), (600, 660), (690, 750)]);
+    }
+
+    #[test]
+    fn no_slots_on_days_off() {
+        assert!(schedule().free_slots(parse_date("2026-10-20").unwrap(), &[], 0).is_empty());
+    }
+
+    #[test]
+    fn bookings_and_past_time_are_removed() {
+        let busy = [(600, 660)];
+        assert_eq!(schedule().free_slots(monday(), &busy, 0), vec![(540, 600), (690, 750)]);
+        assert_eq!(schedule().free_slots(monday(), &[], 600), vec![(600, 660), (690, 750)]);
+    }
+
+    #[test]
+    fn holidays_and_blackouts_are_removed() {
+        let mut holiday = schedule();
+        holiday.holidays.push(Holiday { date: "2026-10-19".to_string(), name: None });
+        assert!(holiday.free_slots(monday(), &[], 0).is_empty());
+
+        let mut blackout = schedule();
+        blackout.blackouts.push(BlackoutDate {
+            date: "2026-10-19".to_string(),
+            window: Some(window("09:00", "10:00")),
+            reason: None,
+        });
+        assert_eq!(blackout.free_slots(monday(), &[], 0), vec![(600, 660), (690, 750)]);
+    }
+
+    #[test]
+    fn short_gaps_give_no_slot() {
+        let busy = [(570, 600)];
+        assert_eq!(schedule().free_slots(monday(), &busy, 0), vec![(600, 660), (690, 750)]);
+    }
+}
@@ -0,0 +1,157 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// Worker figures a badge rule can test
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgeMetric {
+    Rating,
+    TotalReviews,
+    JobsCompleted,
+    ExperienceYears,
+    FavoriteCount,
+    AvgResponseMinutes, // Direct booking requests over the last 90 days
+    ResponseCount,      // Requests answered over the last 90 days
+    AcceptanceRate,     // Percent of answered requests that were accepted
+    KycApproved,        // 1 or 0
+    LicenseProvided,    // 1 or 0
+    Verified,           // 1 or 0, onboarding verified
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BadgeComparison {
+    Gte,
+    Lte,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct BadgeCondition {
+    pub metric: BadgeMetric,
+    pub op: BadgeComparison,
+    pub value: f64,
+}
+
+impl BadgeCondition {
+    /// Missing metrics (e.g. no response time yet) never satisfy a condition
+    pub fn holds(&self, actual: Option<f64>) -> bool {
+        match (actual, self.op) {
+            (Some(actual), BadgeComparison::Gte) => actual >= self.value,
+            (Some(actual), BadgeComparison::Lte) => actual <= self.value,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Admin-editable rule; a worker holds the badge while every condition holds
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BadgeRule {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub code: String, // Stable slug, e.g. "top_rated"
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    pub conditions: Vec<BadgeCondition>,
+    pub is_active: bool,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+/// Badge currently shown on a worker profile
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkerBadge {
+    pub code: String,
+    pub name: String,
+    pub icon_url: Option<String>,
+    pub awarded_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BadgeAction {
+    Awarded,
+    Revoked,
+}
+
+/// Award history, one entry per badge gained or lost
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BadgeAward {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub worker_id: ObjectId,
+    pub code: String,
+    pub name: String,
+    pub action: BadgeAction,
+    pub at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBadgeRuleDto {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    pub conditions: Vec<BadgeCondition>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateBadgeRuleDto {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    pub conditions: Option<Vec<BadgeCondition>>,
+    pub is_active: Option<bool>,
+}
+
+fn condition(metric: BadgeMetric, op: BadgeComparison, value: f64) -> BadgeCondition {
+    BadgeCondition { metric, op, value }
+}
+
+/// Rules created the first time the engine runs against an empty collection
+pub fn default_badge_rules() -> Vec<BadgeRule> {
+    use BadgeComparison::{Gte, Lte};
+    use BadgeMetric::*;
+
+    let now = DateTime::now();
+    let rule = |code: &str, name: &str, description: &str, conditions: Vec<BadgeCondition>| BadgeRule {
+        id: None,
+        code: code.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        icon_url: None,
+        conditions,
+        is_active: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    vec![
+        rule(
+            "top_rated",
+            "Top Rated",
+            "Rated 4.8 or higher across at least 50 reviews",
+            vec![condition(Rating, Gte, 4.8), condition(TotalReviews, Gte, 50.0)],
+        ),
+        rule(
+            "quick_responder",
+            "Quick Responder",
+            "Answers booking requests within 30 minutes on average",
+            vec![condition(AvgResponseMinutes, Lte, 30.0), condition(ResponseCount, Gte, 10.0)],
+        ),
+        rule(
+            "jobs_100",
+            "100 Jobs",
+            "Completed 100 jobs",
+            vec![condition(JobsCompleted, Gte, 100.0)],
+        ),
+        rule(
+            "verified_pro",
+            "Verified Pro",
+            "KYC approved and trade license on file",
+            vec![condition(KycApproved, Gte, 1.0), condition(LicenseProvided, Gte, 1.0)],
+        ),
+    ]
+}
@@ -0,0 +1,134 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use super::GeoLocation;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    Requested,
+    Accepted,
+    Declined,
+    Scheduled,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+impl BookingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingStatus::Requested => "requested",
+            BookingStatus::Accepted => "accepted",
+            BookingStatus::Declined => "declined",
+            BookingStatus::Scheduled => "scheduled",
+            BookingStatus::InProgress => "in_progress",
+            BookingStatus::Completed => "completed",
+            BookingStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// States a booking may move to from this one
+    pub fn next_states(&self) -> &'static [BookingStatus] {
+        match self {
+            BookingStatus::Requested => &[BookingStatus::Accepted, BookingStatus::Declined, BookingStatus::Cancelled],
+            BookingStatus::Accepted => &[BookingStatus::Scheduled, BookingStatus::Cancelled],
+            BookingStatus::Scheduled => &[BookingStatus::InProgress, BookingStatus::Cancelled],
+            BookingStatus::InProgress => &[BookingStatus::Completed],
+            BookingStatus::Declined | BookingStatus::Completed | BookingStatus::Cancelled => &[],
+        }
+    }
+
+    /// Field recording when the booking entered this state
+    pub fn timestamp_field(&self) -> &'static str {
+        match self {
+            BookingStatus::Requested => "requested_at",
+            BookingStatus::Accepted => "accepted_at",
+            BookingStatus::Declined => "declined_at",
+            BookingStatus::Scheduled => "scheduled_at",
+            BookingStatus::InProgress => "started_at",
+            BookingStatus::Completed => "completed_at",
+            BookingStatus::Cancelled => "cancelled_at",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BookingParty {
+    Customer,
+    Worker,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Booking {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,    // User who hires
+    pub worker_id: ObjectId,      // WorkerProfile id
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub description: Option<String>,
+    pub address: String,
+    pub location: GeoLocation,
+    pub hourly_rate: f64, // Worker's rate when the booking was made
+    pub estimated_hours: f64,
+    pub agreed_price: f64,
+    pub preferred_start: DateTime, // Asked for by the customer
+    pub quote_id: Option<ObjectId>, // Set when the price came from an accepted quote
+    pub scheduled_start: Option<DateTime>,
+    pub scheduled_end: Option<DateTime>,
+    pub status: BookingStatus,
+    pub decline_reason: Option<String>,
+    pub cancelled_by: Option<BookingParty>,
+    pub cancellation_reason: Option<String>,
+    pub requested_at: DateTime,
+    pub accepted_at: Option<DateTime>,
+    pub declined_at: Option<DateTime>,
+    pub scheduled_at: Option<DateTime>,
+    pub en_route_at: Option<DateTime>, // Worker set off, live location sharing starts
+    pub started_at: Option<DateTime>,
+    pub completed_at: Option<DateTime>,
+    pub cancelled_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Booking {
+    /// Live location is shared from setting off until the job ends
+    pub fn is_sharing_location(&self) -> bool {
+        match self.status {
+            BookingStatus::InProgress => true,
+            BookingStatus::Scheduled => self.en_route_at.is_some(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBookingDto {
+    pub worker_id: String,
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub description: Option<String>,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// RFC 3339, e.g. "2026-10-20T10:00:00+05:30"
+    pub preferred_start: String,
+    pub estimated_hours: f64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ScheduleBookingDto {
+    /// RFC 3339
+    pub scheduled_start: String,
+    /// RFC 3339, defaults to start + estimated hours
+    pub scheduled_end: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BookingReasonDto {
+    pub reason: Option<String>,
+}
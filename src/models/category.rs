@@ -20,6 +20,9 @@ pub struct MainCategory {
     pub icon: Option<String>,
     pub is_active: bool,
     pub order: i32,
+    /// Workers in this category must hold a trade license
+    #[serde(default)]
+    pub requires_license: bool,
 
     #[serde(default = "default_bson_datetime", skip_deserializing, skip_serializing)]
     #[schemars(skip)]
@@ -44,6 +47,8 @@ pub struct SubCategory {
     pub description: Option<String>,
     pub is_active: bool,
     pub order: i32,
+    #[serde(default)]
+    pub requires_license: bool,
 
     #[serde(default = "default_bson_datetime", skip_deserializing, skip_serializing)]
     #[schemars(skip)]
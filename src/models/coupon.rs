@@ -0,0 +1,152 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use super::SubscriptionType;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscountType {
+    Percentage,
+    Flat, // Fixed INR amount
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Coupon {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub code: String, // Stored uppercase
+    pub description: Option<String>,
+    pub discount_type: DiscountType,
+    pub discount_value: f64,
+    pub max_discount: Option<f64>, // Cap for percentage coupons
+    pub subscription_type: Option<SubscriptionType>, // None applies to both
+    #[serde(default)]
+    pub applicable_plans: Vec<String>, // Empty applies to every plan
+    pub valid_from: Option<DateTime>,
+    pub valid_until: Option<DateTime>,
+    pub max_redemptions: Option<i64>,
+    pub max_redemptions_per_user: Option<i64>,
+    #[serde(default)]
+    pub first_purchase_only: bool,
+    #[serde(default)]
+    pub redemption_count: i64, // Reserved + redeemed, released ones are given back
+    pub is_active: bool,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Coupon {
+    /// Discount in INR for a plan price, never more than the price itself
+    pub fn discount_for(&self, price: f64) -> f64 {
+        let discount = match self.discount_type {
+            DiscountType::Percentage => {
+                let amount = price * self.discount_value / 100.0;
+                self.max_discount.map_or(amount, |cap| amount.min(cap))
+            }
+            DiscountType::Flat => self.discount_value,
+        };
+        ((discount.max(0.0) * 100.0).round() / 100.0).min(price)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RedemptionStatus {
+    Reserved, // Order created, waiting for payment
+    Redeemed,
+    Released, // Order abandoned, the use was given back
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CouponRedemption {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub coupon_id: ObjectId,
+    pub code: String,
+    pub user_id: ObjectId,
+    pub subscription_id: Option<ObjectId>,
+    pub plan_name: String,
+    pub original_price: f64,
+    pub discount: f64,
+    pub final_price: f64,
+    pub status: RedemptionStatus,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateCouponDto {
+    pub code: String,
+    pub description: Option<String>,
+    pub discount_type: DiscountType,
+    pub discount_value: f64,
+    pub max_discount: Option<f64>,
+    pub subscription_type: Option<SubscriptionType>,
+    pub applicable_plans: Option<Vec<String>>,
+    /// RFC 3339, e.g. "2026-01-01T00:00:00+05:30"
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub max_redemptions: Option<i64>,
+    pub max_redemptions_per_user: Option<i64>,
+    pub first_purchase_only: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateCouponDto {
+    pub description: Option<String>,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub max_redemptions: Option<i64>,
+    pub max_redemptions_per_user: Option<i64>,
+    pub is_active: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coupon(discount_type: DiscountType, discount_value: f64, max_discount: Option<f64>) -> Coupon {
+        Coupon {
+            id: None,
+            code: "TEST".to_string(),
+            description: None,
+            discount_type,
+            discount_value,
+            max_discount,
+            subscription_type: None,
+            applicable_plans: Vec::new(),
+            valid_from: None,
+            valid_until: None,
+            max_redemptions: None,
+            max_redemptions_per_user: None,
+            first_purchase_only: false,
+            redemption_count: 0,
+            is_active: true,
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+        }
+    }
+
+    #[test]
+    fn percentage_discount_is_capped() {
+        assert_eq!(coupon(DiscountType::Percentage, 20.0, None).discount_for(500.0), 100.0);
+        assert_eq!(coupon(DiscountType::Percentage, 20.0, Some(50.0)).discount_for(500.0), 50.0);
+    }
+
+    #[test]
+    fn discount_is_rounded_to_paise() {
+        assert_eq!(coupon(DiscountType::Percentage, 12.5, None).discount_for(99.99), 12.5);
+    }
+
+    #[test]
+    fn discount_never_exceeds_price() {
+        assert_eq!(coupon(DiscountType::Flat, 300.0, None).discount_for(199.0), 199.0);
+        assert_eq!(coupon(DiscountType::Percentage, 150.0, None).discount_for(10.0), 10.0);
+    }
+
+    #[test]
+    fn negative_discount_is_ignored() {
+        assert_eq!(coupon(DiscountType::Flat, -5.0, None).discount_for(100.0), 0.0);
+    }
+}
@@ -0,0 +1,165 @@
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use super::GeoLocation;
+
+pub const MAX_COVERAGE_RADIUS_KM: f64 = 50.0;
+pub const MAX_COVERAGE_POLYGONS: usize = 10;
+pub const MAX_POLYGON_POINTS: usize = 200;
+
+/// Points used to approximate a radius as a polygon
+const CIRCLE_SEGMENTS: usize = 32;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageMode {
+    Radius,   // Circle around the worker's base location
+    Polygons, // Areas drawn on the map
+}
+
+/// What the worker configured. `WorkerProfile::coverage_area` is derived from it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServiceCoverage {
+    pub mode: CoverageMode,
+    pub radius_km: Option<f64>,
+    #[serde(default)]
+    pub polygons: Vec<Vec<[f64; 2]>>, // Outer rings, [longitude, latitude]
+    pub updated_at: DateTime,
+}
+
+/// GeoJSON MultiPolygon, indexed for `$geoIntersects`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GeoMultiPolygon {
+    #[serde(rename = "type")]
+    pub geo_type: String, // "MultiPolygon"
+    pub coordinates: Vec<Vec<Vec<[f64; 2]>>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateCoverageDto {
+    /// Serve everything within this many km of the base location
+    pub radius_km: Option<f64>,
+    /// Or serve these areas; each is a ring of [longitude, latitude] points
+    pub polygons: Option<Vec<Vec<[f64; 2]>>>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct ServingWorkerQuery {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+    pub min_rating: Option<f64>,
+    pub max_rate: Option<f64>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Ring approximating a circle of `radius_km` around `center`
+pub fn circle_polygon(center: [f64; 2], radius_km: f64) -> Vec<[f64; 2]> {
+    let (lng, lat) = (center[0].to_radians(), center[1].to_radians());
+    let angular = radius_km / EARTH_RADIUS_KM;
+
+    let mut ring: Vec<[f64; 2]> = (0..CIRCLE_SEGMENTS)
+        .map(|i| {
+            // Counter-clockwise, as GeoJSON expects for outer rings
+            let bearing = -(i as f64) * std::f64::consts::TAU / CIRCLE_SEGMENTS as f64;
+            let point_lat = (lat.sin() * angular.cos() + lat.cos() * angular.sin() * bearing.cos()).asin();
+            let point_lng = lng
+                + (bearing.sin() * angular.sin() * lat.cos())
+                    .atan2(angular.cos() - lat.sin() * point_lat.sin());
+            [point_lng.to_degrees(), point_lat.to_degrees()]
+        })
+        .collect();
+    ring.push(ring[0]);
+    ring
+}
+
+/// Check a drawn ring and close it if the client left it open
+pub fn normalize_ring(mut ring: Vec<[f64; 2]>) -> Result<Vec<[f64; 2]>, String> {
+    if ring.len() > MAX_POLYGON_POINTS {
+        return Err(format!("Areas can have at most {} points", MAX_POLYGON_POINTS));
+    }
+    if ring.iter().any(|[lng, lat]| !(-180.0..=180.0).contains(lng) || !(-90.0..=90.0).contains(lat)) {
+        return Err("Area has coordinates out of range".to_string());
+    }
+    if ring.first() != ring.last() {
+        ring.push(ring[0]);
+    }
+    if ring.len() < 4 {
+        return Err("Areas need at least three distinct points".to_string());
+    }
+    Ok(ring)
+}
+
+impl ServiceCoverage {
+    /// Searchable shape for this coverage around `location`
+    pub fn area(&self, location: &GeoLocation) -> GeoMultiPolygon {
+        let coordinates = match self.mode {
+            CoverageMode::Radius => vec![vec![circle_polygon(
+                location.coordinates,
+                self.radius_km.unwrap_or_default(),
+            )]],
+            CoverageMode::Polygons => self.polygons.iter().map(|ring| vec![ring.clone()]).collect(),
+        };
+
+        GeoMultiPolygon {
+            geo_type: "MultiPolygon".to_string(),
+            coordinates,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance_km(a: [f64; 2], b: [f64; 2]) -> f64 {
+        let (lat1, lat2) = (a[1].to_radians(), b[1].to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (b[0] - a[0]).to_radians();
+        let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+    }
+
+    #[test]
+    fn circle_is_a_closed_ring_at_the_radius() {
+        let center = [77.5946, 12.9716];
+        let ring = circle_polygon(center, 10.0);
+
+        assert_eq!(ring.len(), CIRCLE_SEGMENTS + 1);
+        assert_eq!(ring.first(), ring.last());
+        for point in &ring {
+            assert!((distance_km(center, *point) - 10.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn circle_runs_counter_clockwise() {
+        let ring = circle_polygon([77.5946, 12.9716], 5.0);
+        // Shoelace formula: positive area means counter-clockwise
+        let area: f64 = ring.windows(2).map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1]).sum();
+        assert!(area > 0.0);
+    }
+
+    #[test]
+    fn open_ring_is_closed() {
+        let ring = normalize_ring(vec![[77.0, 12.0], [77.1, 12.0], [77.1, 12.1]]).unwrap();
+        assert_eq!(ring, vec![[77.0, 12.0], [77.1, 12.0], [77.1, 12.1], [77.0, 12.0]]);
+    }
+
+    #[test]
+    fn closed_ring_is_kept() {
+        let closed = vec![[77.0, 12.0], [77.1, 12.0], [77.1, 12.1], [77.0, 12.0]];
+        assert_eq!(normalize_ring(closed.clone()).unwrap(), closed);
+    }
+
+    #[test]
+    fn invalid_rings_are_rejected() {
+        assert!(normalize_ring(vec![[77.0, 12.0], [77.1, 12.0]]).is_err());
+        assert!(normalize_ring(vec![[77.0, 12.0], [77.1, 12.0], [77.0, 12.0]]).is_err());
+        assert!(normalize_ring(vec![[190.0, 12.0], [77.1, 12.0], [77.1, 12.1]]).is_err());
+        assert!(normalize_ring(vec![[77.0, 12.0]; MAX_POLYGON_POINTS + 1]).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use crate::models::KycStatusEnum;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CompanySize {
+    Micro,      // 1-10 employees
+    Small,      // 11-50
+    Medium,     // 51-200
+    Large,      // 201-1000
+    Enterprise, // 1000+
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BusinessDocumentType {
+    GstCertificate,
+    IncorporationCertificate,
+    Pan,
+    ShopEstablishment,
+    Udyam,
+}
+
+/// A business document uploaded through `/upload/document`
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct BusinessDocument {
+    pub document_type: BusinessDocumentType,
+    pub document_number: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Employer {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub owner_id: ObjectId, // User who manages the company and posts its jobs
+
+    // Company details
+    pub company_name: String,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub industry: String,
+    pub size: CompanySize,
+    pub gstin: Option<String>,
+    pub cin: Option<String>,
+
+    // Registered address
+    pub address: String,
+    pub city: String,
+    pub state: String,
+    pub pincode: String,
+
+    // Verification, same lifecycle as user KYC
+    pub verification_status: KycStatusEnum,
+    pub documents: Vec<BusinessDocument>,
+    pub rejection_reason: Option<String>,
+    pub is_verified: bool, // True exactly when verification is approved
+    pub verified_at: Option<DateTime>,
+
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateEmployerDto {
+    pub company_name: String,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub industry: String,
+    pub size: CompanySize,
+    pub gstin: Option<String>,
+    pub cin: Option<String>,
+    pub address: String,
+    pub city: String,
+    pub state: String,
+    pub pincode: String,
+}
+
+/// Changing the company name, GSTIN or CIN of a verified company sends it back for verification
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateEmployerDto {
+    pub company_name: Option<String>,
+    pub logo_url: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub industry: Option<String>,
+    pub size: Option<CompanySize>,
+    pub gstin: Option<String>,
+    pub cin: Option<String>,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub pincode: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SubmitEmployerVerificationDto {
+    pub documents: Vec<BusinessDocument>,
+}
@@ -0,0 +1,37 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+pub const MAX_FAVORITE_NOTE_LENGTH: usize = 500;
+
+/// A worker a customer saved to hire again
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FavoriteWorker {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,
+    pub worker_id: ObjectId, // WorkerProfile id
+    pub note: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SaveFavoriteDto {
+    /// Private note, e.g. "fixed the kitchen sink"
+    pub note: Option<String>,
+}
+
+/// Book a favourite again. Anything left out is copied from the last booking with them.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RebookFavoriteDto {
+    /// RFC 3339
+    pub preferred_start: String,
+    pub estimated_hours: f64,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+    pub address: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
@@ -0,0 +1,86 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GstSupplyType {
+    Intrastate, // CGST + SGST
+    Interstate, // IGST
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvoiceParty {
+    pub name: String,
+    pub gstin: Option<String>,
+    pub address: Option<String>,
+    pub state: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvoiceLineItem {
+    pub description: String,
+    pub sac_code: String,
+    pub quantity: i32,
+    pub taxable_value: f64,
+}
+
+/// GST tax invoice issued for a captured payment. Stored as an immutable snapshot.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Invoice {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub invoice_number: String, // e.g. "MS/2026-27/000042"
+    pub user_id: ObjectId,
+    pub payment_id: ObjectId,
+    pub subscription_id: Option<ObjectId>,
+    pub razorpay_payment_id: Option<String>,
+    pub supplier: InvoiceParty,
+    pub customer: InvoiceParty,
+    pub place_of_supply: String,
+    pub supply_type: GstSupplyType,
+    pub items: Vec<InvoiceLineItem>,
+    pub gst_rate: f64,
+    pub taxable_value: f64,
+    pub cgst: f64,
+    pub sgst: f64,
+    pub igst: f64,
+    pub total: f64,
+    pub currency: String,
+    pub issued_at: DateTime,
+    pub emailed_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct InvoiceResponse {
+    pub id: String,
+    pub invoice_number: String,
+    pub payment_id: String,
+    pub subscription_id: Option<String>,
+    pub taxable_value: f64,
+    pub cgst: f64,
+    pub sgst: f64,
+    pub igst: f64,
+    pub total: f64,
+    pub currency: String,
+    pub issued_at: String,
+}
+
+impl From<Invoice> for InvoiceResponse {
+    fn from(invoice: Invoice) -> Self {
+        InvoiceResponse {
+            id: invoice.id.unwrap().to_hex(),
+            invoice_number: invoice.invoice_number,
+            payment_id: invoice.payment_id.to_hex(),
+            subscription_id: invoice.subscription_id.map(|id| id.to_hex()),
+            taxable_value: invoice.taxable_value,
+            cgst: invoice.cgst,
+            sgst: invoice.sgst,
+            igst: invoice.igst,
+            total: invoice.total,
+            currency: invoice.currency,
+            issued_at: invoice.issued_at.try_to_rfc3339_string().unwrap_or_default(),
+        }
+    }
+}
@@ -44,6 +44,8 @@ pub struct JobSeekerProfile {
     pub subscription_plan: JobSeekerSubscriptionPlan,
     pub subscription_expires_at: Option<DateTime>,
     pub is_verified: bool,
+    #[serde(default)]
+    pub verified_at: Option<DateTime>, // First verification, drives saved search alerts
     pub is_available: bool, // Currently looking for jobs
     
     // Metadata
@@ -53,6 +55,14 @@ pub struct JobSeekerProfile {
     pub updated_at: DateTime,
 }
 
+impl JobSeekerProfile {
+    /// Premium plan that hasn't lapsed
+    pub fn has_premium(&self) -> bool {
+        matches!(self.subscription_plan, JobSeekerSubscriptionPlan::Premium)
+            && self.subscription_expires_at.is_none_or(|expires| expires > DateTime::now())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Education {
     pub degree: String,
@@ -170,4 +180,130 @@ impl From<JobSeekerProfile> for JobSeekerProfileResponse {
             applications_count: profile.applications_count,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,  // Waiting for admin moderation
+    Approved, // Live in the public feed
+    Rejected,
+    Closed, // Closed by the employer
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Approved => "approved",
+            JobStatus::Rejected => "rejected",
+            JobStatus::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "approved" => Some(JobStatus::Approved),
+            "rejected" => Some(JobStatus::Rejected),
+            "closed" => Some(JobStatus::Closed),
+            _ => None,
+        }
+    }
+
+    /// States an admin may move a job to from this one
+    pub fn moderation_states(&self) -> &'static [JobStatus] {
+        match self {
+            JobStatus::Pending => &[JobStatus::Approved, JobStatus::Rejected],
+            JobStatus::Approved => &[JobStatus::Rejected, JobStatus::Closed],
+            JobStatus::Rejected => &[JobStatus::Approved],
+            JobStatus::Closed => &[],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobType {
+    Fulltime,
+    Parttime,
+    Contract,
+    Freelance,
+}
+
+impl JobType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobType::Fulltime => "fulltime",
+            JobType::Parttime => "parttime",
+            JobType::Contract => "contract",
+            JobType::Freelance => "freelance",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "fulltime" => Some(JobType::Fulltime),
+            "parttime" => Some(JobType::Parttime),
+            "contract" => Some(JobType::Contract),
+            "freelance" => Some(JobType::Freelance),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Job {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub title: String,
+    pub company: Option<String>, // Copied from the employer profile
+    pub description: Option<String>,
+    pub location: Option<String>, // City
+    pub job_type: Option<JobType>,
+    pub category: Option<String>,
+    pub salary_min: Option<f64>, // Monthly, INR
+    pub salary_max: Option<f64>,
+    pub requirements: Option<Vec<String>>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    pub experience_min: Option<i32>, // Years
+    pub status: JobStatus,
+    pub rejection_reason: Option<String>,
+    pub applications_count: i32,
+    pub posted_by: Option<ObjectId>, // User who posted it
+    #[serde(default)]
+    pub employer_id: Option<ObjectId>, // None on jobs that predate employer profiles
+    pub published_at: Option<DateTime>, // First approval
+    pub closed_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateJobDto {
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub job_type: JobType,
+    pub category: String,
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub requirements: Option<Vec<String>>,
+    pub skills: Option<Vec<String>>,
+    pub experience_min: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateJobDto {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub job_type: Option<JobType>,
+    pub category: Option<String>,
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub requirements: Option<Vec<String>>,
+    pub skills: Option<Vec<String>>,
+    pub experience_min: Option<i32>,
+}
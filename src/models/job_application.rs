@@ -0,0 +1,113 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+pub const MAX_COVER_LETTER_LENGTH: usize = 5000;
+pub const MAX_APPLICATION_NOTE_LENGTH: usize = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplicationStage {
+    Applied,
+    Shortlisted,
+    Interview,
+    Offered,
+    Hired,
+    Rejected,
+    Withdrawn, // By the applicant
+}
+
+impl ApplicationStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationStage::Applied => "applied",
+            ApplicationStage::Shortlisted => "shortlisted",
+            ApplicationStage::Interview => "interview",
+            ApplicationStage::Offered => "offered",
+            ApplicationStage::Hired => "hired",
+            ApplicationStage::Rejected => "rejected",
+            ApplicationStage::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "applied" => Some(ApplicationStage::Applied),
+            "shortlisted" => Some(ApplicationStage::Shortlisted),
+            "interview" => Some(ApplicationStage::Interview),
+            "offered" => Some(ApplicationStage::Offered),
+            "hired" => Some(ApplicationStage::Hired),
+            "rejected" => Some(ApplicationStage::Rejected),
+            "withdrawn" => Some(ApplicationStage::Withdrawn),
+            _ => None,
+        }
+    }
+
+    /// Stages the employer may move an application to from this one
+    pub fn next_states(&self) -> &'static [ApplicationStage] {
+        match self {
+            ApplicationStage::Applied => &[ApplicationStage::Shortlisted, ApplicationStage::Rejected],
+            ApplicationStage::Shortlisted => &[ApplicationStage::Interview, ApplicationStage::Rejected],
+            ApplicationStage::Interview => &[ApplicationStage::Offered, ApplicationStage::Rejected],
+            ApplicationStage::Offered => &[ApplicationStage::Hired, ApplicationStage::Rejected],
+            ApplicationStage::Hired | ApplicationStage::Rejected | ApplicationStage::Withdrawn => &[],
+        }
+    }
+
+    /// The applicant can pull out until the process has ended
+    pub fn can_withdraw(&self) -> bool {
+        !self.next_states().is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplicationStageEvent {
+    pub from: ApplicationStage,
+    pub to: ApplicationStage,
+    pub note: Option<String>, // Shared with the applicant
+    pub at: DateTime,
+}
+
+/// Employer-only note on an applicant
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApplicationNote {
+    pub text: String,
+    pub at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobApplication {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub job_id: ObjectId,
+    pub employer_id: ObjectId,  // User who posted the job
+    pub applicant_id: ObjectId, // User who applied
+    pub job_seeker_profile_id: ObjectId,
+    pub cover_letter: Option<String>,
+    pub resume_url: Option<String>, // Copied from the profile when applying
+    pub stage: ApplicationStage,
+    #[serde(default)]
+    pub history: Vec<ApplicationStageEvent>,
+    #[serde(default)]
+    pub employer_notes: Vec<ApplicationNote>,
+    pub applied_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApplyJobDto {
+    pub cover_letter: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateApplicationStageDto {
+    /// shortlisted, interview, offered, hired or rejected
+    pub stage: String,
+    /// Shown to the applicant
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddApplicationNoteDto {
+    pub note: String,
+}
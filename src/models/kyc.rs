@@ -21,6 +21,29 @@ pub enum KycStatusEnum {
     Rejected,
 }
 
+impl KycStatusEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatusEnum::Pending => "pending",
+            KycStatusEnum::Submitted => "submitted",
+            KycStatusEnum::UnderReview => "underreview",
+            KycStatusEnum::Approved => "approved",
+            KycStatusEnum::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(KycStatusEnum::Pending),
+            "submitted" => Some(KycStatusEnum::Submitted),
+            "underreview" | "under_review" => Some(KycStatusEnum::UnderReview),
+            "approved" => Some(KycStatusEnum::Approved),
+            "rejected" => Some(KycStatusEnum::Rejected),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Kyc {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -7,6 +7,22 @@ pub mod category;
 pub mod subscription;
 pub mod review;
 pub mod service;
+pub mod payment;
+pub mod invoice;
+pub mod coupon;
+pub mod booking;
+pub mod availability;
+pub mod quote;
+pub mod coverage;
+pub mod worker_stats;
+pub mod notification;
+pub mod tracking;
+pub mod favorite;
+pub mod badge;
+pub mod job_application;
+pub mod saved_search;
+pub mod profile_view;
+pub mod employer;
 
 pub use user::*;
 pub use otp::*;
@@ -16,4 +32,20 @@ pub use job::*;
 pub use category::*;
 pub use subscription::*;
 pub use review::*;
-pub use service::*;
\ No newline at end of file
+pub use service::*;
+pub use payment::*;
+pub use invoice::*;
+pub use coupon::*;
+pub use booking::*;
+pub use availability::*;
+pub use quote::*;
+pub use coverage::*;
+pub use worker_stats::*;
+pub use notification::*;
+pub use tracking::*;
+pub use favorite::*;
+pub use badge::*;
+pub use job_application::*;
+pub use saved_search::*;
+pub use profile_view::*;
+pub use employer::*;
\ No newline at end of file
@@ -0,0 +1,16 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+/// In-app notification, also sent by email when the user has one on file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notification {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub kind: String, // e.g. "onboarding", "booking"
+    pub title: String,
+    pub message: String,
+    pub data: Option<serde_json::Value>,
+    pub read_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
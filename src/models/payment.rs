@@ -0,0 +1,83 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Created, // Razorpay order created, not paid yet
+    Captured,
+    Failed,
+    PartiallyRefunded,
+    Refunded,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentKind {
+    #[default]
+    Purchase, // First payment for a subscription
+    Renewal,  // Auto-renew charge
+    Upgrade,  // Prorated plan upgrade
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RefundStatus {
+    Pending,
+    Processed,
+    Failed,
+}
+
+impl RefundStatus {
+    pub fn from_razorpay(status: &str) -> Self {
+        match status {
+            "processed" => RefundStatus::Processed,
+            "failed" => RefundStatus::Failed,
+            _ => RefundStatus::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Refund {
+    pub refund_id: String, // Razorpay refund id (rfnd_...)
+    pub amount: f64,
+    pub status: RefundStatus,
+    pub reason: Option<String>,
+    pub initiated_by: Option<ObjectId>, // Admin user id when known, None for self-service cancellations
+    pub created_at: DateTime,
+    pub processed_at: Option<DateTime>,
+}
+
+/// Payments ledger, one entry per Razorpay order
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Payment {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub subscription_id: Option<ObjectId>,
+    #[serde(default)]
+    pub kind: PaymentKind,
+    pub razorpay_order_id: String,
+    pub razorpay_payment_id: Option<String>,
+    pub amount: f64,
+    pub currency: String,
+    pub status: PaymentStatus,
+    #[serde(default)]
+    pub refunds: Vec<Refund>,
+    #[serde(default)]
+    pub refunded_amount: f64, // Sum of refunds that have not failed, the guard against over-refunding
+    pub captured_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateRefundDto {
+    /// Amount in INR, defaults to the full unrefunded amount
+    pub amount: Option<f64>,
+    pub reason: Option<String>,
+    /// Cancel the linked subscription once the refund is issued
+    pub cancel_subscription: Option<bool>,
+}
@@ -0,0 +1,34 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileKind {
+    JobSeeker,
+    Worker,
+}
+
+impl ProfileKind {
+    /// Collection holding the profile and its `profile_views` counter
+    pub fn collection(&self) -> &'static str {
+        match self {
+            ProfileKind::JobSeeker => "job_seeker_profiles",
+            ProfileKind::Worker => "worker_profiles",
+        }
+    }
+}
+
+/// One counted view of a public profile, at most one per viewer per IST day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileView {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub profile_kind: ProfileKind,
+    pub profile_id: ObjectId,
+    pub owner_id: ObjectId, // User who owns the profile
+    pub viewer_id: Option<ObjectId>, // None for anonymous visitors
+    pub viewer_key: String, // "user:<id>" or "ip:<hash>", the dedupe key
+    pub date: String, // "YYYY-MM-DD" in IST
+    pub viewed_at: DateTime,
+}
@@ -0,0 +1,163 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use super::{BookingParty, GeoLocation};
+
+pub const MAX_QUOTE_PHOTOS: usize = 10;
+pub const MAX_LINE_ITEMS: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    Requested, // Waiting for the worker's first quote
+    Quoted,    // Worker sent a quote, customer to answer
+    Countered, // Customer counter-offered, worker to answer
+    Accepted,
+    Rejected,
+    Expired,
+}
+
+impl QuoteStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteStatus::Requested => "requested",
+            QuoteStatus::Quoted => "quoted",
+            QuoteStatus::Countered => "countered",
+            QuoteStatus::Accepted => "accepted",
+            QuoteStatus::Rejected => "rejected",
+            QuoteStatus::Expired => "expired",
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self, QuoteStatus::Requested | QuoteStatus::Quoted | QuoteStatus::Countered)
+    }
+
+    /// Who has to act next while the negotiation is open
+    pub fn waiting_on(&self) -> Option<BookingParty> {
+        match self {
+            QuoteStatus::Requested | QuoteStatus::Countered => Some(BookingParty::Worker),
+            QuoteStatus::Quoted => Some(BookingParty::Customer),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LineItemKind {
+    Labour,
+    Materials,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct QuoteLineItem {
+    pub kind: LineItemKind,
+    pub description: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub amount: f64,
+}
+
+/// One offer in the negotiation. Revisions are append-only.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuoteRevision {
+    pub revision: i32,
+    pub author: BookingParty,
+    pub line_items: Vec<QuoteLineItem>,
+    pub subtotal: f64,
+    pub tax_rate: f64, // Percent
+    pub tax_amount: f64,
+    pub total: f64,
+    pub estimated_hours: f64,
+    pub valid_until: Option<DateTime>,
+    pub message: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Quote {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,
+    pub worker_id: ObjectId,      // WorkerProfile id
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub description: String,
+    pub photos: Vec<String>,
+    pub address: String,
+    pub location: GeoLocation,
+    pub preferred_start: DateTime,
+    pub status: QuoteStatus,
+    pub revisions: Vec<QuoteRevision>,
+    pub rejected_by: Option<BookingParty>,
+    pub rejection_reason: Option<String>,
+    pub booking_id: Option<ObjectId>, // Set once accepted
+    pub accepted_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Quote {
+    pub fn latest(&self) -> Option<&QuoteRevision> {
+        self.revisions.last()
+    }
+
+    /// Expiry of the worker's most recent offer
+    pub fn valid_until(&self) -> Option<DateTime> {
+        self.revisions
+            .iter()
+            .rev()
+            .find(|r| r.author == BookingParty::Worker)
+            .and_then(|r| r.valid_until)
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RequestQuoteDto {
+    pub worker_id: String,
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub description: String,
+    /// URLs returned by `/upload/image`
+    pub photos: Option<Vec<String>>,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// RFC 3339
+    pub preferred_start: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LineItemDto {
+    pub kind: LineItemKind,
+    pub description: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SubmitQuoteDto {
+    pub line_items: Vec<LineItemDto>,
+    /// GST percent applied to the subtotal
+    pub tax_rate: f64,
+    pub estimated_hours: f64,
+    /// How many days the quote stays open, 1 to 30. Defaults to 7.
+    pub valid_days: Option<i64>,
+    pub message: Option<String>,
+}
+
+/// Customer's counter-offer: their version of the line items.
+/// Tax rate, hours and validity carry over from the worker's last quote.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CounterQuoteDto {
+    pub line_items: Vec<LineItemDto>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RejectQuoteDto {
+    pub reason: Option<String>,
+}
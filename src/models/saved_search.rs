@@ -0,0 +1,89 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+pub const MAX_SAVED_SEARCHES: u64 = 20;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedSearchKind {
+    Jobs,       // Job seekers watching the job feed
+    JobSeekers, // Employers watching for candidates
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertFrequency {
+    Instant, // Within minutes of a match
+    Daily,
+}
+
+/// Same filters as `/job/search`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+pub struct JobSearchCriteria {
+    pub q: Option<String>,
+    pub category: Option<String>,
+    pub job_type: Option<String>,
+    pub location: Option<String>,
+    pub min_salary: Option<f64>,
+    pub max_salary: Option<f64>,
+}
+
+/// Same filters as `/job-seeker/search`
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+pub struct JobSeekerSearchCriteria {
+    pub skills: Option<String>, // Comma-separated
+    pub category: Option<String>,
+    pub min_experience: Option<i32>,
+    pub max_experience: Option<i32>,
+    pub location: Option<String>,
+    pub job_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedSearch {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub name: String,
+    pub kind: SavedSearchKind,
+    pub job_criteria: Option<JobSearchCriteria>,
+    pub seeker_criteria: Option<JobSeekerSearchCriteria>,
+    pub frequency: AlertFrequency,
+    pub email_alerts: bool,
+    pub push_alerts: bool,
+    pub is_active: bool, // False once unsubscribed
+    pub unsubscribe_token: String, // Secret used by the email unsubscribe link
+    pub last_checked_at: DateTime, // Matches after this are new
+    pub last_alerted_at: Option<DateTime>,
+    #[serde(default)]
+    pub lease_until: Option<DateTime>, // Held by the alert run checking this search
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateSavedSearchDto {
+    pub name: String,
+    pub kind: SavedSearchKind,
+    /// Required for `jobs` searches
+    pub job_criteria: Option<JobSearchCriteria>,
+    /// Required for `job_seekers` searches
+    pub seeker_criteria: Option<JobSeekerSearchCriteria>,
+    /// Defaults to daily
+    pub frequency: Option<AlertFrequency>,
+    pub email_alerts: Option<bool>,
+    pub push_alerts: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateSavedSearchDto {
+    pub name: Option<String>,
+    pub job_criteria: Option<JobSearchCriteria>,
+    pub seeker_criteria: Option<JobSeekerSearchCriteria>,
+    pub frequency: Option<AlertFrequency>,
+    pub email_alerts: Option<bool>,
+    pub push_alerts: Option<bool>,
+    /// Turn alerts back on after unsubscribing, or pause them
+    pub is_active: Option<bool>,
+}
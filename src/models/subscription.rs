@@ -2,21 +2,51 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SubscriptionType {
     Worker,
     JobSeeker,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SubscriptionStatus {
+    Pending, // Order created, waiting for payment
     Active,
     Expired,
     Cancelled,
 }
 
+/// State of the Razorpay recurring subscription backing auto-renew
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RenewalStatus {
+    AwaitingAuthorization, // Mandate link sent, user has not approved it yet
+    Active,
+    Retrying, // Last renewal charge failed, Razorpay is retrying
+    Halted,   // Retries exhausted, plan falls back to expiry
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanChangeKind {
+    Upgrade,   // Paid now, applied as soon as the payment is verified
+    Downgrade, // Applied at the end of the current period
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingPlanChange {
+    pub plan_name: String,
+    pub kind: PlanChangeKind,
+    pub price: f64,      // Full price of the new plan
+    pub credit: f64,     // Unused value of the current period
+    pub amount_due: f64, // Charged for an upgrade, 0 for a downgrade
+    pub razorpay_order_id: Option<String>,
+    pub effective_at: DateTime,
+    pub requested_at: DateTime,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Subscription {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -30,6 +60,109 @@ pub struct Subscription {
     pub expires_at: DateTime,
     pub auto_renew: bool,
     pub payment_id: Option<String>,
+    #[serde(default)]
+    pub cancel_at_period_end: bool,
+    pub cancelled_at: Option<DateTime>,
+    pub cancellation_reason: Option<String>,
+    pub razorpay_subscription_id: Option<String>,
+    pub renewal_status: Option<RenewalStatus>,
+    #[serde(default)]
+    pub renewal_attempts: i32,
+    pub pending_change: Option<PendingPlanChange>,
+    pub coupon_code: Option<String>,
+    #[serde(default)]
+    pub discount: f64, // Coupon discount taken off the plan price, `price` is what was charged
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefundPolicy {
+    NonRefundable,
+    Prorated, // Unused days are refunded on immediate cancellation
+}
+
+/// Static plan catalog shared by the worker and job seeker subscription flows
+#[derive(Debug, Clone, Copy)]
+pub struct PlanDetails {
+    pub name: &'static str,
+    pub price: f64,
+    pub duration_days: i64,
+    pub refund_policy: RefundPolicy,
+}
+
+pub const WORKER_PLANS: &[PlanDetails] = &[
+    PlanDetails { name: "silver", price: 1.0, duration_days: 365, refund_policy: RefundPolicy::Prorated },
+    PlanDetails { name: "gold", price: 2.0, duration_days: 365, refund_policy: RefundPolicy::Prorated },
+];
+
+pub const JOB_SEEKER_PLANS: &[PlanDetails] = &[
+    PlanDetails { name: "basic", price: 0.5, duration_days: 365, refund_policy: RefundPolicy::NonRefundable },
+    PlanDetails { name: "premium", price: 1.5, duration_days: 365, refund_policy: RefundPolicy::Prorated },
+];
+
+impl PlanDetails {
+    /// Razorpay plan period and interval matching the plan duration
+    pub fn gateway_period(&self) -> (&'static str, i64) {
+        if self.duration_days % 365 == 0 {
+            ("yearly", self.duration_days / 365)
+        } else if self.duration_days % 30 == 0 {
+            ("monthly", self.duration_days / 30)
+        } else if self.duration_days % 7 == 0 {
+            ("weekly", self.duration_days / 7)
+        } else {
+            ("daily", self.duration_days)
+        }
+    }
+}
+
+impl SubscriptionType {
+    pub fn plans(&self) -> &'static [PlanDetails] {
+        match self {
+            SubscriptionType::Worker => WORKER_PLANS,
+            SubscriptionType::JobSeeker => JOB_SEEKER_PLANS,
+        }
+    }
+
+    pub fn find_plan(&self, plan_name: &str) -> Option<&'static PlanDetails> {
+        let plan_name = plan_name.to_lowercase();
+        self.plans().iter().find(|p| p.name == plan_name)
+    }
+
+    /// Collection holding the profile whose plan follows this subscription
+    pub fn profile_collection(&self) -> &'static str {
+        match self {
+            SubscriptionType::Worker => "worker_profiles",
+            SubscriptionType::JobSeeker => "job_seeker_profiles",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelSubscriptionDto {
+    pub subscription_id: String,
+    /// `false` stops the plan at the end of the current period,
+    /// `true` ends it now and refunds unused days where the plan allows it
+    pub immediate: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateAutoRenewDto {
+    pub subscription_id: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ChangePlanDto {
+    pub subscription_id: String,
+    pub plan_name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct VerifyPlanChangeDto {
+    pub subscription_id: String,
+    pub razorpay_order_id: String,
+    pub razorpay_payment_id: String,
+    pub razorpay_signature: String,
+}
@@ -0,0 +1,34 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// One position reported by the worker during a booking. Kept as the trail.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocationPoint {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub booking_id: ObjectId,
+    pub worker_user_id: ObjectId,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy_m: Option<f64>,
+    pub recorded_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LocationUpdateDto {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Reported GPS accuracy in metres
+    pub accuracy_m: Option<f64>,
+}
+
+/// What the customer's stream receives for each position
+#[derive(Debug, Serialize, Clone, JsonSchema)]
+pub struct LiveLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub distance_km: f64,
+    pub eta_minutes: i64,
+    pub recorded_at: String, // RFC 3339
+}
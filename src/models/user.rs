@@ -30,6 +30,8 @@ pub struct User {
     pub pincode: Option<String>,
     pub kyc_status: KycStatus,
     pub is_active: bool,
+    #[serde(default)]
+    pub is_admin: bool, // Granted directly in the database
     pub fcm_token: Option<FcmToken>,
     pub last_login_at: DateTime,
     pub created_at: DateTime,
@@ -1,14 +1,19 @@
 use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars::JsonSchema;
+use super::{GeoMultiPolygon, ServiceCoverage, WorkerBadge, WorkerSchedule};
 
 #[derive(Debug, FromForm, Deserialize, JsonSchema)]
 pub struct NearbyWorkerQuery {
     pub latitude: f64,
     pub longitude: f64,
+    /// Search radius, defaults to 10 km and is capped at 50 km
+    pub radius_km: Option<f64>,
 
     pub category: Option<String>,
     pub subcategory: Option<String>,
+    pub min_rating: Option<f64>,
+    pub max_rate: Option<f64>,
 
     pub page: Option<i64>,
     pub limit: Option<i64>,
@@ -22,6 +27,170 @@ pub enum WorkerSubscriptionPlan {
     Gold,
 }
 
+impl WorkerSubscriptionPlan {
+    /// How far from the customer the worker is shown in nearby search
+    pub fn search_reach_km(&self) -> f64 {
+        match self {
+            WorkerSubscriptionPlan::None => 10.0,
+            WorkerSubscriptionPlan::Silver => 25.0,
+            WorkerSubscriptionPlan::Gold => 50.0,
+        }
+    }
+
+    /// Ranking weight in search results, Gold first
+    pub fn rank(&self) -> i32 {
+        match self {
+            WorkerSubscriptionPlan::None => 0,
+            WorkerSubscriptionPlan::Silver => 1,
+            WorkerSubscriptionPlan::Gold => 2,
+        }
+    }
+
+    /// How far back the worker dashboard goes
+    pub fn dashboard_history_days(&self) -> i64 {
+        match self {
+            WorkerSubscriptionPlan::None => 30,
+            WorkerSubscriptionPlan::Silver => 90,
+            WorkerSubscriptionPlan::Gold => 730,
+        }
+    }
+
+    /// Gold workers see who viewed their profile; others see counts only
+    pub fn shows_profile_viewers(&self) -> bool {
+        matches!(self, WorkerSubscriptionPlan::Gold)
+    }
+
+    /// How many portfolio images the plan allows
+    pub fn portfolio_limit(&self) -> usize {
+        match self {
+            WorkerSubscriptionPlan::None => 5,
+            WorkerSubscriptionPlan::Silver => 15,
+            WorkerSubscriptionPlan::Gold => 50,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStatus {
+    Draft,
+    Submitted,
+    UnderReview,
+    Verified,
+    Rejected,
+    Suspended,
+}
+
+impl OnboardingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OnboardingStatus::Draft => "draft",
+            OnboardingStatus::Submitted => "submitted",
+            OnboardingStatus::UnderReview => "under_review",
+            OnboardingStatus::Verified => "verified",
+            OnboardingStatus::Rejected => "rejected",
+            OnboardingStatus::Suspended => "suspended",
+        }
+    }
+
+    /// States an admin may move a profile to from this one
+    pub fn admin_next_states(&self) -> &'static [OnboardingStatus] {
+        match self {
+            OnboardingStatus::Submitted => &[OnboardingStatus::UnderReview, OnboardingStatus::Verified, OnboardingStatus::Rejected],
+            OnboardingStatus::UnderReview => &[OnboardingStatus::Verified, OnboardingStatus::Rejected],
+            OnboardingStatus::Verified => &[OnboardingStatus::Suspended],
+            OnboardingStatus::Suspended => &[OnboardingStatus::Verified],
+            OnboardingStatus::Draft | OnboardingStatus::Rejected => &[],
+        }
+    }
+
+    /// Whether the worker can send the profile for review from this state
+    pub fn can_submit(&self) -> bool {
+        matches!(self, OnboardingStatus::Draft | OnboardingStatus::Rejected)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OnboardingEvent {
+    pub from: OnboardingStatus,
+    pub to: OnboardingStatus,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+    pub note: Option<String>,
+    pub by_admin: bool,
+    pub at: DateTime,
+}
+
+/// What a profile needs before it can be verified
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct OnboardingChecklist {
+    pub kyc_approved: bool,
+    pub location_set: bool,
+    pub has_category: bool,
+    pub license_required: bool,
+    pub license_provided: bool,
+    pub profile_photo: bool,
+}
+
+impl OnboardingChecklist {
+    pub fn is_complete(&self) -> bool {
+        self.kyc_approved
+            && self.location_set
+            && self.has_category
+            && (!self.license_required || self.license_provided)
+            && self.profile_photo
+    }
+
+    /// Human readable list of what is still missing
+    pub fn missing(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !self.kyc_approved {
+            missing.push("KYC approval");
+        }
+        if !self.location_set {
+            missing.push("work location");
+        }
+        if !self.has_category {
+            missing.push("at least one category");
+        }
+        if self.license_required && !self.license_provided {
+            missing.push("trade license number and document");
+        }
+        if !self.profile_photo {
+            missing.push("profile photo");
+        }
+        missing
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateOnboardingStatusDto {
+    pub status: OnboardingStatus,
+    /// Required when rejecting or suspending
+    pub reasons: Option<Vec<String>>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PortfolioStage {
+    Before,
+    After,
+}
+
+/// A photo of past work. Order in `WorkerProfile::portfolio` is display order.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortfolioItem {
+    pub id: ObjectId,
+    pub image_url: String,
+    pub caption: Option<String>,
+    pub category: String,
+    pub subcategory: Option<String>,
+    pub stage: Option<PortfolioStage>,
+    pub paired_with: Option<ObjectId>, // The other half of a before/after pair
+    pub created_at: DateTime,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateLocationDto {
     pub latitude: f64,
@@ -35,6 +204,17 @@ pub struct GeoLocation {
     pub coordinates: [f64; 2], // [longitude, latitude]
 }
 
+impl GeoLocation {
+    /// Great-circle distance to a point, in km
+    pub fn distance_km(&self, latitude: f64, longitude: f64) -> f64 {
+        let (lng1, lat1) = (self.coordinates[0].to_radians(), self.coordinates[1].to_radians());
+        let (lng2, lat2) = (longitude.to_radians(), latitude.to_radians());
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lng2 - lng1) / 2.0).sin().powi(2);
+        2.0 * 6371.0 * a.sqrt().asin()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkerProfile {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -46,14 +226,34 @@ pub struct WorkerProfile {
     pub description: Option<String>,
     pub hourly_rate: Option<f64>,
     pub license_number: Option<String>,
+    pub license_document: Option<String>, // Uploaded through /upload/document
     pub service_areas: Vec<String>,
+    pub coverage: Option<ServiceCoverage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage_area: Option<GeoMultiPolygon>, // Derived from `coverage`, queried with `$geoIntersects`
     pub subscription_plan: WorkerSubscriptionPlan,
     pub subscription_expires_at: Option<DateTime>,
-    pub is_verified: bool,
-    pub is_available: bool,
+    pub is_verified: bool, // True exactly when onboarding is verified
+    pub onboarding_status: Option<OnboardingStatus>, // None on profiles that predate onboarding
+    #[serde(default)]
+    pub onboarding_history: Vec<OnboardingEvent>,
+    #[serde(default)]
+    pub rejection_reasons: Vec<String>,
+    pub location_set_at: Option<DateTime>,
+    pub is_available: bool, // Effective availability, follows `schedule` when one is set
+    pub accepting_bookings: Option<bool>, // Manual switch, None on profiles that predate schedules
+    pub schedule: Option<WorkerSchedule>,
+    #[serde(default)]
+    pub portfolio: Vec<PortfolioItem>,
     pub rating: f64,
     pub total_reviews: i32,
     pub total_jobs_completed: i32,
+    #[serde(default)]
+    pub favorite_count: i64, // Customers who saved this worker
+    #[serde(default)]
+    pub profile_views: i64, // Unique viewers per day, from `profile_views` events
+    #[serde(default)]
+    pub badges: Vec<WorkerBadge>, // Kept in step with the badge rules by the badge engine
     pub created_at: DateTime,
     pub updated_at: DateTime,
     pub location: GeoLocation,
@@ -67,6 +267,7 @@ pub struct CreateWorkerProfileDto {
     pub description: Option<String>,
     pub hourly_rate: Option<f64>,
     pub license_number: Option<String>,
+    pub license_document: Option<String>,
     pub longitude: Option<f64>,
     pub latitude: Option<f64>,
     pub service_areas: Vec<String>,
@@ -80,5 +281,54 @@ pub struct UpdateWorkerProfileDto {
     pub description: Option<String>,
     pub hourly_rate: Option<f64>,
     pub service_areas: Option<Vec<String>>,
+    pub license_number: Option<String>,
+    pub license_document: Option<String>,
     pub is_available: Option<bool>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddPortfolioItemDto {
+    /// URL returned by `/upload/image`
+    pub image_url: String,
+    pub caption: Option<String>,
+    pub category: String,
+    pub subcategory: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdatePortfolioItemDto {
+    pub caption: Option<String>,
+    pub category: Option<String>,
+    pub subcategory: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReorderPortfolioDto {
+    /// Every portfolio item id, in the new display order
+    pub item_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PairPortfolioDto {
+    pub before_id: String,
+    pub after_id: String,
+}
+
+impl WorkerProfile {
+    pub fn onboarding(&self) -> OnboardingStatus {
+        self.onboarding_status.unwrap_or(if self.is_verified {
+            OnboardingStatus::Verified
+        } else {
+            OnboardingStatus::Draft
+        })
+    }
+
+    /// Availability implied by the manual switch and the schedule at `at`
+    pub fn scheduled_availability(&self, at: DateTime) -> bool {
+        let accepting = self.accepting_bookings.unwrap_or(self.is_available);
+        match self.schedule {
+            Some(ref schedule) => accepting && schedule.is_working_at(at),
+            None => accepting,
+        }
+    }
+}
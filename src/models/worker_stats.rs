@@ -0,0 +1,16 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+/// Per-day counters for a worker profile, one document per worker per IST day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkerDailyStats {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub worker_id: ObjectId, // WorkerProfile id
+    pub date: String,        // "YYYY-MM-DD" in IST
+    #[serde(default)]
+    pub profile_views: i64,
+    #[serde(default)]
+    pub search_impressions: i64,
+    pub updated_at: DateTime,
+}
@@ -1,6 +1,12 @@
 use crate::db::DbConn;
-use crate::models::{CategoryResponse, MainCategory, SubCategory, SubCategoryResponse, WorkerProfile, JobSeekerProfile};
-use crate::utils::{ApiError, ApiResponse};
+use crate::guards::AdminGuard;
+use crate::models::{CategoryResponse, MainCategory, SubCategory, SubCategoryResponse, WorkerProfile, JobSeekerProfile, Subscription, Payment, CreateRefundDto, Coupon, CouponRedemption, CreateCouponDto, UpdateCouponDto, DiscountType, OnboardingStatus, UpdateOnboardingStatusDto, BadgeAward, BadgeCondition, BadgeRule, CreateBadgeRuleDto, UpdateBadgeRuleDto, Job, JobStatus, Employer, KycStatusEnum};
+use crate::routes::badge::evaluate_badges;
+use crate::routes::notification::notify;
+use crate::routes::onboarding::{onboarding_checklist, transition_onboarding};
+use crate::routes::subscription::{end_subscription, issue_refund, sync_refund};
+use crate::services::payment_gateway;
+use crate::utils::{ApiError, ApiResponse, is_duplicate_key};
 use mongodb::bson::{doc, DateTime, oid::ObjectId};
 use mongodb::options::FindOptions;
 use rocket::State;
@@ -156,6 +162,9 @@ pub async fn get_all_workers(
     if let Some(is_verified) = query.is_verified {
         filter.insert("is_verified", is_verified);
     }
+    if let Some(ref status) = query.status {
+        filter.insert("onboarding_status", status);
+    }
 
     let find_options = FindOptions::builder()
         .skip(skip as u64)
@@ -194,8 +203,103 @@ pub async fn get_all_workers(
 #[derive(serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct UpdateWorkerVerificationDto {
     pub is_verified: bool,
+    /// Required when revoking
+    pub reason: Option<String>,
+}
+
+async fn find_worker_profile(db: &DbConn, worker_id: &str) -> Result<WorkerProfile, ApiError> {
+    let object_id = ObjectId::parse_str(worker_id)
+        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))
+}
+
+/// Validate and apply an admin onboarding decision
+async fn review_worker(
+    db: &DbConn,
+    worker: &WorkerProfile,
+    to: OnboardingStatus,
+    reasons: Vec<String>,
+    note: Option<String>,
+) -> Result<(), ApiError> {
+    let from = worker.onboarding();
+    if !from.admin_next_states().contains(&to) {
+        return Err(ApiError::bad_request(format!(
+            "Cannot move a {} profile to {}",
+            from.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let reasons: Vec<String> = reasons
+        .into_iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    if matches!(to, OnboardingStatus::Rejected | OnboardingStatus::Suspended) && reasons.is_empty() {
+        return Err(ApiError::bad_request("Give at least one reason"));
+    }
+
+    if to == OnboardingStatus::Verified {
+        let (checklist, _) = onboarding_checklist(db, worker).await?;
+        if !checklist.is_complete() {
+            return Err(ApiError::bad_request(format!(
+                "Checklist incomplete. Missing: {}",
+                checklist.missing().join(", ")
+            )));
+        }
+    }
+
+    transition_onboarding(db, worker, to, reasons, note, true).await
+}
+
+#[openapi(tag = "Admin - Workers")]
+#[put("/admin/workers/<worker_id>/onboarding", data = "<dto>")]
+pub async fn update_worker_onboarding(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    worker_id: String,
+    dto: Json<UpdateOnboardingStatusDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_worker_profile(db, &worker_id).await?;
+    let dto = dto.into_inner();
+
+    review_worker(db, &worker, dto.status, dto.reasons.unwrap_or_default(), dto.note).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": format!("Worker moved to {}", dto.status.as_str()),
+        "status": dto.status
+    }))))
 }
 
+#[openapi(tag = "Admin - Workers")]
+#[get("/admin/workers/<worker_id>/onboarding")]
+pub async fn get_worker_onboarding(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    worker_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_worker_profile(db, &worker_id).await?;
+    let (checklist, licensed) = onboarding_checklist(db, &worker).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "status": worker.onboarding(),
+        "checklist": checklist,
+        "complete": checklist.is_complete(),
+        "missing": checklist.missing(),
+        "licensed_categories": licensed,
+        "license_number": worker.license_number,
+        "license_document": worker.license_document,
+        "rejection_reasons": worker.rejection_reasons,
+        "history": worker.onboarding_history
+    }))))
+}
+
+/// Shortcut kept for existing admin tools: verify, or revoke by suspending
 #[openapi(tag = "Admin - Workers")]
 #[put("/admin/workers/<worker_id>/verify", data = "<dto>")]
 pub async fn verify_worker(
@@ -203,20 +307,19 @@ pub async fn verify_worker(
     worker_id: String,
     dto: Json<UpdateWorkerVerificationDto>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    let object_id = ObjectId::parse_str(&worker_id)
-        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+    let worker = find_worker_profile(db, &worker_id).await?;
+    let reasons = dto.reason.clone().into_iter().collect();
 
-    db.collection::<WorkerProfile>("worker_profiles")
-        .update_one(
-            doc! { "_id": object_id },
-            doc! { "$set": { "is_verified": dto.is_verified, "updated_at": DateTime::now() } },
-            None
-        )
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Failed to update worker: {}", e)))?;
+    let to = match (dto.is_verified, worker.onboarding()) {
+        (true, _) => OnboardingStatus::Verified,
+        (false, OnboardingStatus::Verified) => OnboardingStatus::Suspended,
+        (false, _) => OnboardingStatus::Rejected,
+    };
+    review_worker(db, &worker, to, reasons, None).await?;
 
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "message": if dto.is_verified { "Worker verified successfully" } else { "Worker verification revoked" }
+        "message": if dto.is_verified { "Worker verified successfully" } else { "Worker verification revoked" },
+        "status": to
     }))))
 }
 
@@ -303,6 +406,18 @@ pub async fn verify_job_seeker(
         .await
         .map_err(|e| ApiError::internal_error(format!("Failed to update job seeker: {}", e)))?;
 
+    // Keep the first verification time so employer alerts only fire once per profile
+    if dto.is_verified {
+        db.collection::<JobSeekerProfile>("job_seeker_profiles")
+            .update_one(
+                doc! { "_id": object_id, "verified_at": null },
+                doc! { "$set": { "verified_at": DateTime::now() } },
+                None
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update job seeker: {}", e)))?;
+    }
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": if dto.is_verified { "Job seeker verified successfully" } else { "Job seeker verification revoked" }
     }))))
@@ -317,6 +432,7 @@ pub struct CreateCategoryDto {
     pub icon: Option<String>,
     pub order: Option<i32>,
     pub is_active: Option<bool>,
+    pub requires_license: Option<bool>,
 }
 
 #[openapi(tag = "Admin - Categories")]
@@ -332,6 +448,7 @@ pub async fn create_category(
         icon: dto.icon.clone(),
         is_active: dto.is_active.unwrap_or(true),
         order: dto.order.unwrap_or(0),
+        requires_license: dto.requires_license.unwrap_or(false),
         created_at: DateTime::now(),
         updated_at: DateTime::now(),
     };
@@ -376,6 +493,9 @@ pub async fn update_category(
     if let Some(is_active) = dto.is_active {
         update_doc.insert("is_active", is_active);
     }
+    if let Some(requires_license) = dto.requires_license {
+        update_doc.insert("requires_license", requires_license);
+    }
 
     db.collection::<MainCategory>("main_categories")
         .update_one(
@@ -424,6 +544,7 @@ pub struct CreateSubcategoryDto {
     pub description: Option<String>,
     pub order: Option<i32>,
     pub is_active: Option<bool>,
+    pub requires_license: Option<bool>,
 }
 
 #[openapi(tag = "Admin - Categories")]
@@ -451,6 +572,7 @@ pub async fn create_subcategory(
                 icon: None,
                 is_active: true,
                 order: 0,
+                requires_license: false,
                 created_at: DateTime::now(),
                 updated_at: DateTime::now(),
             };
@@ -472,6 +594,7 @@ pub async fn create_subcategory(
         description: dto.description.clone(),
         is_active: dto.is_active.unwrap_or(true),
         order: dto.order.unwrap_or(0),
+        requires_license: dto.requires_license.unwrap_or(false),
         created_at: DateTime::now(),
         updated_at: DateTime::now(),
     };
@@ -513,6 +636,9 @@ pub async fn update_subcategory(
     if let Some(is_active) = dto.is_active {
         update_doc.insert("is_active", is_active);
     }
+    if let Some(requires_license) = dto.requires_license {
+        update_doc.insert("requires_license", requires_license);
+    }
 
     db.collection::<SubCategory>("sub_categories")
         .update_one(
@@ -549,27 +675,6 @@ pub async fn delete_subcategory(
 
 // ==================== JOBS ADMIN ROUTES ====================
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct Job {
-    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
-    pub id: Option<ObjectId>,
-    pub title: String,
-    pub company: Option<String>,
-    pub description: Option<String>,
-    pub location: Option<String>,
-    pub job_type: Option<String>,
-    pub category: Option<String>,
-    pub salary_min: Option<f64>,
-    pub salary_max: Option<f64>,
-    pub requirements: Option<Vec<String>>,
-    pub status: String,
-    pub rejection_reason: Option<String>,
-    pub applications_count: i32,
-    pub posted_by: Option<ObjectId>,
-    pub created_at: DateTime,
-    pub updated_at: DateTime,
-}
-
 #[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct JobListQuery {
     pub status: Option<String>,
@@ -632,6 +737,7 @@ pub struct UpdateJobStatusDto {
     pub rejection_reason: Option<String>,
 }
 
+/// Moderate a job post. Pending posts go live when approved.
 #[openapi(tag = "Admin - Jobs")]
 #[put("/admin/jobs/<job_id>/status", data = "<dto>")]
 pub async fn update_job_status(
@@ -641,27 +747,77 @@ pub async fn update_job_status(
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     let object_id = ObjectId::parse_str(&job_id)
         .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+    let to = JobStatus::parse(&dto.status)
+        .ok_or_else(|| ApiError::bad_request("Status must be approved, rejected or closed"))?;
 
+    let job = db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+
+    if !job.status.moderation_states().contains(&to) {
+        return Err(ApiError::bad_request(format!(
+            "Cannot move a {} job to {}",
+            job.status.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let reason = dto.rejection_reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if to == JobStatus::Rejected && reason.is_none() {
+        return Err(ApiError::bad_request("A rejection reason is required"));
+    }
+
+    let now = DateTime::now();
     let mut update_doc = doc! {
-        "status": &dto.status,
-        "updated_at": DateTime::now(),
+        "status": to.as_str(),
+        "rejection_reason": reason,
+        "updated_at": now,
     };
-
-    if let Some(ref reason) = dto.rejection_reason {
-        update_doc.insert("rejection_reason", reason);
+    match to {
+        JobStatus::Approved if job.published_at.is_none() => {
+            update_doc.insert("published_at", now);
+        }
+        JobStatus::Closed => {
+            update_doc.insert("closed_at", now);
+        }
+        _ => {}
     }
 
-    db.collection::<Job>("jobs")
+    let result = db.collection::<Job>("jobs")
         .update_one(
-            doc! { "_id": object_id },
+            doc! { "_id": object_id, "status": job.status.as_str() },
             doc! { "$set": update_doc },
             None
         )
         .await
         .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?;
 
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else, please refresh"));
+    }
+
+    if let Some(posted_by) = job.posted_by {
+        let message = match to {
+            JobStatus::Approved => format!("\"{}\" is now live on the job board.", job.title),
+            JobStatus::Rejected => format!("\"{}\" was not approved: {}", job.title, reason.unwrap_or_default()),
+            _ => format!("\"{}\" was closed by an administrator.", job.title),
+        };
+        notify(
+            db,
+            posted_by,
+            "job_moderation",
+            &format!("Job post {}", to.as_str()),
+            &message,
+            Some(serde_json::json!({ "job_id": job_id, "status": to })),
+        )
+        .await;
+    }
+
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "message": format!("Job status updated to {}", dto.status)
+        "message": format!("Job status updated to {}", to.as_str()),
+        "status": to
     }))))
 }
 
@@ -682,4 +838,732 @@ pub async fn delete_job(
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Job deleted successfully"
     }))))
-}
\ No newline at end of file
+}
+// ==================== EMPLOYERS ADMIN ROUTES ====================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct EmployerListQuery {
+    pub status: Option<String>, // Verification status
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Admin - Employers")]
+#[get("/admin/employers?<query..>")]
+pub async fn get_all_employers(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: EmployerListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(ref status) = query.status {
+        let status = KycStatusEnum::parse(status)
+            .ok_or_else(|| ApiError::bad_request("Status must be pending, submitted, underreview, approved or rejected"))?;
+        filter.insert("verification_status", status.as_str());
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "updated_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Employer>("employers")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut employers = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let employer = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        employers.push(employer);
+    }
+
+    let total = db.collection::<Employer>("employers")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "employers": employers,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[derive(serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct UpdateEmployerVerificationDto {
+    pub status: String, // "underreview", "approved" or "rejected"
+    pub rejection_reason: Option<String>,
+}
+
+#[openapi(tag = "Admin - Employers")]
+#[put("/admin/employers/<employer_id>/verification", data = "<dto>")]
+pub async fn update_employer_verification(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    employer_id: String,
+    dto: Json<UpdateEmployerVerificationDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&employer_id)
+        .map_err(|_| ApiError::bad_request("Invalid employer ID"))?;
+    let to = KycStatusEnum::parse(&dto.status)
+        .filter(|s| matches!(s, KycStatusEnum::UnderReview | KycStatusEnum::Approved | KycStatusEnum::Rejected))
+        .ok_or_else(|| ApiError::bad_request("Status must be underreview, approved or rejected"))?;
+
+    let employer = db.collection::<Employer>("employers")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Employer not found"))?;
+
+    // Submitted documents are reviewed; an approval can later be revoked
+    let allowed = match employer.verification_status {
+        KycStatusEnum::Submitted => true,
+        KycStatusEnum::UnderReview => !matches!(to, KycStatusEnum::UnderReview),
+        KycStatusEnum::Approved => matches!(to, KycStatusEnum::Rejected),
+        KycStatusEnum::Pending | KycStatusEnum::Rejected => false,
+    };
+    if !allowed {
+        return Err(ApiError::bad_request(format!(
+            "Cannot move a {} employer to {}",
+            employer.verification_status.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let reason = dto.rejection_reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if matches!(to, KycStatusEnum::Rejected) && reason.is_none() {
+        return Err(ApiError::bad_request("A rejection reason is required"));
+    }
+
+    let now = DateTime::now();
+    let approved = matches!(to, KycStatusEnum::Approved);
+    let result = db.collection::<Employer>("employers")
+        .update_one(
+            doc! { "_id": object_id, "verification_status": employer.verification_status.as_str() },
+            doc! { "$set": {
+                "verification_status": to.as_str(),
+                "is_verified": approved,
+                "verified_at": if approved { Some(now) } else { None },
+                "rejection_reason": reason,
+                "updated_at": now,
+            } },
+            None
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update employer: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Employer was updated by someone else, please refresh"));
+    }
+
+    if !matches!(to, KycStatusEnum::UnderReview) {
+        let message = if approved {
+            format!("{} is now a verified employer.", employer.company_name)
+        } else {
+            format!("{} could not be verified: {}", employer.company_name, reason.unwrap_or_default())
+        };
+        notify(
+            db,
+            employer.owner_id,
+            "employer_verification",
+            &format!("Company verification {}", to.as_str()),
+            &message,
+            Some(serde_json::json!({ "employer_id": employer_id, "status": to })),
+        )
+        .await;
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": format!("Employer verification updated to {}", to.as_str()),
+        "status": to
+    }))))
+}
+
+// ==================== PAYMENTS & REFUNDS ADMIN ROUTES ====================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct PaymentListQuery {
+    pub status: Option<String>,
+    pub user_id: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Admin - Payments")]
+#[get("/admin/payments?<query..>")]
+pub async fn get_all_payments(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: PaymentListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+    if let Some(ref user_id) = query.user_id {
+        let user_oid = ObjectId::parse_str(user_id)
+            .map_err(|_| ApiError::bad_request("Invalid user ID"))?;
+        filter.insert("user_id", user_oid);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Payment>("payments")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut payments = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let payment = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        payments.push(payment);
+    }
+
+    let total = db.collection::<Payment>("payments")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "payments": payments,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Admin - Payments")]
+#[post("/admin/payments/<payment_id>/refund", data = "<dto>")]
+pub async fn refund_payment(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    payment_id: String,
+    dto: Json<CreateRefundDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&payment_id)
+        .map_err(|_| ApiError::bad_request("Invalid payment ID"))?;
+
+    let payment = db.collection::<Payment>("payments")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Payment not found"))?;
+
+    let amount = dto.amount.unwrap_or(payment.amount - payment.refunded_amount);
+
+    let refund = issue_refund(db, &payment, amount, dto.reason.as_deref(), Some(admin.auth.user_id)).await?;
+
+    // Only once the refund went through, so a failed refund leaves the subscription running
+    if let Some(subscription_id) = payment.subscription_id.filter(|_| dto.cancel_subscription.unwrap_or(false)) {
+        let subscription = db.collection::<Subscription>("subscriptions")
+            .find_one(doc! { "_id": subscription_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+        if let Some(ref subscription) = subscription {
+            end_subscription(db, subscription, dto.reason.as_deref()).await?;
+        }
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Refund initiated successfully".to_string(),
+        serde_json::json!({
+            "payment_id": payment_id,
+            "refund_id": refund.refund_id,
+            "amount": refund.amount,
+            "status": refund.status
+        })
+    )))
+}
+
+#[openapi(tag = "Admin - Payments")]
+#[post("/admin/payments/<payment_id>/refunds/<refund_id>/sync")]
+pub async fn sync_payment_refund(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    payment_id: String,
+    refund_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&payment_id)
+        .map_err(|_| ApiError::bad_request("Invalid payment ID"))?;
+
+    let payment = db.collection::<Payment>("payments")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Payment not found"))?;
+
+    let status = sync_refund(db, &payment, &refund_id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "payment_id": payment_id,
+        "refund_id": refund_id,
+        "status": status
+    }))))
+}
+
+/// Live state of a payment at the gateway, for reconciling the ledger
+#[openapi(tag = "Admin - Payments")]
+#[get("/admin/payments/<payment_id>/gateway")]
+pub async fn get_gateway_payment(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    payment_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&payment_id)
+        .map_err(|_| ApiError::bad_request("Invalid payment ID"))?;
+
+    let payment = db.collection::<Payment>("payments")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Payment not found"))?;
+
+    let gateway_payment_id = payment
+        .razorpay_payment_id
+        .ok_or_else(|| ApiError::bad_request("Payment has not been captured"))?;
+
+    let gateway_payment = payment_gateway()
+        .fetch_payment(&gateway_payment_id)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to fetch payment: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "payment_id": payment_id,
+        "gateway": payment_gateway().name(),
+        "gateway_payment": gateway_payment
+    }))))
+}
+
+// ==================== COUPONS ADMIN ROUTES ====================
+
+fn parse_coupon_date(value: &str) -> Result<DateTime, ApiError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|d| DateTime::from_millis(d.timestamp_millis()))
+        .map_err(|_| ApiError::bad_request(format!("Invalid date '{}', expected RFC 3339", value)))
+}
+
+#[openapi(tag = "Admin - Coupons")]
+#[post("/admin/coupons", data = "<dto>")]
+pub async fn create_coupon(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    dto: Json<CreateCouponDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let code = dto.code.trim().to_uppercase();
+    if code.is_empty() {
+        return Err(ApiError::bad_request("Coupon code is required"));
+    }
+    if dto.discount_value <= 0.0
+        || (dto.discount_type == DiscountType::Percentage && dto.discount_value > 100.0)
+    {
+        return Err(ApiError::bad_request("Invalid discount value"));
+    }
+
+    let now = DateTime::now();
+    let coupon = Coupon {
+        id: None,
+        code,
+        description: dto.description.clone(),
+        discount_type: dto.discount_type.clone(),
+        discount_value: dto.discount_value,
+        max_discount: dto.max_discount,
+        subscription_type: dto.subscription_type.clone(),
+        applicable_plans: dto
+            .applicable_plans
+            .clone()
+            .unwrap_or_default()
+            .iter()
+            .map(|p| p.to_lowercase())
+            .collect(),
+        valid_from: dto.valid_from.as_deref().map(parse_coupon_date).transpose()?,
+        valid_until: dto.valid_until.as_deref().map(parse_coupon_date).transpose()?,
+        max_redemptions: dto.max_redemptions,
+        max_redemptions_per_user: dto.max_redemptions_per_user,
+        first_purchase_only: dto.first_purchase_only.unwrap_or(false),
+        redemption_count: 0,
+        is_active: dto.is_active.unwrap_or(true),
+        created_at: now,
+        updated_at: now,
+    };
+
+    // The unique index on `code` settles concurrent creates
+    let result = match db.collection::<Coupon>("coupons").insert_one(&coupon, None).await {
+        Ok(result) => result,
+        Err(e) if is_duplicate_key(&e) => {
+            return Err(ApiError::bad_request("A coupon with this code already exists"));
+        }
+        Err(e) => return Err(ApiError::internal_error(format!("Failed to create coupon: {}", e))),
+    };
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Coupon created successfully".to_string(),
+        serde_json::json!({
+            "id": result.inserted_id.as_object_id().unwrap().to_hex(),
+            "code": coupon.code
+        })
+    )))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct CouponListQuery {
+    pub is_active: Option<bool>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Admin - Coupons")]
+#[get("/admin/coupons?<query..>")]
+pub async fn get_all_coupons(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: CouponListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(is_active) = query.is_active {
+        filter.insert("is_active", is_active);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Coupon>("coupons")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut coupons = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let coupon = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        coupons.push(coupon);
+    }
+
+    let total = db.collection::<Coupon>("coupons")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "coupons": coupons,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Admin - Coupons")]
+#[put("/admin/coupons/<coupon_id>", data = "<dto>")]
+pub async fn update_coupon(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    coupon_id: String,
+    dto: Json<UpdateCouponDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&coupon_id)
+        .map_err(|_| ApiError::bad_request("Invalid coupon ID"))?;
+
+    let mut update_doc = doc! {
+        "updated_at": DateTime::now(),
+    };
+
+    if let Some(ref description) = dto.description {
+        update_doc.insert("description", description);
+    }
+    if let Some(ref valid_from) = dto.valid_from {
+        update_doc.insert("valid_from", parse_coupon_date(valid_from)?);
+    }
+    if let Some(ref valid_until) = dto.valid_until {
+        update_doc.insert("valid_until", parse_coupon_date(valid_until)?);
+    }
+    if let Some(max_redemptions) = dto.max_redemptions {
+        update_doc.insert("max_redemptions", max_redemptions);
+    }
+    if let Some(max_per_user) = dto.max_redemptions_per_user {
+        update_doc.insert("max_redemptions_per_user", max_per_user);
+    }
+    if let Some(is_active) = dto.is_active {
+        update_doc.insert("is_active", is_active);
+    }
+
+    let result = db.collection::<Coupon>("coupons")
+        .update_one(
+            doc! { "_id": object_id },
+            doc! { "$set": update_doc },
+            None
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update coupon: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("Coupon not found"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Coupon updated successfully"
+    }))))
+}
+
+#[openapi(tag = "Admin - Coupons")]
+#[get("/admin/coupons/<coupon_id>/redemptions?<page>&<limit>")]
+pub async fn get_coupon_redemptions(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    coupon_id: String,
+    page: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&coupon_id)
+        .map_err(|_| ApiError::bad_request("Invalid coupon ID"))?;
+
+    let page = page.unwrap_or(1).max(1);
+    let limit = limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let filter = doc! { "coupon_id": object_id };
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<CouponRedemption>("coupon_redemptions")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut redemptions = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let redemption = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        redemptions.push(redemption);
+    }
+
+    let total = db.collection::<CouponRedemption>("coupon_redemptions")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "redemptions": redemptions,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+fn validate_badge_conditions(conditions: &[BadgeCondition]) -> Result<(), ApiError> {
+    if conditions.is_empty() {
+        return Err(ApiError::bad_request("A badge needs at least one condition"));
+    }
+    if conditions.iter().any(|c| !c.value.is_finite()) {
+        return Err(ApiError::bad_request("Condition values must be numbers"));
+    }
+    Ok(())
+}
+
+#[openapi(tag = "Admin - Badges")]
+#[get("/admin/badges")]
+pub async fn get_badge_rules(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let mut cursor = db.collection::<BadgeRule>("badge_rules")
+        .find(doc! {}, FindOptions::builder().sort(doc! { "created_at": 1 }).build())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut rules = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let rule = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        rules.push(rule);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "badges": rules
+    }))))
+}
+
+#[openapi(tag = "Admin - Badges")]
+#[post("/admin/badges", data = "<dto>")]
+pub async fn create_badge_rule(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    dto: Json<CreateBadgeRuleDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let code = dto.code.trim().to_lowercase();
+    if code.is_empty() || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ApiError::bad_request("Badge code must be letters, digits and underscores"));
+    }
+    if dto.name.trim().is_empty() {
+        return Err(ApiError::bad_request("Badge name is required"));
+    }
+    validate_badge_conditions(&dto.conditions)?;
+
+    let existing = db.collection::<BadgeRule>("badge_rules")
+        .find_one(doc! { "code": &code }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if existing.is_some() {
+        return Err(ApiError::bad_request("A badge with this code already exists"));
+    }
+
+    let now = DateTime::now();
+    let rule = BadgeRule {
+        id: None,
+        code,
+        name: dto.name.trim().to_string(),
+        description: dto.description.clone(),
+        icon_url: dto.icon_url.clone(),
+        conditions: dto.conditions.clone(),
+        is_active: dto.is_active.unwrap_or(true),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db.collection::<BadgeRule>("badge_rules")
+        .insert_one(&rule, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create badge: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Badge created; workers are evaluated on the next run".to_string(),
+        serde_json::json!({
+            "badge_id": result.inserted_id.as_object_id().unwrap().to_hex(),
+            "code": rule.code
+        })
+    )))
+}
+
+/// Changes apply to workers on the next engine run. Deactivating revokes the badge.
+#[openapi(tag = "Admin - Badges")]
+#[put("/admin/badges/<badge_id>", data = "<dto>")]
+pub async fn update_badge_rule(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    badge_id: String,
+    dto: Json<UpdateBadgeRuleDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&badge_id)
+        .map_err(|_| ApiError::bad_request("Invalid badge ID"))?;
+
+    let mut update_doc = doc! {
+        "updated_at": DateTime::now(),
+    };
+
+    if let Some(ref name) = dto.name {
+        if name.trim().is_empty() {
+            return Err(ApiError::bad_request("Badge name is required"));
+        }
+        update_doc.insert("name", name.trim());
+    }
+    if let Some(ref description) = dto.description {
+        update_doc.insert("description", description);
+    }
+    if let Some(ref icon_url) = dto.icon_url {
+        update_doc.insert("icon_url", icon_url);
+    }
+    if let Some(ref conditions) = dto.conditions {
+        validate_badge_conditions(conditions)?;
+        update_doc.insert(
+            "conditions",
+            mongodb::bson::to_bson(conditions).map_err(|e| ApiError::internal_error(e.to_string()))?,
+        );
+    }
+    if let Some(is_active) = dto.is_active {
+        update_doc.insert("is_active", is_active);
+    }
+
+    let result = db.collection::<BadgeRule>("badge_rules")
+        .update_one(doc! { "_id": object_id }, doc! { "$set": update_doc }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update badge: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("Badge not found"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Badge updated",
+        "badge_id": badge_id
+    }))))
+}
+
+/// Run the badge engine now instead of waiting for the next scheduled run
+#[openapi(tag = "Admin - Badges")]
+#[post("/admin/badges/evaluate")]
+pub async fn evaluate_badges_now(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let summary = evaluate_badges(db).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "workers": summary.workers,
+        "awarded": summary.awarded,
+        "revoked": summary.revoked
+    }))))
+}
+
+#[openapi(tag = "Admin - Badges")]
+#[get("/admin/workers/<worker_id>/badges")]
+pub async fn get_worker_badge_history(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    worker_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_worker_profile(db, &worker_id).await?;
+
+    let mut cursor = db.collection::<BadgeAward>("badge_awards")
+        .find(doc! { "worker_id": worker.id }, FindOptions::builder().sort(doc! { "at": -1 }).build())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut history = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let award = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        history.push(award);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "badges": worker.badges,
+        "history": history
+    }))))
+}
@@ -0,0 +1,503 @@
+use std::collections::BTreeMap;
+use rocket::serde::json::Json;
+use rocket::http::ContentType;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, from_document, DateTime, Document};
+use serde::{Deserialize, Serialize};
+use crate::db::DbConn;
+use crate::guards::AdminGuard;
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// ANALYTICS HELPERS
+// ============================================================================
+
+/// Reporting is done in Indian time so "a day" matches the business day
+const REPORT_TIMEZONE: &str = "Asia/Kolkata";
+const IST_OFFSET_MS: i64 = (5 * 60 + 30) * 60 * 1000;
+pub(crate) const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// Average month length used to normalise plan prices into MRR
+const MONTH_MS: f64 = 365.0 / 12.0 * DAY_MS as f64;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct AnalyticsQuery {
+    /// First day of the range, "YYYY-MM-DD" (IST). Defaults to 30 days before `to`.
+    pub from: Option<String>,
+    /// Last day of the range, inclusive, "YYYY-MM-DD" (IST). Defaults to today.
+    pub to: Option<String>,
+    /// Bucket size: "day" (default), "week" or "month"
+    pub period: Option<String>,
+}
+
+pub(crate) struct ReportRange {
+    pub(crate) from: DateTime,
+    pub(crate) to: DateTime, // Exclusive
+    bucket_format: &'static str,
+}
+
+fn parse_day(value: &str) -> Result<i64, ApiError> {
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request(format!("Invalid date '{}', expected YYYY-MM-DD", value)))?;
+    // Midnight IST in UTC milliseconds
+    Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis() - IST_OFFSET_MS)
+}
+
+impl ReportRange {
+    pub(crate) fn from_query(query: &AnalyticsQuery) -> Result<Self, ApiError> {
+        let bucket_format = match query.period.as_deref().unwrap_or("day") {
+            "day" => "%Y-%m-%d",
+            "week" => "%G-W%V",
+            "month" => "%Y-%m",
+            _ => return Err(ApiError::bad_request("Invalid period. Use 'day', 'week' or 'month'")),
+        };
+
+        let to = match query.to {
+            Some(ref to) => parse_day(to)? + DAY_MS,
+            None => {
+                let now = chrono::Utc::now().timestamp_millis();
+                // End of the current IST day
+                (now + IST_OFFSET_MS).div_euclid(DAY_MS) * DAY_MS - IST_OFFSET_MS + DAY_MS
+            }
+        };
+        let from = match query.from {
+            Some(ref from) => parse_day(from)?,
+            None => to - DEFAULT_RANGE_DAYS * DAY_MS,
+        };
+
+        if from >= to {
+            return Err(ApiError::bad_request("'from' must be on or before 'to'"));
+        }
+
+        Ok(ReportRange {
+            from: DateTime::from_millis(from),
+            to: DateTime::from_millis(to),
+            bucket_format,
+        })
+    }
+
+    /// Don't reach further back than `days` before the end of today
+    pub(crate) fn limit_history(&mut self, days: i64) {
+        let now = chrono::Utc::now().timestamp_millis();
+        let today_end = (now + IST_OFFSET_MS).div_euclid(DAY_MS) * DAY_MS - IST_OFFSET_MS + DAY_MS;
+        let earliest = today_end - days * DAY_MS;
+        if self.from.timestamp_millis() < earliest {
+            self.from = DateTime::from_millis(earliest);
+        }
+        if self.to.timestamp_millis() < self.from.timestamp_millis() {
+            self.to = self.from;
+        }
+    }
+
+    /// First and last IST day in the range, "YYYY-MM-DD"
+    pub(crate) fn day_bounds(&self) -> (String, String) {
+        let day = |millis: i64| {
+            chrono::DateTime::from_timestamp_millis(millis + IST_OFFSET_MS)
+                .unwrap_or_default()
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+        (day(self.from.timestamp_millis()), day(self.to.timestamp_millis() - 1))
+    }
+
+    pub(crate) fn bucket(&self, field: &str) -> Document {
+        doc! {
+            "$dateToString": {
+                "format": self.bucket_format,
+                "date": field,
+                "timezone": REPORT_TIMEZONE
+            }
+        }
+    }
+}
+
+pub(crate) async fn run_aggregation<T: for<'de> Deserialize<'de>>(
+    db: &DbConn,
+    collection: &str,
+    pipeline: Vec<Document>,
+) -> Result<Vec<T>, ApiError> {
+    let mut cursor = db
+        .collection::<Document>(collection)
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    let mut rows = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(e.to_string()))? {
+        let doc = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        rows.push(from_document(doc).map_err(|e| ApiError::internal_error(e.to_string()))?);
+    }
+
+    Ok(rows)
+}
+
+pub(crate) fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// ============================================================================
+// REVENUE
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct RevenueRow {
+    #[serde(rename(deserialize = "_id"))]
+    pub period: String,
+    pub payments: i64,
+    pub gross: f64,
+    pub refunded: f64,
+    #[serde(default)]
+    pub net: f64,
+    pub purchases: f64,
+    pub renewals: f64,
+    pub upgrades: f64,
+}
+
+async fn revenue_rows(db: &DbConn, range: &ReportRange) -> Result<Vec<RevenueRow>, ApiError> {
+    let amount_of = |kind: &str| doc! {
+        "$sum": { "$cond": [{ "$eq": ["$kind", kind] }, "$amount", 0] }
+    };
+
+    let pipeline = vec![
+        doc! { "$match": { "status": { "$in": ["captured", "partially_refunded", "refunded"] } } },
+        doc! {
+            "$addFields": {
+                "paid_at": { "$ifNull": ["$captured_at", "$created_at"] },
+                "kind": { "$ifNull": ["$kind", "purchase"] }
+            }
+        },
+        doc! { "$match": { "paid_at": { "$gte": range.from, "$lt": range.to } } },
+        doc! {
+            "$addFields": {
+                "bucket": range.bucket("$paid_at"),
+                "refunded": {
+                    "$sum": {
+                        "$map": {
+                            "input": {
+                                "$filter": {
+                                    "input": { "$ifNull": ["$refunds", []] },
+                                    "cond": { "$ne": ["$$this.status", "failed"] }
+                                }
+                            },
+                            "in": "$$this.amount"
+                        }
+                    }
+                }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": "$bucket",
+                "payments": { "$sum": 1 },
+                "gross": { "$sum": "$amount" },
+                "refunded": { "$sum": "$refunded" },
+                "purchases": amount_of("purchase"),
+                "renewals": amount_of("renewal"),
+                "upgrades": amount_of("upgrade")
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    let mut rows: Vec<RevenueRow> = run_aggregation(db, "payments", pipeline).await?;
+    for row in rows.iter_mut() {
+        row.gross = round2(row.gross);
+        row.refunded = round2(row.refunded);
+        row.net = round2(row.gross - row.refunded);
+        row.purchases = round2(row.purchases);
+        row.renewals = round2(row.renewals);
+        row.upgrades = round2(row.upgrades);
+    }
+
+    Ok(rows)
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/revenue?<query..>")]
+pub async fn get_revenue_report(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = ReportRange::from_query(&query)?;
+    let rows = revenue_rows(db, &range).await?;
+
+    let gross: f64 = rows.iter().map(|r| r.gross).sum();
+    let refunded: f64 = rows.iter().map(|r| r.refunded).sum();
+    let payments: i64 = rows.iter().map(|r| r.payments).sum();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "from": range.from,
+        "to": range.to,
+        "period": query.period.as_deref().unwrap_or("day"),
+        "currency": "INR",
+        "totals": {
+            "payments": payments,
+            "gross": round2(gross),
+            "refunded": round2(refunded),
+            "net": round2(gross - refunded)
+        },
+        "revenue": rows
+    }))))
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/revenue/export?<query..>")]
+pub async fn export_revenue_report(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<(ContentType, String), ApiError> {
+    let range = ReportRange::from_query(&query)?;
+    let rows = revenue_rows(db, &range).await?;
+
+    let mut csv = String::from("period,payments,gross,refunded,net,purchases,renewals,upgrades\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}\n",
+            row.period, row.payments, row.gross, row.refunded, row.net, row.purchases, row.renewals, row.upgrades
+        ));
+    }
+
+    Ok((ContentType::CSV, csv))
+}
+
+// ============================================================================
+// SUBSCRIPTIONS
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct ActivePlanRow {
+    pub subscription_type: String,
+    pub plan_name: String,
+    pub active: i64,
+    pub mrr: f64,
+}
+
+#[derive(Debug, Default, Serialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct SubscriptionMovementRow {
+    pub period: String,
+    pub new: i64,
+    pub renewed: i64,
+    pub churned: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BucketCount {
+    #[serde(rename = "_id")]
+    key: Document,
+    count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct ConversionRow {
+    #[serde(rename(deserialize = "_id"))]
+    pub subscription_type: String,
+    pub created: i64,
+    pub paid: i64,
+    #[serde(default)]
+    pub conversion_rate: f64,
+}
+
+/// Active subscribers and MRR per plan. Each subscription's price is spread
+/// over its own period, so coupon discounts and plan lengths are accounted for.
+async fn active_plan_rows(db: &DbConn) -> Result<Vec<ActivePlanRow>, ApiError> {
+    let pipeline = vec![
+        doc! { "$match": { "status": "active" } },
+        doc! {
+            "$group": {
+                "_id": { "subscription_type": "$subscription_type", "plan_name": "$plan_name" },
+                "active": { "$sum": 1 },
+                "mrr": {
+                    "$sum": {
+                        "$divide": [
+                            { "$multiply": ["$price", MONTH_MS] },
+                            { "$max": [{ "$subtract": ["$expires_at", "$starts_at"] }, DAY_MS] }
+                        ]
+                    }
+                }
+            }
+        },
+        doc! {
+            "$project": {
+                "_id": 0,
+                "subscription_type": "$_id.subscription_type",
+                "plan_name": "$_id.plan_name",
+                "active": 1,
+                "mrr": 1
+            }
+        },
+        doc! { "$sort": { "subscription_type": 1, "plan_name": 1 } },
+    ];
+
+    let mut rows: Vec<ActivePlanRow> = run_aggregation(db, "subscriptions", pipeline).await?;
+    for row in rows.iter_mut() {
+        row.mrr = round2(row.mrr);
+    }
+
+    Ok(rows)
+}
+
+/// New (first purchase) and renewed subscriptions come from captured payments,
+/// churn from subscriptions that expired or were cancelled in the range
+async fn movement_rows(db: &DbConn, range: &ReportRange) -> Result<Vec<SubscriptionMovementRow>, ApiError> {
+    let payments_pipeline = vec![
+        doc! { "$match": { "status": { "$in": ["captured", "partially_refunded", "refunded"] } } },
+        doc! {
+            "$addFields": {
+                "paid_at": { "$ifNull": ["$captured_at", "$created_at"] },
+                "kind": { "$ifNull": ["$kind", "purchase"] }
+            }
+        },
+        doc! {
+            "$match": {
+                "paid_at": { "$gte": range.from, "$lt": range.to },
+                "kind": { "$in": ["purchase", "renewal"] }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": { "period": range.bucket("$paid_at"), "kind": "$kind" },
+                "count": { "$sum": 1 }
+            }
+        },
+    ];
+
+    let churn_pipeline = vec![
+        doc! {
+            "$match": {
+                "status": { "$in": ["expired", "cancelled"] },
+                "expires_at": { "$gte": range.from, "$lt": range.to }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": { "period": range.bucket("$expires_at"), "kind": "churned" },
+                "count": { "$sum": 1 }
+            }
+        },
+    ];
+
+    let mut buckets: BTreeMap<String, SubscriptionMovementRow> = BTreeMap::new();
+    let payments: Vec<BucketCount> = run_aggregation(db, "payments", payments_pipeline).await?;
+    let churn: Vec<BucketCount> = run_aggregation(db, "subscriptions", churn_pipeline).await?;
+
+    for bucket in payments.into_iter().chain(churn) {
+        let period = bucket.key.get_str("period").unwrap_or_default().to_string();
+        let row = buckets.entry(period.clone()).or_insert_with(|| SubscriptionMovementRow {
+            period,
+            ..Default::default()
+        });
+        match bucket.key.get_str("kind").unwrap_or_default() {
+            "purchase" => row.new += bucket.count,
+            "renewal" => row.renewed += bucket.count,
+            _ => row.churned += bucket.count,
+        }
+    }
+
+    Ok(buckets.into_values().collect())
+}
+
+/// Share of subscription orders created in the range that were paid
+async fn conversion_rows(db: &DbConn, range: &ReportRange) -> Result<Vec<ConversionRow>, ApiError> {
+    let pipeline = vec![
+        doc! { "$match": { "created_at": { "$gte": range.from, "$lt": range.to } } },
+        doc! {
+            "$group": {
+                "_id": "$subscription_type",
+                "created": { "$sum": 1 },
+                "paid": { "$sum": { "$cond": [{ "$gt": ["$payment_id", null] }, 1, 0] } }
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    let mut rows: Vec<ConversionRow> = run_aggregation(db, "subscriptions", pipeline).await?;
+    for row in rows.iter_mut() {
+        if row.created > 0 {
+            row.conversion_rate = round2(row.paid as f64 / row.created as f64 * 100.0);
+        }
+    }
+
+    Ok(rows)
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/subscriptions?<query..>")]
+pub async fn get_subscription_report(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = ReportRange::from_query(&query)?;
+
+    let active = active_plan_rows(db).await?;
+    let movements = movement_rows(db, &range).await?;
+    let conversion = conversion_rows(db, &range).await?;
+
+    let total_active: i64 = active.iter().map(|r| r.active).sum();
+    let mrr: f64 = active.iter().map(|r| r.mrr).sum();
+    let created: i64 = conversion.iter().map(|r| r.created).sum();
+    let paid: i64 = conversion.iter().map(|r| r.paid).sum();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "from": range.from,
+        "to": range.to,
+        "period": query.period.as_deref().unwrap_or("day"),
+        "active": {
+            "total": total_active,
+            "mrr": round2(mrr),
+            "by_plan": active
+        },
+        "movements": {
+            "new": movements.iter().map(|r| r.new).sum::<i64>(),
+            "renewed": movements.iter().map(|r| r.renewed).sum::<i64>(),
+            "churned": movements.iter().map(|r| r.churned).sum::<i64>(),
+            "by_period": movements
+        },
+        "conversion": {
+            "created": created,
+            "paid": paid,
+            "conversion_rate": if created > 0 { round2(paid as f64 / created as f64 * 100.0) } else { 0.0 },
+            "by_type": conversion
+        }
+    }))))
+}
+
+/// CSV of the new / renewed / churned series for the range
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/subscriptions/export?<query..>")]
+pub async fn export_subscription_report(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<(ContentType, String), ApiError> {
+    let range = ReportRange::from_query(&query)?;
+    let movements = movement_rows(db, &range).await?;
+
+    let mut csv = String::from("period,new,renewed,churned\n");
+    for row in movements {
+        csv.push_str(&format!("{},{},{},{}\n", row.period, row.new, row.renewed, row.churned));
+    }
+
+    Ok((ContentType::CSV, csv))
+}
+
+/// CSV of active subscribers and MRR per plan
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/plans/export")]
+pub async fn export_plan_report(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+) -> Result<(ContentType, String), ApiError> {
+    let active = active_plan_rows(db).await?;
+
+    let mut csv = String::from("subscription_type,plan_name,active,mrr\n");
+    for row in active {
+        csv.push_str(&format!("{},{},{},{:.2}\n", row.subscription_type, row.plan_name, row.active, row.mrr));
+    }
+
+    Ok((ContentType::CSV, csv))
+}
@@ -194,6 +194,7 @@ pub async fn verify_otp(
                 pincode: None,
                 kyc_status: KycStatus::Pending,
                 is_active: true,
+                is_admin: false,
                 fcm_token: None,
                 last_login_at: DateTime::now(),
                 created_at: DateTime::now(),
@@ -0,0 +1,352 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, oid::ObjectId};
+use mongodb::options::FindOptions;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use crate::db::DbConn;
+use crate::models::{
+    BadgeAction, BadgeAward, BadgeMetric, BadgeRule, User, WorkerBadge,
+    WorkerProfile,
+};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::{run_aggregation, DAY_MS};
+use crate::routes::notification::notify;
+use crate::utils::{ApiResponse, ApiError};
+
+/// Window for the response-time metrics
+const RESPONSE_WINDOW_DAYS: i64 = 90;
+
+/// Workers evaluated per round trip for KYC lookups and updates
+const EVALUATION_BATCH: usize = 500;
+
+// ============================================================================
+// BADGE ENGINE
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ResponseRow {
+    #[serde(rename = "_id")]
+    worker_id: ObjectId,
+    accepted: i64,
+    declined: i64,
+    avg_response_ms: Option<f64>,
+}
+
+/// What the engine knows about one worker
+struct WorkerMetrics<'a> {
+    worker: &'a WorkerProfile,
+    response: Option<&'a ResponseRow>,
+    kyc_approved: bool,
+}
+
+impl WorkerMetrics<'_> {
+    fn value(&self, metric: BadgeMetric) -> Option<f64> {
+        let has_text = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+        let flag = |set: bool| Some(if set { 1.0 } else { 0.0 });
+        let answered = self.response.map_or(0, |r| r.accepted + r.declined);
+
+        match metric {
+            BadgeMetric::Rating => Some(self.worker.rating),
+            BadgeMetric::TotalReviews => Some(self.worker.total_reviews as f64),
+            BadgeMetric::JobsCompleted => Some(self.worker.total_jobs_completed as f64),
+            BadgeMetric::ExperienceYears => self.worker.experience_years.map(f64::from),
+            BadgeMetric::FavoriteCount => Some(self.worker.favorite_count as f64),
+            BadgeMetric::AvgResponseMinutes => self.response
+                .and_then(|r| r.avg_response_ms)
+                .map(|ms| ms / 60_000.0),
+            BadgeMetric::ResponseCount => Some(answered as f64),
+            BadgeMetric::AcceptanceRate => (answered > 0)
+                .then(|| self.response.map_or(0, |r| r.accepted) as f64 / answered as f64 * 100.0),
+            BadgeMetric::KycApproved => flag(self.kyc_approved),
+            BadgeMetric::LicenseProvided => {
+                flag(has_text(&self.worker.license_number) && has_text(&self.worker.license_document))
+            }
+            BadgeMetric::Verified => flag(self.worker.is_verified),
+        }
+    }
+
+    fn earns(&self, rule: &BadgeRule) -> bool {
+        !rule.conditions.is_empty() && rule.conditions.iter().all(|c| c.holds(self.value(c.metric)))
+    }
+}
+
+async fn active_badge_rules(db: &DbConn) -> Result<Vec<BadgeRule>, ApiError> {
+    let rules = db.collection::<BadgeRule>("badge_rules");
+
+    let mut cursor = rules
+        .find(doc! { "is_active": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut active = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let rule = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        active.push(rule);
+    }
+    Ok(active)
+}
+
+/// Response stats from direct booking requests in the window, keyed by worker
+async fn response_stats(db: &DbConn, worker_ids: &[ObjectId]) -> Result<HashMap<ObjectId, ResponseRow>, ApiError> {
+    let since = DateTime::from_millis(chrono::Utc::now().timestamp_millis() - RESPONSE_WINDOW_DAYS * DAY_MS);
+
+    let rows: Vec<ResponseRow> = run_aggregation(db, "bookings", vec![
+        doc! {
+            "$match": {
+                "worker_id": { "$in": worker_ids },
+                "requested_at": { "$gte": since },
+                "quote_id": null,
+                "$or": [{ "accepted_at": { "$ne": null } }, { "declined_at": { "$ne": null } }]
+            }
+        },
+        doc! {
+            "$project": {
+                "worker_id": 1,
+                "accepted": { "$cond": [{ "$ne": [{ "$ifNull": ["$accepted_at", null] }, null] }, 1, 0] },
+                "declined": { "$cond": [{ "$ne": [{ "$ifNull": ["$declined_at", null] }, null] }, 1, 0] },
+                "response_ms": {
+                    "$subtract": [{ "$ifNull": ["$accepted_at", "$declined_at"] }, "$requested_at"]
+                }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": "$worker_id",
+                "accepted": { "$sum": "$accepted" },
+                "declined": { "$sum": "$declined" },
+                "avg_response_ms": { "$avg": "$response_ms" }
+            }
+        },
+    ]).await?;
+
+    Ok(rows.into_iter().map(|row| (row.worker_id, row)).collect())
+}
+
+async fn kyc_approved_users(db: &DbConn, user_ids: &[ObjectId]) -> Result<HashSet<ObjectId>, ApiError> {
+    let mut cursor = db.collection::<User>("users")
+        .find(doc! { "_id": { "$in": user_ids }, "kyc_status": "approved" }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut approved = HashSet::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let user = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        if let Some(id) = user.id {
+            approved.insert(id);
+        }
+    }
+    Ok(approved)
+}
+
+#[derive(Debug, Default)]
+pub struct BadgeRunSummary {
+    pub workers: usize,
+    pub awarded: usize,
+    pub revoked: usize,
+}
+
+/// Bring one batch of workers' badges in line with the rules
+async fn evaluate_batch(
+    db: &DbConn,
+    rules: &[BadgeRule],
+    workers: &[WorkerProfile],
+    summary: &mut BadgeRunSummary,
+) -> Result<(), ApiError> {
+    let worker_ids: Vec<ObjectId> = workers.iter().filter_map(|w| w.id).collect();
+    let user_ids: Vec<ObjectId> = workers.iter().map(|w| w.user_id).collect();
+    let responses = response_stats(db, &worker_ids).await?;
+    let kyc_approved = kyc_approved_users(db, &user_ids).await?;
+
+    let now = DateTime::now();
+    for worker in workers {
+        let Some(worker_id) = worker.id else {
+            continue;
+        };
+        let metrics = WorkerMetrics {
+            worker,
+            response: responses.get(&worker_id),
+            kyc_approved: kyc_approved.contains(&worker.user_id),
+        };
+
+        // Badges kept from the last run keep their award time
+        let badges: Vec<WorkerBadge> = rules
+            .iter()
+            .filter(|rule| metrics.earns(rule))
+            .map(|rule| WorkerBadge {
+                code: rule.code.clone(),
+                name: rule.name.clone(),
+                icon_url: rule.icon_url.clone(),
+                awarded_at: worker
+                    .badges
+                    .iter()
+                    .find(|b| b.code == rule.code)
+                    .map_or(now, |b| b.awarded_at),
+            })
+            .collect();
+
+        let awarded: Vec<&WorkerBadge> = badges
+            .iter()
+            .filter(|b| !worker.badges.iter().any(|held| held.code == b.code))
+            .collect();
+        let revoked: Vec<&WorkerBadge> = worker
+            .badges
+            .iter()
+            .filter(|held| !badges.iter().any(|b| b.code == held.code))
+            .collect();
+        let renamed = badges.iter().any(|b| {
+            worker.badges.iter().any(|held| held.code == b.code && (held.name != b.name || held.icon_url != b.icon_url))
+        });
+
+        if awarded.is_empty() && revoked.is_empty() && !renamed {
+            continue;
+        }
+
+        let badges_bson = mongodb::bson::to_bson(&badges)
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        db.collection::<WorkerProfile>("worker_profiles")
+            .update_one(doc! { "_id": worker_id }, doc! { "$set": { "badges": badges_bson } }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update badges: {}", e)))?;
+
+        let history: Vec<BadgeAward> = awarded
+            .iter()
+            .map(|b| (b, BadgeAction::Awarded))
+            .chain(revoked.iter().map(|b| (b, BadgeAction::Revoked)))
+            .map(|(b, action)| BadgeAward {
+                id: None,
+                worker_id,
+                code: b.code.clone(),
+                name: b.name.clone(),
+                action,
+                at: now,
+            })
+            .collect();
+        if !history.is_empty() {
+            db.collection::<BadgeAward>("badge_awards")
+                .insert_many(&history, None)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Failed to record badge history: {}", e)))?;
+        }
+
+        for badge in &awarded {
+            notify(
+                db,
+                worker.user_id,
+                "badge",
+                &format!("You earned the {} badge", badge.name),
+                "It now shows on your profile and in search results.",
+                Some(serde_json::json!({ "badge": badge.code })),
+            )
+            .await;
+        }
+
+        summary.awarded += awarded.len();
+        summary.revoked += revoked.len();
+    }
+
+    summary.workers += workers.len();
+    Ok(())
+}
+
+/// Evaluate every worker against the active badge rules
+pub async fn evaluate_badges(db: &DbConn) -> Result<BadgeRunSummary, ApiError> {
+    let rules = active_badge_rules(db).await?;
+    let mut summary = BadgeRunSummary::default();
+
+    let mut cursor = db.collection::<WorkerProfile>("worker_profiles")
+        .find(doc! {}, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut batch = Vec::with_capacity(EVALUATION_BATCH);
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        match cursor.deserialize_current() {
+            Ok(worker) => batch.push(worker),
+            Err(e) => warn!("Skipping unreadable worker profile in badge run: {}", e),
+        }
+        if batch.len() == EVALUATION_BATCH {
+            evaluate_batch(db, &rules, &batch, &mut summary).await?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        evaluate_batch(db, &rules, &batch, &mut summary).await?;
+    }
+
+    Ok(summary)
+}
+
+// ============================================================================
+// WORKER BADGE ENDPOINTS
+// ============================================================================
+
+/// Current badges, progress towards the rest and award history
+#[openapi(tag = "Worker")]
+#[get("/worker/badges")]
+pub async fn get_my_badges(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker profile has no ID"))?;
+
+    let rules = active_badge_rules(db).await?;
+    let responses = response_stats(db, &[worker_id]).await?;
+    let kyc_approved = kyc_approved_users(db, &[worker.user_id]).await?;
+    let metrics = WorkerMetrics {
+        worker: &worker,
+        response: responses.get(&worker_id),
+        kyc_approved: kyc_approved.contains(&worker.user_id),
+    };
+
+    // Live view; the profile itself updates on the next engine run
+    let progress: Vec<serde_json::Value> = rules
+        .iter()
+        .map(|rule| serde_json::json!({
+            "code": rule.code,
+            "name": rule.name,
+            "description": rule.description,
+            "icon_url": rule.icon_url,
+            "earned": metrics.earns(rule),
+            "conditions": rule.conditions.iter().map(|c| {
+                let actual = metrics.value(c.metric);
+                serde_json::json!({
+                    "metric": c.metric,
+                    "op": c.op,
+                    "value": c.value,
+                    "actual": actual,
+                    "met": c.holds(actual)
+                })
+            }).collect::<Vec<_>>()
+        }))
+        .collect();
+
+    let mut cursor = db.collection::<BadgeAward>("badge_awards")
+        .find(
+            doc! { "worker_id": worker_id },
+            FindOptions::builder().sort(doc! { "at": -1 }).limit(100).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut history = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let award = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        history.push(award);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "badges": worker.badges,
+        "progress": progress,
+        "history": history
+    }))))
+}
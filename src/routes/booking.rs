@@ -0,0 +1,530 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::FindOptions;
+use crate::db::DbConn;
+use crate::models::{
+    ist, Booking, BookingParty, BookingReasonDto, BookingStatus, CreateBookingDto, GeoLocation,
+    ScheduleBookingDto, WorkerProfile,
+};
+use crate::guards::AuthGuard;
+use crate::routes::tracking::LocationHub;
+use crate::routes::worker::worker_free_slots;
+use chrono::{TimeZone, Timelike};
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// BOOKING HELPERS
+// ============================================================================
+
+pub fn parse_booking_time(value: &str) -> Result<DateTime, ApiError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|d| DateTime::from_millis(d.timestamp_millis()))
+        .map_err(|_| ApiError::bad_request(format!("Invalid time '{}', expected RFC 3339", value)))
+}
+
+pub(crate) async fn find_booking(db: &DbConn, booking_id: &str) -> Result<Booking, ApiError> {
+    let object_id = ObjectId::parse_str(booking_id)
+        .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+
+    db.collection::<Booking>("bookings")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))
+}
+
+/// Which side of the booking the caller is on
+pub(crate) fn booking_party(booking: &Booking, user_id: ObjectId) -> Result<BookingParty, ApiError> {
+    if booking.worker_user_id == user_id {
+        Ok(BookingParty::Worker)
+    } else if booking.customer_id == user_id {
+        Ok(BookingParty::Customer)
+    } else {
+        Err(ApiError::not_found("Booking not found"))
+    }
+}
+
+/// Check the worker is free for `hours` from `start`. On a worker with a schedule the time has
+/// to begin a free slot and run through back-to-back free slots; on any worker it can't overlap
+/// a committed booking.
+async fn check_booking_slot(db: &DbConn, worker: &WorkerProfile, start: DateTime, hours: f64) -> Result<(), ApiError> {
+    let end = DateTime::from_millis(start.timestamp_millis() + (hours * 60.0 * 60.0 * 1000.0) as i64);
+
+    if worker.schedule.is_some() {
+        let local = ist().timestamp_millis_opt(start.timestamp_millis()).unwrap();
+        let Some((_, free)) = worker_free_slots(db, worker, local.date_naive()).await? else {
+            return Err(ApiError::bad_request("Worker is not taking bookings"));
+        };
+
+        let minute = (local.hour() * 60 + local.minute()) as i32;
+        let needed_until = minute + (hours * 60.0).ceil() as i32;
+        let mut covered_until = minute;
+        for (slot_start, slot_end) in free {
+            if slot_start == covered_until && covered_until < needed_until {
+                covered_until = slot_end;
+            }
+        }
+
+        if local.second() != 0 || covered_until < needed_until {
+            return Err(ApiError::bad_request(
+                "Preferred start is not a free slot long enough for the job, pick one from the worker's slots",
+            ));
+        }
+    }
+
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker has no ID"))?;
+    check_no_overlap(db, worker_id, start, end, None).await
+}
+
+/// Refuse a window that overlaps one of the worker's scheduled or running bookings,
+/// other than `exclude`
+async fn check_no_overlap(
+    db: &DbConn,
+    worker_id: ObjectId,
+    start: DateTime,
+    end: DateTime,
+    exclude: Option<ObjectId>,
+) -> Result<(), ApiError> {
+    let mut filter = doc! {
+        "worker_id": worker_id,
+        "status": { "$in": ["scheduled", "in_progress"] },
+        "scheduled_start": { "$lt": end },
+        "scheduled_end": { "$gt": start }
+    };
+    if let Some(id) = exclude {
+        filter.insert("_id", doc! { "$ne": id });
+    }
+
+    let overlapping = db.collection::<Booking>("bookings")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if overlapping > 0 {
+        return Err(ApiError::bad_request("Worker is already booked at that time"));
+    }
+
+    Ok(())
+}
+
+/// Move a booking into `to`, stamping the transition time.
+/// The update is conditional on the current state, so two racing transitions
+/// cannot both succeed.
+async fn transition_booking(
+    db: &DbConn,
+    booking: &Booking,
+    to: BookingStatus,
+    mut extra: Document,
+) -> Result<DateTime, ApiError> {
+    if !booking.status.next_states().contains(&to) {
+        return Err(ApiError::bad_request(format!(
+            "Cannot move a {} booking to {}",
+            booking.status.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let now = DateTime::now();
+    extra.insert("status", to.as_str());
+    extra.insert(to.timestamp_field(), now);
+    extra.insert("updated_at", now);
+
+    let result = db.collection::<Booking>("bookings")
+        .update_one(
+            doc! { "_id": booking.id, "status": booking.status.as_str() },
+            doc! { "$set": extra },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update booking: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Booking was updated by someone else, please refresh"));
+    }
+
+    Ok(now)
+}
+
+// ============================================================================
+// CUSTOMER ENDPOINTS
+// ============================================================================
+
+/// Validate and store a new booking request from `customer_id`
+pub(crate) async fn request_booking(
+    db: &DbConn,
+    customer_id: ObjectId,
+    dto: &CreateBookingDto,
+) -> Result<(ObjectId, Booking), ApiError> {
+    let worker_id = ObjectId::parse_str(&dto.worker_id)
+        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    if worker.user_id == customer_id {
+        return Err(ApiError::bad_request("You cannot book yourself"));
+    }
+    if !worker.is_available {
+        return Err(ApiError::bad_request("Worker is not available for bookings"));
+    }
+    if !worker.categories.iter().any(|c| c.eq_ignore_ascii_case(&dto.category)) {
+        return Err(ApiError::bad_request("Worker does not offer this category"));
+    }
+    if let Some(ref subcategory) = dto.subcategory
+        && !worker.subcategories.iter().any(|s| s.eq_ignore_ascii_case(subcategory))
+    {
+        return Err(ApiError::bad_request("Worker does not offer this subcategory"));
+    }
+
+    let hourly_rate = worker
+        .hourly_rate
+        .filter(|rate| *rate > 0.0)
+        .ok_or_else(|| ApiError::bad_request("Worker has not set an hourly rate"))?;
+
+    if dto.estimated_hours <= 0.0 || dto.estimated_hours > 24.0 {
+        return Err(ApiError::bad_request("Estimated hours must be between 0 and 24"));
+    }
+    if dto.address.trim().is_empty() {
+        return Err(ApiError::bad_request("Service address is required"));
+    }
+    if !(-90.0..=90.0).contains(&dto.latitude) || !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Invalid coordinates"));
+    }
+
+    let preferred_start = parse_booking_time(&dto.preferred_start)?;
+    let now = DateTime::now();
+    if preferred_start < now {
+        return Err(ApiError::bad_request("Preferred start must be in the future"));
+    }
+    check_booking_slot(db, &worker, preferred_start, dto.estimated_hours).await?;
+
+    let booking = Booking {
+        id: None,
+        customer_id,
+        worker_id,
+        worker_user_id: worker.user_id,
+        category: dto.category.clone(),
+        subcategory: dto.subcategory.clone(),
+        description: dto.description.clone(),
+        address: dto.address.trim().to_string(),
+        location: GeoLocation {
+            geo_type: "Point".to_string(),
+            coordinates: [dto.longitude, dto.latitude],
+        },
+        hourly_rate,
+        estimated_hours: dto.estimated_hours,
+        agreed_price: (hourly_rate * dto.estimated_hours * 100.0).round() / 100.0,
+        preferred_start,
+        quote_id: None,
+        scheduled_start: None,
+        scheduled_end: None,
+        status: BookingStatus::Requested,
+        decline_reason: None,
+        cancelled_by: None,
+        cancellation_reason: None,
+        requested_at: now,
+        accepted_at: None,
+        declined_at: None,
+        scheduled_at: None,
+        en_route_at: None,
+        started_at: None,
+        completed_at: None,
+        cancelled_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db.collection::<Booking>("bookings")
+        .insert_one(&booking, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create booking: {}", e)))?;
+    let booking_id = result.inserted_id.as_object_id()
+        .ok_or_else(|| ApiError::internal_error("Booking was stored without an ID"))?;
+
+    Ok((booking_id, booking))
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/create", data = "<dto>")]
+pub async fn create_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateBookingDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking_id, booking) = request_booking(db, auth.user_id, &dto).await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Booking requested successfully".to_string(),
+        serde_json::json!({
+            "booking_id": booking_id.to_hex(),
+            "status": booking.status,
+            "agreed_price": booking.agreed_price
+        })
+    )))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct BookingListQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+async fn list_bookings(
+    db: &DbConn,
+    mut filter: Document,
+    query: BookingListQuery,
+) -> Result<serde_json::Value, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Booking>("bookings")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut bookings = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let booking = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        bookings.push(booking);
+    }
+
+    let total = db.collection::<Booking>("bookings")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "bookings": bookings,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/customer?<query..>")]
+pub async fn get_customer_bookings(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: BookingListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let bookings = list_bookings(db, doc! { "customer_id": auth.user_id }, query).await?;
+    Ok(Json(ApiResponse::success(bookings)))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/worker?<query..>")]
+pub async fn get_worker_bookings(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: BookingListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let bookings = list_bookings(db, doc! { "worker_user_id": auth.user_id }, query).await?;
+    Ok(Json(ApiResponse::success(bookings)))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/<booking_id>")]
+pub async fn get_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_booking(db, &booking_id).await?;
+    booking_party(&booking, auth.user_id)?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!(booking))))
+}
+
+// ============================================================================
+// LIFECYCLE ENDPOINTS
+// ============================================================================
+
+pub(crate) async fn find_worker_booking(db: &DbConn, booking_id: &str, user_id: ObjectId) -> Result<Booking, ApiError> {
+    let booking = find_booking(db, booking_id).await?;
+    if booking_party(&booking, user_id)? != BookingParty::Worker {
+        return Err(ApiError::forbidden("Only the worker can do this"));
+    }
+    Ok(booking)
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/accept")]
+pub async fn accept_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    transition_booking(db, &booking, BookingStatus::Accepted, doc! {}).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking accepted",
+        "booking_id": booking_id,
+        "status": BookingStatus::Accepted
+    }))))
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/decline", data = "<dto>")]
+pub async fn decline_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<BookingReasonDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    transition_booking(db, &booking, BookingStatus::Declined, doc! { "decline_reason": &dto.reason }).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking declined",
+        "booking_id": booking_id,
+        "status": BookingStatus::Declined
+    }))))
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/schedule", data = "<dto>")]
+pub async fn schedule_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<ScheduleBookingDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+
+    let scheduled_start = parse_booking_time(&dto.scheduled_start)?;
+    let scheduled_end = match dto.scheduled_end {
+        Some(ref end) => parse_booking_time(end)?,
+        None => DateTime::from_millis(
+            scheduled_start.timestamp_millis() + (booking.estimated_hours * 60.0 * 60.0 * 1000.0) as i64,
+        ),
+    };
+    if scheduled_end <= scheduled_start {
+        return Err(ApiError::bad_request("Scheduled end must be after the start"));
+    }
+    if scheduled_start < DateTime::now() {
+        return Err(ApiError::bad_request("Scheduled start must be in the future"));
+    }
+    check_no_overlap(db, booking.worker_id, scheduled_start, scheduled_end, booking.id).await?;
+
+    transition_booking(
+        db,
+        &booking,
+        BookingStatus::Scheduled,
+        doc! { "scheduled_start": scheduled_start, "scheduled_end": scheduled_end },
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking scheduled",
+        "booking_id": booking_id,
+        "status": BookingStatus::Scheduled,
+        "scheduled_start": scheduled_start,
+        "scheduled_end": scheduled_end
+    }))))
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/start")]
+pub async fn start_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    transition_booking(db, &booking, BookingStatus::InProgress, doc! {}).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking started",
+        "booking_id": booking_id,
+        "status": BookingStatus::InProgress
+    }))))
+}
+
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/complete")]
+pub async fn complete_booking(
+    db: &State<DbConn>,
+    hub: &State<LocationHub>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    transition_booking(db, &booking, BookingStatus::Completed, doc! {}).await?;
+    if let Some(id) = booking.id {
+        hub.end(id, BookingStatus::Completed);
+    }
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": booking.worker_id },
+            doc! {
+                "$inc": { "total_jobs_completed": 1 },
+                "$set": { "updated_at": DateTime::now() }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update worker stats: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking completed",
+        "booking_id": booking_id,
+        "status": BookingStatus::Completed
+    }))))
+}
+
+/// Either side can cancel until the work has started
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/cancel", data = "<dto>")]
+pub async fn cancel_booking(
+    db: &State<DbConn>,
+    hub: &State<LocationHub>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<BookingReasonDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_booking(db, &booking_id).await?;
+    let party = booking_party(&booking, auth.user_id)?;
+
+    let cancelled_by = mongodb::bson::to_bson(&party)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    transition_booking(
+        db,
+        &booking,
+        BookingStatus::Cancelled,
+        doc! { "cancelled_by": cancelled_by, "cancellation_reason": &dto.reason },
+    )
+    .await?;
+    if let Some(id) = booking.id {
+        hub.end(id, BookingStatus::Cancelled);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking cancelled",
+        "booking_id": booking_id,
+        "status": BookingStatus::Cancelled
+    }))))
+}
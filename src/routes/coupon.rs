@@ -0,0 +1,327 @@
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::UpdateOptions;
+use crate::db::DbConn;
+use crate::models::{Coupon, CouponRedemption, PlanDetails, RedemptionStatus, SubscriptionType};
+use crate::utils::{is_duplicate_key, ApiError};
+
+// ============================================================================
+// COUPON REDEMPTION HELPERS
+// ============================================================================
+
+/// A coupon reserved against a subscription order
+pub struct AppliedCoupon {
+    pub redemption_id: ObjectId,
+    pub code: String,
+    pub discount: f64,
+    pub final_price: f64,
+}
+
+fn usage_key(coupon_id: ObjectId, user_id: ObjectId) -> String {
+    format!("{}:{}", coupon_id.to_hex(), user_id.to_hex())
+}
+
+/// Validate a coupon for a plan and reserve one use of it.
+///
+/// Both the global and the per-user limits are enforced with conditional
+/// updates, so concurrent orders can never redeem a coupon past its limits.
+pub async fn reserve_coupon(
+    db: &DbConn,
+    code: &str,
+    user_id: ObjectId,
+    subscription_type: &SubscriptionType,
+    plan: &PlanDetails,
+) -> Result<AppliedCoupon, ApiError> {
+    let code = code.trim().to_uppercase();
+    let now = DateTime::now();
+
+    let coupon = db
+        .collection::<Coupon>("coupons")
+        .find_one(doc! { "code": &code, "is_active": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::bad_request("Invalid coupon code"))?;
+    let coupon_id = coupon.id.ok_or_else(|| ApiError::internal_error("Coupon has no id"))?;
+
+    if coupon.valid_from.is_some_and(|from| now < from) {
+        return Err(ApiError::bad_request("Coupon is not active yet"));
+    }
+    if coupon.valid_until.is_some_and(|until| now > until) {
+        return Err(ApiError::bad_request("Coupon has expired"));
+    }
+    if coupon.subscription_type.as_ref().is_some_and(|t| t != subscription_type)
+        || (!coupon.applicable_plans.is_empty()
+            && !coupon.applicable_plans.iter().any(|p| p.eq_ignore_ascii_case(plan.name)))
+    {
+        return Err(ApiError::bad_request("Coupon is not valid for this plan"));
+    }
+
+    if coupon.first_purchase_only {
+        let paid_before = db
+            .collection::<Document>("payments")
+            .count_documents(
+                doc! {
+                    "user_id": user_id,
+                    "status": { "$in": ["captured", "partially_refunded", "refunded"] }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        if paid_before > 0 {
+            return Err(ApiError::bad_request("Coupon is only valid on your first purchase"));
+        }
+    }
+
+    let discount = coupon.discount_for(plan.price);
+    let final_price = ((plan.price - discount) * 100.0).round() / 100.0;
+    if discount <= 0.0 {
+        return Err(ApiError::bad_request("Coupon gives no discount on this plan"));
+    }
+    if final_price <= 0.0 {
+        return Err(ApiError::bad_request("Coupon cannot cover the full plan price"));
+    }
+
+    // Per-user limit: a usage counter per (coupon, user). When the counter is
+    // already at the limit the filter misses and the upsert hits the unique _id.
+    let mut usage_filter = doc! { "_id": usage_key(coupon_id, user_id) };
+    if let Some(limit) = coupon.max_redemptions_per_user {
+        usage_filter.insert("count", doc! { "$lt": limit });
+    }
+
+    let result = db
+        .collection::<Document>("coupon_usage")
+        .update_one(
+            usage_filter,
+            doc! { "$inc": { "count": 1_i64 } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) if is_duplicate_key(&e) => {
+            return Err(ApiError::bad_request("You have already used this coupon"));
+        }
+        Err(e) => return Err(ApiError::internal_error(e.to_string())),
+    }
+
+    // Global limit
+    let reserved = db
+        .collection::<Coupon>("coupons")
+        .update_one(
+            doc! {
+                "_id": coupon_id,
+                "is_active": true,
+                "$or": [
+                    { "max_redemptions": null },
+                    { "$expr": { "$lt": ["$redemption_count", "$max_redemptions"] } }
+                ]
+            },
+            doc! { "$inc": { "redemption_count": 1_i64 }, "$set": { "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if reserved.modified_count == 0 {
+        give_back_user_usage(db, &coupon, user_id).await;
+        return Err(ApiError::bad_request("Coupon usage limit reached"));
+    }
+
+    let redemption = CouponRedemption {
+        id: None,
+        coupon_id,
+        code: coupon.code.clone(),
+        user_id,
+        subscription_id: None,
+        plan_name: plan.name.to_string(),
+        original_price: plan.price,
+        discount,
+        final_price,
+        status: RedemptionStatus::Reserved,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db
+        .collection::<CouponRedemption>("coupon_redemptions")
+        .insert_one(&redemption, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to record coupon redemption: {}", e)))?;
+
+    Ok(AppliedCoupon {
+        redemption_id: result
+            .inserted_id
+            .as_object_id()
+            .ok_or_else(|| ApiError::internal_error("Invalid redemption ID"))?,
+        code: coupon.code,
+        discount,
+        final_price,
+    })
+}
+
+async fn give_back_user_usage(db: &DbConn, coupon: &Coupon, user_id: ObjectId) {
+    let Some(coupon_id) = coupon.id else {
+        return;
+    };
+
+    if let Err(e) = db
+        .collection::<Document>("coupon_usage")
+        .update_one(
+            doc! { "_id": usage_key(coupon_id, user_id), "count": { "$gt": 0 } },
+            doc! { "$inc": { "count": -1_i64 } },
+            None,
+        )
+        .await
+    {
+        error!("Failed to release coupon usage for {}: {}", coupon.code, e);
+    }
+}
+
+/// Adjust the coupon counters by `delta` uses for a redemption
+async fn adjust_counters(db: &DbConn, redemption: &CouponRedemption, delta: i64) -> Result<(), ApiError> {
+    db.collection::<Coupon>("coupons")
+        .update_one(
+            doc! { "_id": redemption.coupon_id },
+            doc! { "$inc": { "redemption_count": delta }, "$set": { "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let coupon_usage = db.collection::<Document>("coupon_usage");
+    let key = usage_key(redemption.coupon_id, redemption.user_id);
+    let result = if delta > 0 {
+        coupon_usage
+            .update_one(
+                doc! { "_id": key },
+                doc! { "$inc": { "count": delta } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+    } else {
+        coupon_usage
+            .update_one(
+                doc! { "_id": key, "count": { "$gt": 0 } },
+                doc! { "$inc": { "count": delta } },
+                None,
+            )
+            .await
+    };
+    result.map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Link a reserved redemption to the subscription created for its order
+pub async fn attach_coupon_redemption(
+    db: &DbConn,
+    redemption_id: ObjectId,
+    subscription_id: ObjectId,
+) -> Result<(), ApiError> {
+    db.collection::<CouponRedemption>("coupon_redemptions")
+        .update_one(
+            doc! { "_id": redemption_id },
+            doc! { "$set": { "subscription_id": subscription_id, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Give a reserved use back, e.g. when the order could not be created or was abandoned
+pub async fn release_coupon_redemption(db: &DbConn, redemption_id: ObjectId) -> Result<(), ApiError> {
+    let redemption = db
+        .collection::<CouponRedemption>("coupon_redemptions")
+        .find_one_and_update(
+            doc! { "_id": redemption_id, "status": "reserved" },
+            doc! { "$set": { "status": "released", "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // Someone else already released or confirmed it
+    let Some(redemption) = redemption else {
+        return Ok(());
+    };
+
+    adjust_counters(db, &redemption, -1).await
+}
+
+/// Mark the coupon used on a subscription as redeemed once its payment is verified
+pub async fn confirm_coupon_redemption(db: &DbConn, subscription_id: ObjectId) -> Result<(), ApiError> {
+    let redemption = db
+        .collection::<CouponRedemption>("coupon_redemptions")
+        .find_one_and_update(
+            doc! { "subscription_id": subscription_id, "status": { "$in": ["reserved", "released"] } },
+            doc! { "$set": { "status": "redeemed", "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if let Some(redemption) = redemption.filter(|r| r.status == RedemptionStatus::Released) {
+        recount_lapsed_redemption(db, &redemption).await?;
+    }
+
+    Ok(())
+}
+
+/// The order was paid after its reservation had lapsed, so its use has to be counted again.
+/// The same conditional updates as a reservation keep the counters within the coupon limits;
+/// past them the paid order is honoured, since the discount was already charged, but not counted.
+async fn recount_lapsed_redemption(db: &DbConn, redemption: &CouponRedemption) -> Result<(), ApiError> {
+    let coupon = db
+        .collection::<Coupon>("coupons")
+        .find_one_and_update(
+            doc! {
+                "_id": redemption.coupon_id,
+                "$or": [
+                    { "max_redemptions": null },
+                    { "$expr": { "$lt": ["$redemption_count", "$max_redemptions"] } }
+                ]
+            },
+            doc! { "$inc": { "redemption_count": 1_i64 }, "$set": { "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let Some(coupon) = coupon else {
+        warn!(
+            "Coupon {} was redeemed past its limit by a lapsed reservation paid late (redemption {:?})",
+            redemption.code, redemption.id
+        );
+        return Ok(());
+    };
+
+    let mut usage_filter = doc! { "_id": usage_key(redemption.coupon_id, redemption.user_id) };
+    if let Some(limit) = coupon.max_redemptions_per_user {
+        usage_filter.insert("count", doc! { "$lt": limit });
+    }
+
+    let result = db
+        .collection::<Document>("coupon_usage")
+        .update_one(
+            usage_filter,
+            doc! { "$inc": { "count": 1_i64 } },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await;
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) if is_duplicate_key(&e) => {
+            warn!(
+                "Coupon {} was redeemed past its per-user limit by a lapsed reservation paid late (redemption {:?})",
+                redemption.code, redemption.id
+            );
+            Ok(())
+        }
+        Err(e) => Err(ApiError::internal_error(e.to_string())),
+    }
+}
@@ -0,0 +1,250 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::UpdateOptions;
+use serde::{Deserialize, Serialize};
+use crate::db::DbConn;
+use crate::models::{WorkerDailyStats, WorkerProfile, ist};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::{round2, run_aggregation, AnalyticsQuery, ReportRange};
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// PROFILE VIEW AND IMPRESSION COUNTERS
+// ============================================================================
+
+pub(crate) fn today_ist() -> String {
+    chrono::Utc::now().with_timezone(&ist()).format("%Y-%m-%d").to_string()
+}
+
+pub(crate) async fn bump_daily_stat(db: &DbConn, worker_id: ObjectId, date: &str, field: &str) {
+    let result = db.collection::<WorkerDailyStats>("worker_daily_stats")
+        .update_one(
+            doc! { "worker_id": worker_id, "date": date },
+            doc! {
+                "$inc": { field: 1_i64 },
+                "$set": { "updated_at": DateTime::now() }
+            },
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await;
+
+    if let Err(e) = result {
+        warn!("Failed to count {} for worker {}: {}", field, worker_id, e);
+    }
+}
+
+/// Count one search impression for every worker shown on a results page.
+/// Runs in the background so searches don't wait on it.
+pub fn record_search_impressions(db: &DbConn, worker_ids: Vec<ObjectId>) {
+    if worker_ids.is_empty() {
+        return;
+    }
+
+    let db = db.clone();
+    tokio::spawn(async move {
+        let date = today_ist();
+        for worker_id in worker_ids {
+            bump_daily_stat(&db, worker_id, &date, "search_impressions").await;
+        }
+    });
+}
+
+// ============================================================================
+// WORKER DASHBOARD
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatusCount {
+    #[serde(rename(deserialize = "_id"))]
+    status: String,
+    count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResponseStats {
+    accepted: i64,
+    declined: i64,
+    avg_response_ms: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EarningsRow {
+    #[serde(rename(deserialize = "_id"))]
+    period: String,
+    jobs: i64,
+    earnings: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RatingRow {
+    #[serde(rename(deserialize = "_id"))]
+    period: String,
+    reviews: i64,
+    average_rating: f64,
+}
+
+/// Bookings, earnings, ratings and visibility for the signed-in worker,
+/// plus how many customers have saved them as a favourite.
+/// History goes back 30 days on the free plan, 90 on Silver and two years on Gold.
+#[openapi(tag = "Worker")]
+#[get("/worker/dashboard?<query..>")]
+pub async fn get_worker_dashboard(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker profile has no ID"))?;
+
+    let history_days = worker.subscription_plan.dashboard_history_days();
+    let mut range = ReportRange::from_query(&query)?;
+    range.limit_history(history_days);
+
+    let in_range = doc! { "$gte": range.from, "$lt": range.to };
+
+    // Bookings requested in the range, by current status
+    let by_status: Vec<StatusCount> = run_aggregation(db, "bookings", vec![
+        doc! { "$match": { "worker_id": worker_id, "requested_at": in_range.clone() } },
+        doc! { "$group": { "_id": "$status", "count": { "$sum": 1 } } },
+        doc! { "$sort": { "_id": 1 } },
+    ]).await?;
+
+    let count_of = |status: &str| by_status.iter().find(|s| s.status == status).map_or(0, |s| s.count);
+    let completed = count_of("completed");
+
+    // Of the bookings the worker took on, how many ended up done
+    let cancelled_after_accept = db.collection::<mongodb::bson::Document>("bookings")
+        .count_documents(
+            doc! {
+                "worker_id": worker_id,
+                "requested_at": in_range.clone(),
+                "status": "cancelled",
+                "accepted_at": { "$ne": null }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))? as i64;
+
+    // Acceptance and response time only count direct requests, not accepted quotes
+    let response: Vec<ResponseStats> = run_aggregation(db, "bookings", vec![
+        doc! {
+            "$match": {
+                "worker_id": worker_id,
+                "requested_at": in_range.clone(),
+                "quote_id": null,
+                "$or": [{ "accepted_at": { "$ne": null } }, { "declined_at": { "$ne": null } }]
+            }
+        },
+        doc! {
+            "$project": {
+                "accepted": { "$cond": [{ "$ne": [{ "$ifNull": ["$accepted_at", null] }, null] }, 1, 0] },
+                "declined": { "$cond": [{ "$ne": [{ "$ifNull": ["$declined_at", null] }, null] }, 1, 0] },
+                "response_ms": {
+                    "$subtract": [{ "$ifNull": ["$accepted_at", "$declined_at"] }, "$requested_at"]
+                }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": null,
+                "accepted": { "$sum": "$accepted" },
+                "declined": { "$sum": "$declined" },
+                "avg_response_ms": { "$avg": "$response_ms" }
+            }
+        },
+        doc! { "$project": { "_id": 0 } },
+    ]).await?;
+    let response = response.into_iter().next().unwrap_or(ResponseStats {
+        accepted: 0,
+        declined: 0,
+        avg_response_ms: None,
+    });
+
+    let earnings: Vec<EarningsRow> = run_aggregation(db, "bookings", vec![
+        doc! { "$match": { "worker_id": worker_id, "status": "completed", "completed_at": in_range.clone() } },
+        doc! {
+            "$group": {
+                "_id": range.bucket("$completed_at"),
+                "jobs": { "$sum": 1 },
+                "earnings": { "$sum": "$agreed_price" }
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ]).await?;
+    let earnings: Vec<EarningsRow> = earnings
+        .into_iter()
+        .map(|row| EarningsRow { earnings: round2(row.earnings), ..row })
+        .collect();
+    let total_earnings = round2(earnings.iter().map(|row| row.earnings).sum());
+
+    let rating_trend: Vec<RatingRow> = run_aggregation(db, "reviews", vec![
+        doc! { "$match": { "worker_id": worker_id, "created_at": in_range } },
+        doc! {
+            "$group": {
+                "_id": range.bucket("$created_at"),
+                "reviews": { "$sum": 1 },
+                "average_rating": { "$avg": "$rating" }
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ]).await?;
+    let rating_trend: Vec<RatingRow> = rating_trend
+        .into_iter()
+        .map(|row| RatingRow { average_rating: round2(row.average_rating), ..row })
+        .collect();
+
+    let (first_day, last_day) = range.day_bounds();
+    let mut cursor = db.collection::<WorkerDailyStats>("worker_daily_stats")
+        .find(
+            doc! { "worker_id": worker_id, "date": { "$gte": &first_day, "$lte": &last_day } },
+            mongodb::options::FindOptions::builder().sort(doc! { "date": 1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut visibility = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let day = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        visibility.push(serde_json::json!({
+            "date": day.date,
+            "profile_views": day.profile_views,
+            "search_impressions": day.search_impressions
+        }));
+    }
+
+    let rate = |part: i64, whole: i64| if whole > 0 { round2(part as f64 / whole as f64 * 100.0) } else { 0.0 };
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "from": first_day,
+        "to": last_day,
+        "history_days": history_days,
+        "bookings": {
+            "by_status": by_status,
+            "total": by_status.iter().map(|s| s.count).sum::<i64>(),
+            "completion_rate": rate(completed, completed + cancelled_after_accept)
+        },
+        "responsiveness": {
+            "acceptance_rate": rate(response.accepted, response.accepted + response.declined),
+            "avg_response_minutes": response.avg_response_ms.map(|ms| round2(ms / 60_000.0))
+        },
+        "earnings": {
+            "total": total_earnings,
+            "by_period": earnings
+        },
+        "rating": {
+            "current": worker.rating,
+            "total_reviews": worker.total_reviews,
+            "trend": rating_trend
+        },
+        "visibility": visibility,
+        "favorited_by": worker.favorite_count
+    }))))
+}
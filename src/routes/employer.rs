@@ -0,0 +1,421 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, oid::ObjectId};
+use mongodb::options::FindOptions;
+use crate::db::DbConn;
+use crate::models::{
+    CreateEmployerDto, Employer, Job, JobStatus, KycStatusEnum, SubmitEmployerVerificationDto, UpdateEmployerDto,
+};
+use crate::guards::AuthGuard;
+use crate::utils::{ApiResponse, ApiError, validate_cin, validate_gstin, validate_pincode, is_duplicate_key};
+
+/// Documents accepted in one verification submission
+const MAX_BUSINESS_DOCUMENTS: usize = 10;
+
+// ============================================================================
+// EMPLOYER HELPERS
+// ============================================================================
+
+fn clean(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+fn required(value: &str, field: &str) -> Result<String, ApiError> {
+    clean(Some(value)).ok_or_else(|| ApiError::bad_request(format!("{} is required", field)))
+}
+
+fn clean_gstin(gstin: Option<&str>) -> Result<Option<String>, ApiError> {
+    let gstin = clean(gstin).map(|g| g.to_uppercase());
+    if gstin.as_deref().is_some_and(|g| !validate_gstin(g)) {
+        return Err(ApiError::bad_request("Invalid GSTIN"));
+    }
+    Ok(gstin)
+}
+
+fn clean_cin(cin: Option<&str>) -> Result<Option<String>, ApiError> {
+    let cin = clean(cin).map(|c| c.to_uppercase());
+    if cin.as_deref().is_some_and(|c| !validate_cin(c)) {
+        return Err(ApiError::bad_request("Invalid CIN"));
+    }
+    Ok(cin)
+}
+
+fn clean_pincode(pincode: &str) -> Result<String, ApiError> {
+    let pincode = pincode.trim();
+    if !validate_pincode(pincode) {
+        return Err(ApiError::bad_request("Invalid pincode"));
+    }
+    Ok(pincode.to_string())
+}
+
+/// A GSTIN or CIN can belong to only one company
+async fn ensure_registration_unused(
+    db: &DbConn,
+    field: &str,
+    value: &str,
+    except: Option<ObjectId>,
+) -> Result<(), ApiError> {
+    let mut filter = doc! { field: value };
+    if let Some(except) = except {
+        filter.insert("_id", doc! { "$ne": except });
+    }
+
+    let taken = db.collection::<Employer>("employers")
+        .find_one(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if taken.is_some() {
+        return Err(ApiError::bad_request(format!(
+            "Another company is already registered with this {}",
+            field.to_uppercase()
+        )));
+    }
+    Ok(())
+}
+
+async fn find_own_employer(db: &DbConn, user_id: ObjectId) -> Result<Employer, ApiError> {
+    db.collection::<Employer>("employers")
+        .find_one(doc! { "owner_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Company profile not found"))
+}
+
+// ============================================================================
+// EMPLOYER PROFILE ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Employer")]
+#[post("/employer/profile", data = "<dto>")]
+pub async fn create_employer_profile(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateEmployerDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let gstin = clean_gstin(dto.gstin.as_deref())?;
+    let cin = clean_cin(dto.cin.as_deref())?;
+    if let Some(ref gstin) = gstin {
+        ensure_registration_unused(db, "gstin", gstin, None).await?;
+    }
+    if let Some(ref cin) = cin {
+        ensure_registration_unused(db, "cin", cin, None).await?;
+    }
+
+    let now = DateTime::now();
+    let employer = Employer {
+        id: None,
+        owner_id: auth.user_id,
+        company_name: required(&dto.company_name, "Company name")?,
+        logo_url: clean(dto.logo_url.as_deref()),
+        description: clean(dto.description.as_deref()),
+        website: clean(dto.website.as_deref()),
+        industry: required(&dto.industry, "Industry")?,
+        size: dto.size,
+        gstin,
+        cin,
+        address: required(&dto.address, "Address")?,
+        city: required(&dto.city, "City")?,
+        state: required(&dto.state, "State")?,
+        pincode: clean_pincode(&dto.pincode)?,
+        verification_status: KycStatusEnum::Pending,
+        documents: Vec::new(),
+        rejection_reason: None,
+        is_verified: false,
+        verified_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    // The unique index on owner_id settles racing creates
+    let result = db.collection::<Employer>("employers")
+        .insert_one(&employer, None)
+        .await
+        .map_err(|e| {
+            if is_duplicate_key(&e) {
+                ApiError::bad_request("Company profile already exists")
+            } else {
+                ApiError::internal_error(format!("Failed to create company profile: {}", e))
+            }
+        })?;
+
+    // Jobs this user posted before having a company profile now belong to it
+    db.collection::<Job>("jobs")
+        .update_many(
+            doc! { "posted_by": auth.user_id, "employer_id": null },
+            doc! { "$set": { "employer_id": result.inserted_id.clone(), "company": &employer.company_name } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to link company jobs: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Company profile created. Submit business documents to get verified.".to_string(),
+        serde_json::json!({
+            "employer_id": result.inserted_id.as_object_id().map(|id| id.to_hex())
+        }),
+    )))
+}
+
+#[openapi(tag = "Employer")]
+#[get("/employer/profile")]
+pub async fn get_employer_profile(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let employer = find_own_employer(db, auth.user_id).await?;
+    Ok(Json(ApiResponse::success(serde_json::json!(employer))))
+}
+
+#[openapi(tag = "Employer")]
+#[put("/employer/profile", data = "<dto>")]
+pub async fn update_employer_profile(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateEmployerDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let employer = find_own_employer(db, auth.user_id).await?;
+    let now = DateTime::now();
+
+    let mut update_doc = doc! { "updated_at": now };
+    let mut renamed_to = None;
+    let mut identity_changed = false;
+
+    if let Some(ref company_name) = dto.company_name {
+        let company_name = required(company_name, "Company name")?;
+        if company_name != employer.company_name {
+            identity_changed = true;
+            renamed_to = Some(company_name.clone());
+        }
+        update_doc.insert("company_name", company_name);
+    }
+    if let Some(ref gstin) = dto.gstin {
+        let gstin = clean_gstin(Some(gstin))?;
+        if let Some(ref value) = gstin {
+            ensure_registration_unused(db, "gstin", value, employer.id).await?;
+        }
+        identity_changed |= gstin != employer.gstin;
+        update_doc.insert("gstin", gstin);
+    }
+    if let Some(ref cin) = dto.cin {
+        let cin = clean_cin(Some(cin))?;
+        if let Some(ref value) = cin {
+            ensure_registration_unused(db, "cin", value, employer.id).await?;
+        }
+        identity_changed |= cin != employer.cin;
+        update_doc.insert("cin", cin);
+    }
+    // Empty strings clear the optional fields
+    if let Some(ref logo_url) = dto.logo_url {
+        update_doc.insert("logo_url", clean(Some(logo_url)));
+    }
+    if let Some(ref description) = dto.description {
+        update_doc.insert("description", clean(Some(description)));
+    }
+    if let Some(ref website) = dto.website {
+        update_doc.insert("website", clean(Some(website)));
+    }
+    if let Some(ref industry) = dto.industry {
+        update_doc.insert("industry", required(industry, "Industry")?);
+    }
+    if let Some(size) = dto.size {
+        let size = mongodb::bson::to_bson(&size)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+        update_doc.insert("size", size);
+    }
+    if let Some(ref address) = dto.address {
+        update_doc.insert("address", required(address, "Address")?);
+    }
+    if let Some(ref city) = dto.city {
+        update_doc.insert("city", required(city, "City")?);
+    }
+    if let Some(ref state) = dto.state {
+        update_doc.insert("state", required(state, "State")?);
+    }
+    if let Some(ref pincode) = dto.pincode {
+        update_doc.insert("pincode", clean_pincode(pincode)?);
+    }
+
+    // Verification vouches for the legal identity, so changing it needs a fresh review
+    let reverify = identity_changed
+        && matches!(
+            employer.verification_status,
+            KycStatusEnum::Submitted | KycStatusEnum::UnderReview | KycStatusEnum::Approved
+        );
+    if reverify {
+        update_doc.insert("verification_status", KycStatusEnum::Pending.as_str());
+        update_doc.insert("is_verified", false);
+        update_doc.insert("verified_at", None::<DateTime>);
+    }
+
+    db.collection::<Employer>("employers")
+        .update_one(doc! { "_id": employer.id }, doc! { "$set": update_doc }, None)
+        .await
+        .map_err(|e| {
+            if is_duplicate_key(&e) {
+                ApiError::bad_request("Another company is already registered with this GSTIN or CIN")
+            } else {
+                ApiError::internal_error(format!("Failed to update company profile: {}", e))
+            }
+        })?;
+
+    // Jobs carry the company name for search and listings
+    if let Some(company_name) = renamed_to {
+        db.collection::<Job>("jobs")
+            .update_many(
+                doc! { "employer_id": employer.id },
+                doc! { "$set": { "company": company_name } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update company jobs: {}", e)))?;
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": if reverify {
+            "Company profile updated. Submit your business documents again to restore verification."
+        } else {
+            "Company profile updated"
+        },
+        "verification_status": if reverify { KycStatusEnum::Pending } else { employer.verification_status }
+    }))))
+}
+
+/// Submit business documents for verification. Upload each file with `/upload/document` first.
+#[openapi(tag = "Employer")]
+#[post("/employer/verification", data = "<dto>")]
+pub async fn submit_employer_verification(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<SubmitEmployerVerificationDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let employer = find_own_employer(db, auth.user_id).await?;
+
+    match employer.verification_status {
+        KycStatusEnum::Approved => return Err(ApiError::bad_request("Company is already verified")),
+        KycStatusEnum::Submitted | KycStatusEnum::UnderReview => {
+            return Err(ApiError::bad_request("Verification already submitted and under review"));
+        }
+        KycStatusEnum::Pending | KycStatusEnum::Rejected => {}
+    }
+
+    if employer.gstin.is_none() && employer.cin.is_none() {
+        return Err(ApiError::bad_request("Add a GSTIN or CIN to your company profile before verification"));
+    }
+    if dto.documents.is_empty() {
+        return Err(ApiError::bad_request("At least one business document is required"));
+    }
+    if dto.documents.len() > MAX_BUSINESS_DOCUMENTS {
+        return Err(ApiError::bad_request(format!(
+            "At most {} documents can be submitted",
+            MAX_BUSINESS_DOCUMENTS
+        )));
+    }
+
+    let mut documents = Vec::new();
+    for document in &dto.documents {
+        let mut document = document.clone();
+        document.url = required(&document.url, "Document URL")?;
+        document.document_number = clean(document.document_number.as_deref());
+        documents.push(document);
+    }
+    let documents = mongodb::bson::to_bson(&documents)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let result = db.collection::<Employer>("employers")
+        .update_one(
+            doc! {
+                "_id": employer.id,
+                "verification_status": employer.verification_status.as_str()
+            },
+            doc! { "$set": {
+                "verification_status": KycStatusEnum::Submitted.as_str(),
+                "documents": documents,
+                "rejection_reason": null,
+                "updated_at": DateTime::now()
+            } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to submit verification: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Company profile was updated elsewhere, please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Business documents submitted for verification",
+        "verification_status": KycStatusEnum::Submitted
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct CompanyJobsQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Public company page with the company's open jobs
+#[openapi(tag = "Employer")]
+#[get("/employer/profile/<employer_id>?<query..>")]
+pub async fn get_company_page(
+    db: &State<DbConn>,
+    employer_id: String,
+    query: CompanyJobsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&employer_id)
+        .map_err(|_| ApiError::bad_request("Invalid employer ID"))?;
+
+    let employer = db.collection::<Employer>("employers")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Company not found"))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let filter = doc! { "employer_id": object_id, "status": JobStatus::Approved.as_str() };
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "published_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut jobs = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let job = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        jobs.push(job);
+    }
+
+    let total = db.collection::<Job>("jobs")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    // Business documents and review notes are only for the owner and admins
+    let mut company = serde_json::json!(employer);
+    if let Some(map) = company.as_object_mut() {
+        for field in ["owner_id", "documents", "rejection_reason", "verification_status"] {
+            map.remove(field);
+        }
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "company": company,
+        "open_jobs": jobs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
@@ -0,0 +1,346 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, oid::ObjectId};
+use mongodb::options::FindOneOptions;
+use crate::db::DbConn;
+use crate::models::{
+    Booking, CreateBookingDto, FavoriteWorker, RebookFavoriteDto, SaveFavoriteDto, WorkerProfile,
+    MAX_FAVORITE_NOTE_LENGTH,
+};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::run_aggregation;
+use crate::routes::booking::request_booking;
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// FAVORITE HELPERS
+// ============================================================================
+
+fn parse_worker_id(worker_id: &str) -> Result<ObjectId, ApiError> {
+    ObjectId::parse_str(worker_id).map_err(|_| ApiError::bad_request("Invalid worker ID"))
+}
+
+async fn adjust_favorite_count(db: &DbConn, worker_id: ObjectId, by: i64) -> Result<(), ApiError> {
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker_id },
+            doc! { "$inc": { "favorite_count": by } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update favourite count: {}", e)))?;
+    Ok(())
+}
+
+// ============================================================================
+// FAVORITE ENDPOINTS
+// ============================================================================
+
+/// Save a worker, or update the note on one already saved
+#[openapi(tag = "Favorite")]
+#[put("/favorites/<worker_id>", data = "<dto>")]
+pub async fn save_favorite(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    worker_id: String,
+    dto: Json<SaveFavoriteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = parse_worker_id(&worker_id)?;
+
+    let note = dto.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+    if note.as_ref().is_some_and(|n| n.chars().count() > MAX_FAVORITE_NOTE_LENGTH) {
+        return Err(ApiError::bad_request(format!(
+            "Notes can be at most {} characters",
+            MAX_FAVORITE_NOTE_LENGTH
+        )));
+    }
+
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+    if worker.user_id == auth.user_id {
+        return Err(ApiError::bad_request("You cannot favourite yourself"));
+    }
+
+    let now = DateTime::now();
+    let result = db.collection::<FavoriteWorker>("favorite_workers")
+        .update_one(
+            doc! { "customer_id": auth.user_id, "worker_id": object_id },
+            doc! {
+                "$set": { "note": &note, "updated_at": now },
+                "$setOnInsert": { "created_at": now }
+            },
+            mongodb::options::UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save favourite: {}", e)))?;
+
+    // Only a new favourite counts; the unique index stops racing saves from double counting
+    let added = result.upserted_id.is_some();
+    if added {
+        adjust_favorite_count(db, object_id, 1).await?;
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": if added { "Worker saved to favourites" } else { "Favourite updated" },
+        "worker_id": worker_id,
+        "note": note
+    }))))
+}
+
+#[openapi(tag = "Favorite")]
+#[delete("/favorites/<worker_id>")]
+pub async fn remove_favorite(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    worker_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = parse_worker_id(&worker_id)?;
+
+    let result = db.collection::<FavoriteWorker>("favorite_workers")
+        .delete_one(doc! { "customer_id": auth.user_id, "worker_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to remove favourite: {}", e)))?;
+
+    if result.deleted_count == 0 {
+        return Err(ApiError::not_found("Worker is not in your favourites"));
+    }
+    adjust_favorite_count(db, object_id, -1).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Worker removed from favourites",
+        "worker_id": worker_id
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct FavoriteListQuery {
+    pub category: Option<String>,
+    pub available: Option<bool>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Saved workers with their current availability and the last booking with each,
+/// so the app can offer a one-tap re-book
+#[openapi(tag = "Favorite")]
+#[get("/favorites?<query..>")]
+pub async fn get_favorites(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: FavoriteListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut worker_match = doc! {};
+    if let Some(ref category) = query.category {
+        worker_match.insert("worker.categories", category);
+    }
+    if let Some(available) = query.available {
+        worker_match.insert("worker.is_available", available);
+    }
+
+    let result: Vec<mongodb::bson::Document> = run_aggregation(db, "favorite_workers", vec![
+        doc! { "$match": { "customer_id": auth.user_id } },
+        doc! {
+            "$lookup": {
+                "from": "worker_profiles",
+                "localField": "worker_id",
+                "foreignField": "_id",
+                "as": "worker"
+            }
+        },
+        // Profiles deleted since they were saved drop out
+        doc! { "$unwind": "$worker" },
+        doc! { "$match": worker_match },
+        doc! {
+            "$facet": {
+                "favorites": [
+                    { "$sort": { "created_at": -1 } },
+                    { "$skip": skip },
+                    { "$limit": limit },
+                    {
+                        "$lookup": {
+                            "from": "users",
+                            "localField": "worker.user_id",
+                            "foreignField": "_id",
+                            "as": "account"
+                        }
+                    },
+                    {
+                        "$lookup": {
+                            "from": "bookings",
+                            "let": { "worker_id": "$worker_id" },
+                            "pipeline": [
+                                {
+                                    "$match": {
+                                        "$expr": {
+                                            "$and": [
+                                                { "$eq": ["$worker_id", "$$worker_id"] },
+                                                { "$eq": ["$customer_id", auth.user_id] }
+                                            ]
+                                        }
+                                    }
+                                },
+                                { "$sort": { "created_at": -1 } },
+                                { "$limit": 1 }
+                            ],
+                            "as": "last_booking"
+                        }
+                    }
+                ],
+                "total": [
+                    { "$count": "count" }
+                ]
+            }
+        },
+    ]).await?;
+
+    let result = result.into_iter().next().unwrap_or_default();
+    let total = result
+        .get_array("total")
+        .ok()
+        .and_then(|a| a.first())
+        .and_then(|d| d.as_document())
+        .and_then(|d| d.get_i32("count").ok())
+        .unwrap_or(0) as i64;
+
+    let mut favorites = Vec::new();
+    for entry in result.get_array("favorites").map(|a| a.as_slice()).unwrap_or_default() {
+        let Some(entry) = entry.as_document() else {
+            continue;
+        };
+        let Some(worker) = entry
+            .get_document("worker")
+            .ok()
+            .and_then(|d| mongodb::bson::from_document::<WorkerProfile>(d.clone()).ok())
+        else {
+            continue;
+        };
+        let account = entry
+            .get_array("account")
+            .ok()
+            .and_then(|a| a.first())
+            .and_then(|d| d.as_document());
+        let last_booking = entry
+            .get_array("last_booking")
+            .ok()
+            .and_then(|a| a.first())
+            .and_then(|d| d.as_document())
+            .and_then(|d| mongodb::bson::from_document::<Booking>(d.clone()).ok());
+
+        favorites.push(serde_json::json!({
+            "worker_id": worker.id.map(|id| id.to_hex()),
+            "name": account.and_then(|a| a.get_str("name").ok()),
+            "profile_photo": account.and_then(|a| a.get_str("profile_photo").ok()),
+            "categories": worker.categories,
+            "subcategories": worker.subcategories,
+            "hourly_rate": worker.hourly_rate,
+            "rating": worker.rating,
+            "total_reviews": worker.total_reviews,
+            "is_verified": worker.is_verified,
+            "is_available": worker.is_available,
+            "accepting_bookings": worker.accepting_bookings.unwrap_or(worker.is_available),
+            "note": entry.get_str("note").ok(),
+            "saved_at": entry.get_datetime("created_at").ok(),
+            "last_booking": last_booking.map(|b| serde_json::json!({
+                "booking_id": b.id.map(|id| id.to_hex()),
+                "category": b.category,
+                "subcategory": b.subcategory,
+                "address": b.address,
+                "status": b.status,
+                "requested_at": b.requested_at
+            }))
+        }));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "favorites": favorites,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// Request a new booking with a saved worker, reusing the last booking's details
+#[openapi(tag = "Favorite")]
+#[post("/favorites/<worker_id>/book", data = "<dto>")]
+pub async fn rebook_favorite(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    worker_id: String,
+    dto: Json<RebookFavoriteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = parse_worker_id(&worker_id)?;
+
+    db.collection::<FavoriteWorker>("favorite_workers")
+        .find_one(doc! { "customer_id": auth.user_id, "worker_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker is not in your favourites"))?;
+
+    let last = db.collection::<Booking>("bookings")
+        .find_one(
+            doc! { "customer_id": auth.user_id, "worker_id": object_id },
+            FindOneOptions::builder().sort(doc! { "created_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let dto = dto.into_inner();
+    let missing = |field: &str| ApiError::bad_request(format!("No earlier booking to copy the {} from, please provide it", field));
+
+    let category = match dto.category {
+        Some(category) => category,
+        None => last.as_ref().map(|b| b.category.clone()).ok_or_else(|| missing("category"))?,
+    };
+    let subcategory = dto.subcategory.or_else(|| {
+        // The old subcategory only makes sense for the same category
+        last.as_ref()
+            .filter(|b| b.category.eq_ignore_ascii_case(&category))
+            .and_then(|b| b.subcategory.clone())
+    });
+    let address = match dto.address {
+        Some(address) => address,
+        None => last.as_ref().map(|b| b.address.clone()).ok_or_else(|| missing("address"))?,
+    };
+    let (latitude, longitude) = match (dto.latitude, dto.longitude) {
+        (Some(latitude), Some(longitude)) => (latitude, longitude),
+        _ => last
+            .as_ref()
+            .map(|b| (b.location.coordinates[1], b.location.coordinates[0]))
+            .ok_or_else(|| missing("location"))?,
+    };
+
+    let booking_dto = CreateBookingDto {
+        worker_id,
+        category,
+        subcategory,
+        description: dto.description.or_else(|| last.as_ref().and_then(|b| b.description.clone())),
+        address,
+        latitude,
+        longitude,
+        preferred_start: dto.preferred_start,
+        estimated_hours: dto.estimated_hours,
+    };
+    let (booking_id, booking) = request_booking(db, auth.user_id, &booking_dto).await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Booking requested successfully".to_string(),
+        serde_json::json!({
+            "booking_id": booking_id.to_hex(),
+            "status": booking.status,
+            "agreed_price": booking.agreed_price,
+            "category": booking.category,
+            "address": booking.address
+        })
+    )))
+}
@@ -0,0 +1,347 @@
+use rocket::serde::json::Json;
+use rocket::http::ContentType;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use log::error;
+use crate::config::Config;
+use crate::db::DbConn;
+use crate::models::{
+    Invoice, InvoiceLineItem, InvoiceParty, InvoiceResponse, Kyc, Payment, Subscription,
+    SubscriptionType, User,
+};
+use crate::guards::AuthGuard;
+use crate::services::{EmailService, InvoiceService};
+use crate::utils::{ApiResponse, ApiError, is_duplicate_key};
+
+// ============================================================================
+// INVOICE GENERATION
+// ============================================================================
+
+/// Next invoice number in the current financial year, e.g. "MS/2026-27/000042"
+async fn next_invoice_number(db: &DbConn, issued_at: DateTime) -> Result<String, ApiError> {
+    let financial_year = InvoiceService::financial_year(issued_at);
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+
+    let counter = db
+        .collection::<Document>("counters")
+        .find_one_and_update(
+            doc! { "_id": format!("invoice:{}", financial_year) },
+            doc! { "$inc": { "seq": 1_i64 } },
+            options,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to allocate invoice number: {}", e)))?
+        .ok_or_else(|| ApiError::internal_error("Failed to allocate invoice number"))?;
+
+    let seq = counter.get_i64("seq").unwrap_or_default();
+    Ok(format!("{}/{}/{:06}", Config::invoice_prefix(), financial_year, seq))
+}
+
+fn plan_description(subscription: Option<&Subscription>) -> String {
+    match subscription {
+        Some(sub) => {
+            let audience = match sub.subscription_type {
+                SubscriptionType::Worker => "Worker",
+                SubscriptionType::JobSeeker => "Job Seeker",
+            };
+            let mut plan = sub.plan_name.clone();
+            if let Some(first) = plan.get_mut(0..1) {
+                first.make_ascii_uppercase();
+            }
+            format!("Mento Services {} {} subscription", audience, plan)
+        }
+        None => "Mento Services subscription".to_string(),
+    }
+}
+
+/// Create the GST invoice for a captured payment. Returns the existing invoice
+/// when one was already issued for this payment.
+pub async fn issue_invoice(db: &DbConn, payment: &Payment) -> Result<Invoice, ApiError> {
+    let payment_id = payment
+        .id
+        .ok_or_else(|| ApiError::internal_error("Payment has no id"))?;
+
+    let invoices = db.collection::<Invoice>("invoices");
+    if let Some(existing) = invoices
+        .find_one(doc! { "payment_id": payment_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+    {
+        return assign_invoice_number(db, existing).await;
+    }
+
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": payment.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    // KYC carries the billing address and the state that decides the GST split
+    let kyc = db
+        .collection::<Kyc>("kycs")
+        .find_one(doc! { "user_id": payment.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let subscription = match payment.subscription_id {
+        Some(sub_id) => db
+            .collection::<Subscription>("subscriptions")
+            .find_one(doc! { "_id": sub_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?,
+        None => None,
+    };
+
+    let supplier_state = Config::company_state();
+    let customer_state = kyc.as_ref().map(|k| k.state.clone());
+    let supply_type = InvoiceService::supply_type(&supplier_state, customer_state.as_deref());
+    let gst_rate = Config::gst_rate();
+    let breakdown = InvoiceService::gst_breakdown(payment.amount, gst_rate, &supply_type);
+
+    let issued_at = DateTime::now();
+    let gstin = Config::company_gstin();
+    let address = Config::company_address();
+
+    let invoice = Invoice {
+        id: None,
+        invoice_number: String::new(), // Assigned once this invoice holds the payment
+        user_id: payment.user_id,
+        payment_id,
+        subscription_id: payment.subscription_id,
+        razorpay_payment_id: payment.razorpay_payment_id.clone(),
+        supplier: InvoiceParty {
+            name: Config::company_name(),
+            gstin: (!gstin.is_empty()).then_some(gstin),
+            address: (!address.is_empty()).then_some(address),
+            state: Some(supplier_state.clone()),
+            email: None,
+        },
+        customer: InvoiceParty {
+            name: kyc
+                .as_ref()
+                .map(|k| k.full_name.clone())
+                .or_else(|| user.name.clone())
+                .unwrap_or_else(|| user.mobile.clone()),
+            gstin: None,
+            address: kyc
+                .as_ref()
+                .map(|k| format!("{}, {} - {}", k.address, k.city, k.pincode)),
+            state: customer_state.clone(),
+            email: user.email.clone(),
+        },
+        place_of_supply: customer_state.unwrap_or(supplier_state),
+        supply_type,
+        items: vec![InvoiceLineItem {
+            description: plan_description(subscription.as_ref()),
+            sac_code: Config::invoice_sac_code(),
+            quantity: 1,
+            taxable_value: breakdown.taxable_value,
+        }],
+        gst_rate,
+        taxable_value: breakdown.taxable_value,
+        cgst: breakdown.cgst,
+        sgst: breakdown.sgst,
+        igst: breakdown.igst,
+        total: payment.amount,
+        currency: payment.currency.clone(),
+        issued_at,
+        emailed_at: None,
+    };
+
+    // The unique index on payment_id decides which caller issues the invoice, so a race
+    // never burns an invoice number
+    let invoice = match invoices.insert_one(&invoice, None).await {
+        Ok(result) => Invoice {
+            id: result.inserted_id.as_object_id(),
+            ..invoice
+        },
+        Err(e) if is_duplicate_key(&e) => invoices
+            .find_one(doc! { "payment_id": payment_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+            .ok_or_else(|| ApiError::internal_error("Invoice for this payment disappeared"))?,
+        Err(e) => return Err(ApiError::internal_error(format!("Failed to store invoice: {}", e))),
+    };
+
+    assign_invoice_number(db, invoice).await
+}
+
+/// Give a claimed invoice its sequence number. An invoice left without one, because numbering
+/// failed earlier, is finished by the next call for the same payment.
+async fn assign_invoice_number(db: &DbConn, invoice: Invoice) -> Result<Invoice, ApiError> {
+    if !invoice.invoice_number.is_empty() {
+        return Ok(invoice);
+    }
+
+    let invoice_number = next_invoice_number(db, invoice.issued_at).await?;
+    let numbered = db
+        .collection::<Invoice>("invoices")
+        .find_one_and_update(
+            doc! { "_id": invoice.id, "invoice_number": "" },
+            doc! { "$set": { "invoice_number": &invoice_number } },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to number invoice: {}", e)))?;
+
+    match numbered {
+        Some(invoice) => Ok(invoice),
+        // Numbered concurrently by another caller
+        None => db
+            .collection::<Invoice>("invoices")
+            .find_one(doc! { "_id": invoice.id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+            .ok_or_else(|| ApiError::not_found("Invoice not found")),
+    }
+}
+
+/// Email the invoice to the customer in the background, if they have an email on file
+fn email_invoice(db: &DbConn, invoice: Invoice) {
+    let Some(email) = invoice.customer.email.clone() else {
+        return;
+    };
+    let db = db.clone();
+
+    rocket::tokio::spawn(async move {
+        let html = match InvoiceService::render_html(&invoice) {
+            Ok(html) => html,
+            Err(e) => {
+                error!("Failed to render invoice {}: {}", invoice.invoice_number, e);
+                return;
+            }
+        };
+        let pdf = match InvoiceService::render_pdf(&invoice) {
+            Ok(pdf) => pdf,
+            Err(e) => {
+                error!("Failed to render invoice PDF {}: {}", invoice.invoice_number, e);
+                return;
+            }
+        };
+
+        if EmailService::send_invoice_email(&email, &invoice.invoice_number, &html, pdf).await {
+            let _ = db
+                .collection::<Invoice>("invoices")
+                .update_one(
+                    doc! { "_id": invoice.id },
+                    doc! { "$set": { "emailed_at": DateTime::now() } },
+                    None,
+                )
+                .await;
+        }
+    });
+}
+
+/// Issue and email the invoice for a captured payment. Failures are logged and
+/// never fail the payment flow itself; the invoice can be reissued later.
+pub async fn invoice_payment(db: &DbConn, payment: &Payment) {
+    match issue_invoice(db, payment).await {
+        Ok(invoice) if invoice.emailed_at.is_none() => email_invoice(db, invoice),
+        Ok(_) => {}
+        Err(e) => error!("Failed to issue invoice for payment {:?}: {}", payment.id, e.message),
+    }
+}
+
+// ============================================================================
+// INVOICE ROUTES
+// ============================================================================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct InvoiceListQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/invoices?<query..>")]
+pub async fn get_my_invoices(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: InvoiceListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    // Invoices still waiting on a number aren't issued yet
+    let filter = doc! { "user_id": auth.user_id, "invoice_number": { "$ne": "" } };
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "issued_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Invoice>("invoices")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut invoices = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let invoice = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        invoices.push(InvoiceResponse::from(invoice));
+    }
+
+    let total = db.collection::<Invoice>("invoices")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "invoices": invoices,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+async fn find_user_invoice(db: &DbConn, user_id: ObjectId, invoice_id: &str) -> Result<Invoice, ApiError> {
+    let invoice_oid = ObjectId::parse_str(invoice_id)
+        .map_err(|_| ApiError::bad_request("Invalid invoice ID"))?;
+
+    db.collection::<Invoice>("invoices")
+        .find_one(doc! { "_id": invoice_oid, "user_id": user_id, "invoice_number": { "$ne": "" } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Invoice not found"))
+}
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/invoices/<invoice_id>")]
+pub async fn get_invoice_html(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    invoice_id: String,
+) -> Result<(ContentType, String), ApiError> {
+    let invoice = find_user_invoice(db, auth.user_id, &invoice_id).await?;
+    let html = InvoiceService::render_html(&invoice)
+        .map_err(|e| ApiError::internal_error(format!("Failed to render invoice: {}", e)))?;
+
+    Ok((ContentType::HTML, html))
+}
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/invoices/<invoice_id>/pdf")]
+pub async fn get_invoice_pdf(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    invoice_id: String,
+) -> Result<(ContentType, Vec<u8>), ApiError> {
+    let invoice = find_user_invoice(db, auth.user_id, &invoice_id).await?;
+    let pdf = InvoiceService::render_pdf(&invoice)
+        .map_err(|e| ApiError::internal_error(format!("Failed to render invoice PDF: {}", e)))?;
+
+    Ok((ContentType::PDF, pdf))
+}
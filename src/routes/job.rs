@@ -2,38 +2,39 @@ use rocket::serde::json::Json;
 use rocket::State;
 use rocket::form::FromForm;
 use rocket_okapi::openapi;
-use mongodb::bson::{doc, DateTime};
+use mongodb::bson::{doc, DateTime, Document};
 use mongodb::options::FindOptions;
 use crate::db::DbConn;
-use crate::models::{Subscription, JobSeekerSubscriptionPlan, SubscriptionType, SubscriptionStatus, JobSeekerProfile, CreateJobSeekerProfileDto, UpdateJobSeekerProfileDto};
+use crate::models::{Subscription, JobSeekerSubscriptionPlan, SubscriptionType, SubscriptionStatus, JobSeekerProfile, CreateJobSeekerProfileDto, UpdateJobSeekerProfileDto, PaymentKind, Job, JobStatus, JobType, CreateJobDto, UpdateJobDto, JobSearchCriteria, JobSeekerSearchCriteria, ProfileKind, Employer};
 use crate::guards::{AuthGuard, KycGuard};
 use crate::utils::{ApiResponse, ApiError};
-use crate::services::RazorpayService;
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use crate::services::payment_gateway;
+use crate::routes::subscription::{record_order_payment, record_captured_payment};
+use crate::routes::profile_view::record_profile_view;
+use crate::routes::coupon::{reserve_coupon, attach_coupon_redemption, release_coupon_redemption, confirm_coupon_redemption};
 use mongodb::bson::oid::ObjectId;
+use std::net::IpAddr;
 
 // ============================================================================
 // JOB SEEKER SUBSCRIPTION ENDPOINTS
 // ============================================================================
 
 #[openapi(tag = "JobSeekerSubscription")]
-#[post("/job-seeker/subscription/create/<plan_name>")]
+#[post("/job-seeker/subscription/create/<plan_name>?<coupon>")]
 pub async fn create_job_seeker_subscription(
     db: &State<DbConn>,
     auth: AuthGuard,
     plan_name: String,
+    coupon: Option<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     // Validate plan and get price
-    let (price, plan_type) = match plan_name.to_lowercase().as_str() {
-        "basic" => (0.5, JobSeekerSubscriptionPlan::Basic),
-        "premium" => (1.5, JobSeekerSubscriptionPlan::Premium),
-        _ => return Err(ApiError::bad_request("Invalid plan. Choose 'basic' or 'premium'")),
-    };
+    let plan = SubscriptionType::JobSeeker
+        .find_plan(&plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan. Choose 'basic' or 'premium'"))?;
 
     let now = DateTime::now();
     let expires_at = DateTime::from_millis(
-        chrono::Utc::now().timestamp_millis() + 365 * 24 * 60 * 60 * 1000, // 1 year
+        chrono::Utc::now().timestamp_millis() + plan.duration_days * 24 * 60 * 60 * 1000,
     );
 
     // Check if user already has an active subscription
@@ -54,23 +55,46 @@ pub async fn create_job_seeker_subscription(
         return Err(ApiError::bad_request("You already have an active job seeker subscription"));
     }
 
+    // Reserve the coupon before creating the order so its limits hold
+    let applied_coupon = match coupon.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(code) => Some(reserve_coupon(db, code, auth.user_id, &SubscriptionType::JobSeeker, plan).await?),
+        None => None,
+    };
+    let discount = applied_coupon.as_ref().map_or(0.0, |c| c.discount);
+    let price = applied_coupon.as_ref().map_or(plan.price, |c| c.final_price);
+
     // Create Razorpay order
-    let order = RazorpayService::create_order(price as i64)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Failed to create payment order: {}", e)))?;
+    let order = match payment_gateway().create_order(price).await {
+        Ok(order) => order,
+        Err(e) => {
+            if let Some(ref applied) = applied_coupon {
+                release_coupon_redemption(db, applied.redemption_id).await?;
+            }
+            return Err(ApiError::internal_error(format!("Failed to create payment order: {}", e)));
+        }
+    };
 
-    // Create subscription with cancelled status (pending payment)
+    // Create subscription with pending status
     let subscription = Subscription {
         id: None,
         user_id: auth.user_id,
         subscription_type: SubscriptionType::JobSeeker,
-        plan_name: plan_name.clone(),
+        plan_name: plan.name.to_string(),
         price,
-        status: SubscriptionStatus::Cancelled,
+        status: SubscriptionStatus::Pending,
         starts_at: now,
         expires_at,
         auto_renew: false,
         payment_id: None,
+        cancel_at_period_end: false,
+        cancelled_at: None,
+        cancellation_reason: None,
+        razorpay_subscription_id: None,
+        renewal_status: None,
+        renewal_attempts: 0,
+        pending_change: None,
+        coupon_code: applied_coupon.as_ref().map(|c| c.code.clone()),
+        discount,
         created_at: now,
         updated_at: now,
     };
@@ -81,16 +105,24 @@ pub async fn create_job_seeker_subscription(
         .await
         .map_err(|e| ApiError::internal_error(format!("Failed to create subscription: {}", e)))?;
 
-    let subscription_id = sub_res
+    let subscription_oid = sub_res
         .inserted_id
         .as_object_id()
-        .ok_or_else(|| ApiError::internal_error("Invalid subscription ID"))?
-        .to_hex();
+        .ok_or_else(|| ApiError::internal_error("Invalid subscription ID"))?;
+
+    if let Some(ref applied) = applied_coupon {
+        attach_coupon_redemption(db, applied.redemption_id, subscription_oid).await?;
+    }
+
+    record_order_payment(db, auth.user_id, subscription_oid, PaymentKind::Purchase, &order, price).await?;
 
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "subscription_id": subscription_id,
+        "subscription_id": subscription_oid.to_hex(),
         "order": order,
-        "plan_name": plan_name,
+        "plan_name": plan.name,
+        "original_price": plan.price,
+        "discount": discount,
+        "coupon": applied_coupon.as_ref().map(|c| &c.code),
         "price": price
     }))))
 }
@@ -111,18 +143,11 @@ pub async fn verify_job_seeker_payment(
     dto: Json<VerifyJobSeekerPaymentDto>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     // Verify Razorpay signature
-    let secret = std::env::var("RAZORPAY_KEY_SECRET")
-        .map_err(|_| ApiError::internal_error("Missing Razorpay secret"))?;
-
-    let payload = format!("{}|{}", dto.razorpay_order_id, dto.razorpay_payment_id);
-
-    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
-        .map_err(|_| ApiError::internal_error("Invalid HMAC key"))?;
+    let valid = payment_gateway()
+        .verify_payment_signature(&dto.razorpay_order_id, &dto.razorpay_payment_id, &dto.razorpay_signature)
+        .map_err(ApiError::internal_error)?;
 
-    mac.update(payload.as_bytes());
-    let expected_signature = hex::encode(mac.finalize().into_bytes());
-
-    if expected_signature != dto.razorpay_signature {
+    if !valid {
         return Err(ApiError::bad_request("Invalid payment signature"));
     }
 
@@ -154,6 +179,9 @@ pub async fn verify_job_seeker_payment(
         return Err(ApiError::not_found("Subscription not found"));
     }
 
+    record_captured_payment(db, &dto.razorpay_order_id, &dto.razorpay_payment_id).await?;
+    confirm_coupon_redemption(db, sub_id).await?;
+
     // Get the subscription details
     let subscription = db
         .collection::<Subscription>("subscriptions")
@@ -200,7 +228,9 @@ pub async fn get_job_seeker_subscription_status(
                 "plan_name": sub.plan_name,
                 "status": format!("{:?}", sub.status),
                 "expires_at": sub.expires_at,
-                "auto_renew": sub.auto_renew
+                "auto_renew": sub.auto_renew,
+                "renewal_status": sub.renewal_status,
+                "cancel_at_period_end": sub.cancel_at_period_end
             }
         }))))
     } else {
@@ -294,6 +324,7 @@ pub async fn create_job_seeker_profile(
         subscription_plan,
         subscription_expires_at: Some(subscription.expires_at),
         is_verified: false,
+        verified_at: None,
         is_available: true,
         profile_views: 0,
         applications_count: 0,
@@ -335,21 +366,13 @@ pub async fn get_job_seeker_profile(
 #[get("/job-seeker/profile/<profile_id>")]
 pub async fn get_job_seeker_profile_by_id(
     db: &State<DbConn>,
+    viewer: Option<AuthGuard>,
+    ip: Option<IpAddr>,
     profile_id: String,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     let object_id = ObjectId::parse_str(&profile_id)
         .map_err(|_| ApiError::bad_request("Invalid profile ID"))?;
 
-    // Increment profile views
-    db.collection::<JobSeekerProfile>("job_seeker_profiles")
-        .update_one(
-            doc! { "_id": object_id },
-            doc! { "$inc": { "profile_views": 1 } },
-            None,
-        )
-        .await
-        .ok();
-
     let profile = db
         .collection::<JobSeekerProfile>("job_seeker_profiles")
         .find_one(doc! { "_id": object_id }, None)
@@ -357,6 +380,8 @@ pub async fn get_job_seeker_profile_by_id(
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
         .ok_or_else(|| ApiError::not_found("Job seeker profile not found"))?;
 
+    record_profile_view(db, ProfileKind::JobSeeker, object_id, profile.user_id, viewer.map(|v| v.user_id), ip);
+
     Ok(Json(ApiResponse::success(serde_json::json!(profile))))
 }
 
@@ -464,45 +489,64 @@ pub struct SearchJobSeekersQuery {
     pub limit: Option<i64>,
 }
 
-#[openapi(tag = "JobSeeker")]
-#[get("/job-seeker/search?<query..>")]
-pub async fn search_job_seekers(
-    db: &State<DbConn>,
-    query: SearchJobSeekersQuery,
-) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    let page = query.page.unwrap_or(1).max(1);
-    let limit = query.limit.unwrap_or(20).min(100);
-    let skip = (page - 1) * limit;
-
+/// Filter for verified, available job seekers. Shared by the search endpoint and saved search alerts.
+pub(crate) fn job_seeker_search_filter(criteria: &JobSeekerSearchCriteria) -> Document {
     let mut filter = doc! {
         "is_available": true,
         "is_verified": true,
     };
 
-    if let Some(skills_str) = query.skills {
-        let skills: Vec<&str> = skills_str.split(',').map(|s| s.trim()).collect();
-        filter.insert("skills", doc! { "$in": skills });
+    if let Some(ref skills_str) = criteria.skills {
+        let skills: Vec<&str> = skills_str.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+        if !skills.is_empty() {
+            filter.insert("skills", doc! { "$in": skills });
+        }
     }
 
-    if let Some(category) = query.category {
+    if let Some(ref category) = criteria.category {
         filter.insert("preferred_categories", category);
     }
 
-    if let Some(job_type) = query.job_type {
+    if let Some(ref job_type) = criteria.job_type {
         filter.insert("preferred_job_types", job_type);
     }
 
-    if let Some(location) = query.location {
+    if let Some(ref location) = criteria.location {
         filter.insert("preferred_locations", location);
     }
 
-    if let Some(min_exp) = query.min_experience {
-        filter.insert("experience_years", doc! { "$gte": min_exp });
+    let mut experience = Document::new();
+    if let Some(min_exp) = criteria.min_experience {
+        experience.insert("$gte", min_exp);
     }
-
-    if let Some(max_exp) = query.max_experience {
-        filter.insert("experience_years", doc! { "$lte": max_exp });
+    if let Some(max_exp) = criteria.max_experience {
+        experience.insert("$lte", max_exp);
     }
+    if !experience.is_empty() {
+        filter.insert("experience_years", experience);
+    }
+
+    filter
+}
+
+#[openapi(tag = "JobSeeker")]
+#[get("/job-seeker/search?<query..>")]
+pub async fn search_job_seekers(
+    db: &State<DbConn>,
+    query: SearchJobSeekersQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let filter = job_seeker_search_filter(&JobSeekerSearchCriteria {
+        skills: query.skills,
+        category: query.category,
+        min_experience: query.min_experience,
+        max_experience: query.max_experience,
+        location: query.location,
+        job_type: query.job_type,
+    });
 
     let find_options = FindOptions::builder()
         .skip(skip as u64)
@@ -577,4 +621,443 @@ pub async fn delete_job_seeker_profile(
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Job seeker profile deactivated successfully"
     }))))
-}
\ No newline at end of file
+}
+// ============================================================================
+// JOB POSTING ENDPOINTS
+// ============================================================================
+
+fn validate_salary(salary_min: Option<f64>, salary_max: Option<f64>) -> Result<(), ApiError> {
+    if salary_min.is_some_and(|s| s < 0.0) || salary_max.is_some_and(|s| s < 0.0) {
+        return Err(ApiError::bad_request("Salary cannot be negative"));
+    }
+    if let (Some(min), Some(max)) = (salary_min, salary_max)
+        && min > max
+    {
+        return Err(ApiError::bad_request("Minimum salary cannot be above the maximum"));
+    }
+    Ok(())
+}
+
+fn clean_list(values: &[String]) -> Vec<String> {
+    values.iter().map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect()
+}
+
+async fn find_own_job(db: &DbConn, job_id: &str, user_id: ObjectId) -> Result<Job, ApiError> {
+    let object_id = ObjectId::parse_str(job_id)
+        .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": object_id, "posted_by": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))
+}
+
+/// Post a job. It stays `pending` until an admin approves it.
+#[openapi(tag = "Job")]
+#[post("/job/create", data = "<dto>")]
+pub async fn create_job(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateJobDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if dto.title.trim().is_empty() {
+        return Err(ApiError::bad_request("Job title is required"));
+    }
+    if dto.description.trim().is_empty() {
+        return Err(ApiError::bad_request("Job description is required"));
+    }
+    if dto.location.trim().is_empty() {
+        return Err(ApiError::bad_request("Job location is required"));
+    }
+    if dto.category.trim().is_empty() {
+        return Err(ApiError::bad_request("Job category is required"));
+    }
+    if dto.experience_min.is_some_and(|e| e < 0) {
+        return Err(ApiError::bad_request("Experience cannot be negative"));
+    }
+    validate_salary(dto.salary_min, dto.salary_max)?;
+
+    let employer = db.collection::<Employer>("employers")
+        .find_one(doc! { "owner_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Create your company profile before posting jobs"))?;
+
+    let now = DateTime::now();
+    let job = Job {
+        id: None,
+        title: dto.title.trim().to_string(),
+        company: Some(employer.company_name),
+        description: Some(dto.description.trim().to_string()),
+        location: Some(dto.location.trim().to_string()),
+        job_type: Some(dto.job_type),
+        category: Some(dto.category.trim().to_string()),
+        salary_min: dto.salary_min,
+        salary_max: dto.salary_max,
+        requirements: dto.requirements.as_deref().map(clean_list),
+        skills: dto.skills.as_deref().map(clean_list).unwrap_or_default(),
+        experience_min: dto.experience_min,
+        status: JobStatus::Pending,
+        rejection_reason: None,
+        applications_count: 0,
+        posted_by: Some(auth.user_id),
+        employer_id: employer.id,
+        published_at: None,
+        closed_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db.collection::<Job>("jobs")
+        .insert_one(&job, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create job: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Job submitted for review".to_string(),
+        serde_json::json!({
+            "job_id": result.inserted_id.as_object_id().unwrap().to_hex(),
+            "status": job.status
+        }),
+    )))
+}
+
+/// Edit one of my jobs. Edits to a live or rejected post send it back to moderation.
+#[openapi(tag = "Job")]
+#[put("/job/<job_id>", data = "<dto>")]
+pub async fn update_job(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    dto: Json<UpdateJobDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    if job.status == JobStatus::Closed {
+        return Err(ApiError::bad_request("Closed jobs cannot be edited"));
+    }
+
+    let mut update_doc = doc! {};
+
+    if let Some(ref title) = dto.title {
+        if title.trim().is_empty() {
+            return Err(ApiError::bad_request("Job title cannot be empty"));
+        }
+        update_doc.insert("title", title.trim());
+    }
+    if let Some(ref description) = dto.description {
+        if description.trim().is_empty() {
+            return Err(ApiError::bad_request("Job description cannot be empty"));
+        }
+        update_doc.insert("description", description.trim());
+    }
+    if let Some(ref location) = dto.location {
+        if location.trim().is_empty() {
+            return Err(ApiError::bad_request("Job location cannot be empty"));
+        }
+        update_doc.insert("location", location.trim());
+    }
+    if let Some(job_type) = dto.job_type {
+        update_doc.insert("job_type", job_type.as_str());
+    }
+    if let Some(ref category) = dto.category {
+        if category.trim().is_empty() {
+            return Err(ApiError::bad_request("Job category cannot be empty"));
+        }
+        update_doc.insert("category", category.trim());
+    }
+    if dto.salary_min.is_some() || dto.salary_max.is_some() {
+        let salary_min = dto.salary_min.or(job.salary_min);
+        let salary_max = dto.salary_max.or(job.salary_max);
+        validate_salary(salary_min, salary_max)?;
+        update_doc.insert("salary_min", salary_min);
+        update_doc.insert("salary_max", salary_max);
+    }
+    if let Some(ref requirements) = dto.requirements {
+        update_doc.insert("requirements", clean_list(requirements));
+    }
+    if let Some(ref skills) = dto.skills {
+        update_doc.insert("skills", clean_list(skills));
+    }
+    if let Some(experience) = dto.experience_min {
+        if experience < 0 {
+            return Err(ApiError::bad_request("Experience cannot be negative"));
+        }
+        update_doc.insert("experience_min", experience);
+    }
+
+    if update_doc.is_empty() {
+        return Err(ApiError::bad_request("Nothing to update"));
+    }
+
+    let status = match job.status {
+        JobStatus::Approved | JobStatus::Rejected => JobStatus::Pending,
+        other => other,
+    };
+    update_doc.insert("status", status.as_str());
+    update_doc.insert("rejection_reason", mongodb::bson::Bson::Null);
+    update_doc.insert("updated_at", DateTime::now());
+
+    let result = db.collection::<Job>("jobs")
+        .update_one(
+            doc! { "_id": job.id, "status": job.status.as_str() },
+            doc! { "$set": update_doc },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else, please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": if status == job.status { "Job updated" } else { "Job updated and sent for review" },
+        "status": status
+    }))))
+}
+
+#[openapi(tag = "Job")]
+#[post("/job/<job_id>/close")]
+pub async fn close_job(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    if job.status == JobStatus::Closed {
+        return Err(ApiError::bad_request("Job is already closed"));
+    }
+
+    let now = DateTime::now();
+    let result = db.collection::<Job>("jobs")
+        .update_one(
+            doc! { "_id": job.id, "status": job.status.as_str() },
+            doc! { "$set": { "status": JobStatus::Closed.as_str(), "closed_at": now, "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to close job: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else, please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Job closed",
+        "status": JobStatus::Closed
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct MyJobsQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Every job I posted, in any status
+#[openapi(tag = "Job")]
+#[get("/job/my/posted?<query..>")]
+pub async fn get_my_jobs(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: MyJobsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "posted_by": auth.user_id };
+    if let Some(ref status) = query.status {
+        let status = JobStatus::parse(status)
+            .ok_or_else(|| ApiError::bad_request("Invalid job status"))?;
+        filter.insert("status", status.as_str());
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut jobs = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let job = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        jobs.push(job);
+    }
+
+    let total = db.collection::<Job>("jobs")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "jobs": jobs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct SearchJobsQuery {
+    pub q: Option<String>,
+    pub category: Option<String>,
+    pub job_type: Option<String>,
+    pub location: Option<String>,
+    pub min_salary: Option<f64>,
+    pub max_salary: Option<f64>,
+    pub sort: Option<String>, // "newest" (default) or "salary"
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Filter for approved jobs. Shared by the public feed and saved search alerts.
+pub(crate) fn job_search_filter(criteria: &JobSearchCriteria) -> Result<Document, ApiError> {
+    let mut filter = doc! { "status": JobStatus::Approved.as_str() };
+    if let Some(text) = criteria.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        filter.insert("$text", doc! { "$search": text });
+    }
+    if let Some(ref category) = criteria.category {
+        filter.insert("category", category);
+    }
+    if let Some(ref job_type) = criteria.job_type {
+        let job_type = JobType::parse(job_type)
+            .ok_or_else(|| ApiError::bad_request("Job type must be fulltime, parttime, contract or freelance"))?;
+        filter.insert("job_type", job_type.as_str());
+    }
+    if let Some(location) = criteria.location.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        filter.insert("location", doc! { "$regex": regex::escape(location), "$options": "i" });
+    }
+
+    // A job matches a salary range when the ranges overlap
+    let mut salary = Vec::new();
+    if let Some(min_salary) = criteria.min_salary {
+        salary.push(doc! { "$or": [
+            { "salary_max": { "$gte": min_salary } },
+            { "salary_max": null, "salary_min": { "$gte": min_salary } }
+        ] });
+    }
+    if let Some(max_salary) = criteria.max_salary {
+        salary.push(doc! { "salary_min": { "$lte": max_salary } });
+    }
+    if !salary.is_empty() {
+        filter.insert("$and", salary);
+    }
+
+    Ok(filter)
+}
+
+/// Public job feed. Only approved jobs are listed.
+#[openapi(tag = "Job")]
+#[get("/job/search?<query..>")]
+pub async fn search_jobs(
+    db: &State<DbConn>,
+    query: SearchJobsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let criteria = JobSearchCriteria {
+        q: query.q,
+        category: query.category,
+        job_type: query.job_type,
+        location: query.location,
+        min_salary: query.min_salary,
+        max_salary: query.max_salary,
+    };
+    let filter = job_search_filter(&criteria)?;
+    let text = criteria.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    let sort = match query.sort.as_deref() {
+        None | Some("newest") if text.is_some() => doc! { "score": { "$meta": "textScore" }, "published_at": -1 },
+        None | Some("newest") => doc! { "published_at": -1 },
+        Some("salary") => doc! { "salary_max": -1, "salary_min": -1, "published_at": -1 },
+        Some(_) => return Err(ApiError::bad_request("Sort must be newest or salary")),
+    };
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(sort)
+        .build();
+
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut jobs = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let job = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        jobs.push(job);
+    }
+
+    let total = db.collection::<Job>("jobs")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "jobs": jobs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Job")]
+#[get("/job/<job_id>")]
+pub async fn get_job(
+    db: &State<DbConn>,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&job_id)
+        .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    // Pending, rejected and closed posts are only visible to their employer
+    let job = db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": object_id, "status": JobStatus::Approved.as_str() }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+
+    let employer = match job.employer_id {
+        Some(employer_id) => db.collection::<Employer>("employers")
+            .find_one(doc! { "_id": employer_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?,
+        None => None,
+    };
+
+    let mut response = serde_json::json!(job);
+    response["employer"] = match employer {
+        Some(employer) => serde_json::json!({
+            "id": employer.id.map(|id| id.to_hex()),
+            "company_name": employer.company_name,
+            "logo_url": employer.logo_url,
+            "industry": employer.industry,
+            "size": employer.size,
+            "city": employer.city,
+            "is_verified": employer.is_verified,
+        }),
+        None => serde_json::Value::Null,
+    };
+
+    Ok(Json(ApiResponse::success(response)))
+}
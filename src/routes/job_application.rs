@@ -0,0 +1,551 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use crate::db::DbConn;
+use crate::models::{
+    AddApplicationNoteDto, ApplicationNote, ApplicationStage, ApplicationStageEvent, ApplyJobDto,
+    Job, JobApplication, JobSeekerProfile, JobStatus, UpdateApplicationStageDto,
+    MAX_APPLICATION_NOTE_LENGTH, MAX_COVER_LETTER_LENGTH,
+};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::run_aggregation;
+use crate::routes::notification::notify;
+use crate::utils::{ApiResponse, ApiError, is_duplicate_key};
+
+// ============================================================================
+// APPLICATION HELPERS
+// ============================================================================
+
+async fn find_application(db: &DbConn, application_id: &str) -> Result<JobApplication, ApiError> {
+    let object_id = ObjectId::parse_str(application_id)
+        .map_err(|_| ApiError::bad_request("Invalid application ID"))?;
+
+    db.collection::<JobApplication>("job_applications")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Application not found"))
+}
+
+async fn find_employer_application(db: &DbConn, application_id: &str, user_id: ObjectId) -> Result<JobApplication, ApiError> {
+    let application = find_application(db, application_id).await?;
+    if application.employer_id != user_id {
+        return Err(ApiError::not_found("Application not found"));
+    }
+    Ok(application)
+}
+
+/// Recount both application counters from the applications themselves, so
+/// concurrent applies and withdrawals cannot drift them. Withdrawn
+/// applications are not counted; every other stage, rejected included, is.
+async fn refresh_application_counts(db: &DbConn, application: &JobApplication) -> Result<(), ApiError> {
+    let applications = db.collection::<JobApplication>("job_applications");
+    let withdrawn = ApplicationStage::Withdrawn.as_str();
+
+    let for_job = applications
+        .count_documents(doc! { "job_id": application.job_id, "stage": { "$ne": withdrawn } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    db.collection::<Job>("jobs")
+        .update_one(doc! { "_id": application.job_id }, doc! { "$set": { "applications_count": for_job as i32 } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?;
+
+    let for_profile = applications
+        .count_documents(
+            doc! { "job_seeker_profile_id": application.job_seeker_profile_id, "stage": { "$ne": withdrawn } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .update_one(
+            doc! { "_id": application.job_seeker_profile_id },
+            doc! { "$set": { "applications_count": for_profile as i32 } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update profile: {}", e)))?;
+
+    Ok(())
+}
+
+/// Re-apply on top of a withdrawn application, which the unique index keeps around
+async fn reopen_application(db: &DbConn, application: &JobApplication) -> Result<Option<JobApplication>, ApiError> {
+    let event = ApplicationStageEvent {
+        from: ApplicationStage::Withdrawn,
+        to: ApplicationStage::Applied,
+        note: None,
+        at: application.applied_at,
+    };
+    let event_bson = mongodb::bson::to_bson(&event)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    db.collection::<JobApplication>("job_applications")
+        .find_one_and_update(
+            doc! {
+                "job_id": application.job_id,
+                "applicant_id": application.applicant_id,
+                "stage": ApplicationStage::Withdrawn.as_str()
+            },
+            doc! {
+                "$set": {
+                    "stage": ApplicationStage::Applied.as_str(),
+                    "job_seeker_profile_id": application.job_seeker_profile_id,
+                    "cover_letter": application.cover_letter.as_deref(),
+                    "resume_url": application.resume_url.as_deref(),
+                    "applied_at": application.applied_at,
+                    "updated_at": application.updated_at
+                },
+                "$push": { "history": event_bson }
+            },
+            FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to apply: {}", e)))
+}
+
+/// Record a stage change. Conditional on the current stage so racing updates cannot both apply.
+async fn move_application(
+    db: &DbConn,
+    application: &JobApplication,
+    to: ApplicationStage,
+    note: Option<String>,
+) -> Result<(), ApiError> {
+    let now = DateTime::now();
+    let event = ApplicationStageEvent {
+        from: application.stage,
+        to,
+        note,
+        at: now,
+    };
+    let event_bson = mongodb::bson::to_bson(&event)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let result = db.collection::<JobApplication>("job_applications")
+        .update_one(
+            doc! { "_id": application.id, "stage": application.stage.as_str() },
+            doc! {
+                "$set": { "stage": to.as_str(), "updated_at": now },
+                "$push": { "history": event_bson }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update application: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Application was updated by someone else, please refresh"));
+    }
+    Ok(())
+}
+
+fn clean_note(note: Option<&str>) -> Result<Option<String>, ApiError> {
+    let note = note.map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_APPLICATION_NOTE_LENGTH) {
+        return Err(ApiError::bad_request(format!(
+            "Notes can be at most {} characters",
+            MAX_APPLICATION_NOTE_LENGTH
+        )));
+    }
+    Ok(note.map(str::to_string))
+}
+
+fn total_from_facet(result: &Document) -> i64 {
+    result
+        .get_array("total")
+        .ok()
+        .and_then(|t| t.first())
+        .and_then(|t| t.as_document())
+        .and_then(|t| t.get_i32("count").ok())
+        .unwrap_or(0) as i64
+}
+
+// ============================================================================
+// APPLICANT ENDPOINTS
+// ============================================================================
+
+/// Apply with the resume from my job seeker profile and an optional cover letter
+#[openapi(tag = "JobApplication")]
+#[post("/job/<job_id>/apply", data = "<dto>")]
+pub async fn apply_to_job(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    dto: Json<ApplyJobDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job_oid = ObjectId::parse_str(&job_id)
+        .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    let profile = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Create a job seeker profile before applying"))?;
+    let profile_id = profile.id.ok_or_else(|| ApiError::internal_error("Profile has no ID"))?;
+
+    let job = db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": job_oid, "status": JobStatus::Approved.as_str() }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found or no longer open"))?;
+    let employer_id = job.posted_by.ok_or_else(|| ApiError::bad_request("This job is not accepting applications"))?;
+    if employer_id == auth.user_id {
+        return Err(ApiError::bad_request("You cannot apply to your own job"));
+    }
+
+    let cover_letter = dto.cover_letter.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    if cover_letter.is_some_and(|c| c.chars().count() > MAX_COVER_LETTER_LENGTH) {
+        return Err(ApiError::bad_request(format!(
+            "Cover letters can be at most {} characters",
+            MAX_COVER_LETTER_LENGTH
+        )));
+    }
+
+    let now = DateTime::now();
+    let mut application = JobApplication {
+        id: None,
+        job_id: job_oid,
+        employer_id,
+        applicant_id: auth.user_id,
+        job_seeker_profile_id: profile_id,
+        cover_letter: cover_letter.map(str::to_string),
+        resume_url: profile.resume_url.clone(),
+        stage: ApplicationStage::Applied,
+        history: Vec::new(),
+        employer_notes: Vec::new(),
+        applied_at: now,
+        updated_at: now,
+    };
+
+    // The unique index on (job_id, applicant_id) rejects a second application
+    match db.collection::<JobApplication>("job_applications").insert_one(&application, None).await {
+        Ok(result) => application.id = result.inserted_id.as_object_id(),
+        Err(e) if is_duplicate_key(&e) => {
+            application = reopen_application(db, &application)
+                .await?
+                .ok_or_else(|| ApiError::bad_request("You have already applied to this job"))?;
+        }
+        Err(e) => return Err(ApiError::internal_error(format!("Failed to apply: {}", e))),
+    }
+
+    refresh_application_counts(db, &application).await?;
+
+    notify(
+        db,
+        employer_id,
+        "job_application",
+        &format!("New applicant for {}", job.title),
+        &format!("{} applied to \"{}\".", profile.full_name, job.title),
+        Some(serde_json::json!({ "job_id": job_id, "application_id": application.id.map(|id| id.to_hex()) })),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Application submitted".to_string(),
+        serde_json::json!({
+            "application_id": application.id.map(|id| id.to_hex()),
+            "stage": application.stage,
+            "resume_url": application.resume_url
+        }),
+    )))
+}
+
+#[openapi(tag = "JobApplication")]
+#[post("/applications/<application_id>/withdraw")]
+pub async fn withdraw_application(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_application(db, &application_id).await?;
+    if application.applicant_id != auth.user_id {
+        return Err(ApiError::not_found("Application not found"));
+    }
+    if !application.stage.can_withdraw() {
+        return Err(ApiError::bad_request(format!(
+            "Cannot withdraw an application that is {}",
+            application.stage.as_str()
+        )));
+    }
+
+    move_application(db, &application, ApplicationStage::Withdrawn, None).await?;
+    refresh_application_counts(db, &application).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Application withdrawn",
+        "stage": ApplicationStage::Withdrawn
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct ApplicationListQuery {
+    pub stage: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+fn stage_filter(stage: Option<&str>) -> Result<Option<&'static str>, ApiError> {
+    stage
+        .map(|s| ApplicationStage::parse(s).map(|s| s.as_str()).ok_or_else(|| ApiError::bad_request("Invalid application stage")))
+        .transpose()
+}
+
+/// My applications with the job each one is for
+#[openapi(tag = "JobApplication")]
+#[get("/job-seeker/applications?<query..>")]
+pub async fn get_my_applications(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ApplicationListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "applicant_id": auth.user_id };
+    if let Some(stage) = stage_filter(query.stage.as_deref())? {
+        filter.insert("stage", stage);
+    }
+
+    let result: Vec<Document> = run_aggregation(db, "job_applications", vec![
+        doc! { "$match": filter },
+        doc! {
+            "$facet": {
+                "applications": [
+                    { "$sort": { "applied_at": -1 } },
+                    { "$skip": skip },
+                    { "$limit": limit },
+                    {
+                        "$lookup": {
+                            "from": "jobs",
+                            "localField": "job_id",
+                            "foreignField": "_id",
+                            "as": "job"
+                        }
+                    },
+                    { "$unwind": { "path": "$job", "preserveNullAndEmptyArrays": true } },
+                    {
+                        "$project": {
+                            "employer_notes": 0,
+                            "job.description": 0,
+                            "job.requirements": 0,
+                            "job.rejection_reason": 0
+                        }
+                    }
+                ],
+                "total": [
+                    { "$count": "count" }
+                ]
+            }
+        },
+    ]).await?;
+
+    let result = result.into_iter().next().unwrap_or_default();
+    let total = total_from_facet(&result);
+    let applications = result.get_array("applications").cloned().unwrap_or_default();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "applications": applications,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// One application. Employer notes are only shown to the employer.
+#[openapi(tag = "JobApplication")]
+#[get("/applications/<application_id>")]
+pub async fn get_application(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_application(db, &application_id).await?;
+
+    if application.employer_id == auth.user_id {
+        return Ok(Json(ApiResponse::success(serde_json::json!(application))));
+    }
+    if application.applicant_id != auth.user_id {
+        return Err(ApiError::not_found("Application not found"));
+    }
+
+    let mut response = serde_json::json!(application);
+    if let Some(map) = response.as_object_mut() {
+        map.remove("employer_notes");
+    }
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// ============================================================================
+// EMPLOYER ENDPOINTS
+// ============================================================================
+
+/// Applicants for one of my jobs, with their profiles and counts per stage
+#[openapi(tag = "JobApplication")]
+#[get("/job/<job_id>/applications?<query..>")]
+pub async fn get_job_applications(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    query: ApplicationListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job_oid = ObjectId::parse_str(&job_id)
+        .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+    db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": job_oid, "posted_by": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut selected = doc! {};
+    if let Some(stage) = stage_filter(query.stage.as_deref())? {
+        selected.insert("stage", stage);
+    }
+
+    let result: Vec<Document> = run_aggregation(db, "job_applications", vec![
+        doc! { "$match": { "job_id": job_oid, "employer_id": auth.user_id } },
+        doc! {
+            "$facet": {
+                "applications": [
+                    { "$match": selected.clone() },
+                    { "$sort": { "applied_at": -1 } },
+                    { "$skip": skip },
+                    { "$limit": limit },
+                    {
+                        "$lookup": {
+                            "from": "job_seeker_profiles",
+                            "localField": "job_seeker_profile_id",
+                            "foreignField": "_id",
+                            "as": "profile"
+                        }
+                    },
+                    { "$unwind": { "path": "$profile", "preserveNullAndEmptyArrays": true } }
+                ],
+                "total": [
+                    { "$match": selected },
+                    { "$count": "count" }
+                ],
+                "stages": [
+                    { "$group": { "_id": "$stage", "count": { "$sum": 1 } } },
+                    { "$sort": { "_id": 1 } }
+                ]
+            }
+        },
+    ]).await?;
+
+    let result = result.into_iter().next().unwrap_or_default();
+    let total = total_from_facet(&result);
+    let applications = result.get_array("applications").cloned().unwrap_or_default();
+    let stages = result.get_array("stages").cloned().unwrap_or_default();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "applications": applications,
+        "stages": stages,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// Move an applicant through applied → shortlisted → interview → offered → hired, or reject
+#[openapi(tag = "JobApplication")]
+#[put("/applications/<application_id>/stage", data = "<dto>")]
+pub async fn update_application_stage(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+    dto: Json<UpdateApplicationStageDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_employer_application(db, &application_id, auth.user_id).await?;
+    let to = ApplicationStage::parse(&dto.stage)
+        .ok_or_else(|| ApiError::bad_request("Invalid application stage"))?;
+
+    if !application.stage.next_states().contains(&to) {
+        return Err(ApiError::bad_request(format!(
+            "Cannot move an application from {} to {}",
+            application.stage.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let note = clean_note(dto.note.as_deref())?;
+    move_application(db, &application, to, note.clone()).await?;
+    refresh_application_counts(db, &application).await?;
+
+    let job_title = db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": application.job_id }, None)
+        .await
+        .ok()
+        .flatten()
+        .map(|job| job.title)
+        .unwrap_or_else(|| "a job".to_string());
+    let message = match note {
+        Some(ref note) => format!("Your application for \"{}\" is now {}.\n{}", job_title, to.as_str(), note),
+        None => format!("Your application for \"{}\" is now {}.", job_title, to.as_str()),
+    };
+    notify(
+        db,
+        application.applicant_id,
+        "job_application",
+        "Application update",
+        &message,
+        Some(serde_json::json!({ "application_id": application_id, "stage": to })),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": format!("Application moved to {}", to.as_str()),
+        "stage": to
+    }))))
+}
+
+/// Private employer note on an applicant
+#[openapi(tag = "JobApplication")]
+#[post("/applications/<application_id>/notes", data = "<dto>")]
+pub async fn add_application_note(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+    dto: Json<AddApplicationNoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_employer_application(db, &application_id, auth.user_id).await?;
+    let text = clean_note(Some(&dto.note))?
+        .ok_or_else(|| ApiError::bad_request("Note cannot be empty"))?;
+
+    let note = ApplicationNote {
+        text,
+        at: DateTime::now(),
+    };
+    let note_bson = mongodb::bson::to_bson(&note)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    db.collection::<JobApplication>("job_applications")
+        .update_one(
+            doc! { "_id": application.id },
+            doc! {
+                "$push": { "employer_notes": note_bson },
+                "$set": { "updated_at": note.at }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to add note: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Note added",
+        "note": note
+    }))))
+}
@@ -0,0 +1,555 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::options::FindOptions;
+use serde::Serialize;
+use crate::db::DbConn;
+use crate::models::{Job, JobApplication, JobSeekerProfile, JobStatus};
+use crate::guards::AuthGuard;
+use crate::utils::{ApiResponse, ApiError};
+
+/// Most recent jobs or profiles scored per request
+const MATCH_POOL_SIZE: i64 = 500;
+
+/// Extra ranking weight for premium job seekers when employers look for candidates
+const PREMIUM_BOOST: f64 = 0.15;
+
+/// Score given to a factor when one side has nothing to compare
+const NEUTRAL: f64 = 0.5;
+
+/// Share of the score a relocating candidate keeps for a job outside their locations
+const RELOCATION_CREDIT: f64 = 0.6;
+
+// Factor weights, out of 100
+const SKILLS_WEIGHT: f64 = 35.0;
+const CATEGORY_WEIGHT: f64 = 15.0;
+const JOB_TYPE_WEIGHT: f64 = 10.0;
+const LOCATION_WEIGHT: f64 = 15.0;
+const SALARY_WEIGHT: f64 = 15.0;
+const EXPERIENCE_WEIGHT: f64 = 10.0;
+
+// ============================================================================
+// SCORING
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct MatchFactor {
+    pub score: f64,
+    pub max: f64,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchBreakdown {
+    pub skills: MatchFactor,
+    pub category: MatchFactor,
+    pub job_type: MatchFactor,
+    pub location: MatchFactor,
+    pub salary: MatchFactor,
+    pub experience: MatchFactor,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchScore {
+    pub score: f64, // 0 to 100
+    pub breakdown: MatchBreakdown,
+}
+
+fn factor(weight: f64, fraction: f64, detail: String) -> MatchFactor {
+    MatchFactor {
+        score: round1(weight * fraction.clamp(0.0, 1.0)),
+        max: weight,
+        detail,
+    }
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn contains_ignore_case(values: &[String], value: &str) -> bool {
+    values.iter().any(|v| v.trim().eq_ignore_ascii_case(value.trim()))
+}
+
+/// Lowercase words, keeping `+` and `#` so "c++" and "c#" stay distinct from "c"
+fn tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `skill` appears in `text` as whole words, so "go" does not match "good"
+fn mentions(text: &[String], skill: &[String]) -> bool {
+    !skill.is_empty() && text.windows(skill.len()).any(|window| window == skill)
+}
+
+fn skills_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    // What the job wants: each listed skill, plus each requirement that names none of them
+    let mut wanted: Vec<Vec<String>> = Vec::new();
+    for skill in &job.skills {
+        let skill = tokens(skill);
+        if !skill.is_empty() && !wanted.contains(&skill) {
+            wanted.push(skill);
+        }
+    }
+    let listed = wanted.len();
+    for requirement in job.requirements.iter().flatten() {
+        let requirement = tokens(requirement);
+        if !requirement.is_empty()
+            && !wanted[..listed].iter().any(|skill| mentions(&requirement, skill))
+            && !wanted.contains(&requirement)
+        {
+            wanted.push(requirement);
+        }
+    }
+    if wanted.is_empty() {
+        return factor(SKILLS_WEIGHT, NEUTRAL, "Job lists no skills".to_string());
+    }
+
+    let skills: Vec<(&String, Vec<String>)> = profile.skills.iter().map(|s| (s, tokens(s))).collect();
+    let covered = wanted
+        .iter()
+        .filter(|w| skills.iter().any(|(_, skill)| mentions(w, skill)))
+        .count();
+    let names: Vec<&str> = skills
+        .iter()
+        .filter(|(_, skill)| wanted.iter().any(|w| mentions(w, skill)))
+        .map(|(name, _)| name.trim())
+        .collect();
+
+    let fraction = covered as f64 / wanted.len() as f64;
+    let detail = if names.is_empty() {
+        "No matching skills".to_string()
+    } else {
+        format!("Matches {}", names.join(", "))
+    };
+    factor(SKILLS_WEIGHT, fraction, detail)
+}
+
+fn category_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    match job.category.as_deref() {
+        _ if profile.preferred_categories.is_empty() => {
+            factor(CATEGORY_WEIGHT, NEUTRAL, "No category preference".to_string())
+        }
+        Some(category) if contains_ignore_case(&profile.preferred_categories, category) => {
+            factor(CATEGORY_WEIGHT, 1.0, format!("Preferred category {}", category))
+        }
+        Some(category) => factor(CATEGORY_WEIGHT, 0.0, format!("{} is not a preferred category", category)),
+        None => factor(CATEGORY_WEIGHT, NEUTRAL, "Job has no category".to_string()),
+    }
+}
+
+fn job_type_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    match job.job_type {
+        _ if profile.preferred_job_types.is_empty() => {
+            factor(JOB_TYPE_WEIGHT, NEUTRAL, "No job type preference".to_string())
+        }
+        Some(job_type) if contains_ignore_case(&profile.preferred_job_types, job_type.as_str()) => {
+            factor(JOB_TYPE_WEIGHT, 1.0, format!("Preferred job type {}", job_type.as_str()))
+        }
+        Some(job_type) => factor(JOB_TYPE_WEIGHT, 0.0, format!("{} is not a preferred job type", job_type.as_str())),
+        None => factor(JOB_TYPE_WEIGHT, NEUTRAL, "Job has no type".to_string()),
+    }
+}
+
+fn location_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    let Some(location) = job.location.as_deref().map(str::to_lowercase) else {
+        return factor(LOCATION_WEIGHT, NEUTRAL, "Job has no location".to_string());
+    };
+    if profile.preferred_locations.is_empty() {
+        return factor(LOCATION_WEIGHT, NEUTRAL, "No location preference".to_string());
+    }
+
+    let preferred = profile.preferred_locations.iter().find(|preferred| {
+        let preferred = preferred.trim().to_lowercase();
+        !preferred.is_empty() && (location.contains(&preferred) || preferred.contains(&location))
+    });
+    match preferred {
+        Some(preferred) => factor(LOCATION_WEIGHT, 1.0, format!("In preferred location {}", preferred)),
+        None if profile.willing_to_relocate => {
+            factor(LOCATION_WEIGHT, RELOCATION_CREDIT, "Outside preferred locations, willing to relocate".to_string())
+        }
+        None => factor(LOCATION_WEIGHT, 0.0, "Outside preferred locations".to_string()),
+    }
+}
+
+fn salary_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    let offered = (job.salary_min, job.salary_max.or(job.salary_min));
+    let expected = (profile.expected_salary_min, profile.expected_salary_max.or(profile.expected_salary_min));
+
+    match (offered, expected) {
+        ((_, Some(offer_max)), (Some(expect_min), _)) if offer_max < expect_min => {
+            // Partial credit when the offer falls a little short
+            factor(
+                SALARY_WEIGHT,
+                offer_max / expect_min,
+                format!("Pays up to {} against an expected {}", offer_max, expect_min),
+            )
+        }
+        ((Some(offer_min), _), (_, Some(expect_max))) if offer_min > expect_max => {
+            factor(SALARY_WEIGHT, 1.0, "Pays above the expected range".to_string())
+        }
+        ((None, None), _) => factor(SALARY_WEIGHT, NEUTRAL, "Job has no salary".to_string()),
+        (_, (None, None)) => factor(SALARY_WEIGHT, NEUTRAL, "No salary expectation".to_string()),
+        _ => factor(SALARY_WEIGHT, 1.0, "Salary ranges overlap".to_string()),
+    }
+}
+
+fn experience_factor(profile: &JobSeekerProfile, job: &Job) -> MatchFactor {
+    match (job.experience_min, profile.experience_years) {
+        (None, _) | (Some(0), _) => factor(EXPERIENCE_WEIGHT, 1.0, "No minimum experience".to_string()),
+        (Some(required), Some(years)) if years >= required => {
+            factor(EXPERIENCE_WEIGHT, 1.0, format!("{} years, {} required", years, required))
+        }
+        (Some(required), Some(years)) => factor(
+            EXPERIENCE_WEIGHT,
+            years.max(0) as f64 / required as f64,
+            format!("{} years, {} required", years, required),
+        ),
+        (Some(required), None) => factor(EXPERIENCE_WEIGHT, 0.0, format!("{} years required", required)),
+    }
+}
+
+/// How well a job suits a job seeker, with the points from each factor
+pub fn match_score(profile: &JobSeekerProfile, job: &Job) -> MatchScore {
+    let breakdown = MatchBreakdown {
+        skills: skills_factor(profile, job),
+        category: category_factor(profile, job),
+        job_type: job_type_factor(profile, job),
+        location: location_factor(profile, job),
+        salary: salary_factor(profile, job),
+        experience: experience_factor(profile, job),
+    };
+    let score = breakdown.skills.score
+        + breakdown.category.score
+        + breakdown.job_type.score
+        + breakdown.location.score
+        + breakdown.salary.score
+        + breakdown.experience.score;
+
+    MatchScore {
+        score: round1(score),
+        breakdown,
+    }
+}
+
+/// Ranking multiplier for candidates, for premium job seekers with a current plan
+fn exposure_boost(profile: &JobSeekerProfile) -> f64 {
+    if profile.has_premium() {
+        PREMIUM_BOOST
+    } else {
+        0.0
+    }
+}
+
+// ============================================================================
+// RECOMMENDATION ENDPOINTS
+// ============================================================================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct RecommendationQuery {
+    pub min_score: Option<f64>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Open jobs ranked for my job seeker profile, skipping ones I applied to
+#[openapi(tag = "JobSeeker")]
+#[get("/job-seeker/recommended-jobs?<query..>")]
+pub async fn get_recommended_jobs(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: RecommendationQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let min_score = query.min_score.unwrap_or(0.0);
+
+    let profile = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job seeker profile not found"))?;
+
+    let applied: Vec<ObjectId> = db.collection::<JobApplication>("job_applications")
+        .distinct("job_id", doc! { "applicant_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .into_iter()
+        .filter_map(|id| id.as_object_id())
+        .collect();
+
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(
+            doc! {
+                "status": JobStatus::Approved.as_str(),
+                "_id": { "$nin": applied },
+                "posted_by": { "$ne": auth.user_id }
+            },
+            FindOptions::builder().sort(doc! { "published_at": -1 }).limit(MATCH_POOL_SIZE).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut ranked = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let job = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        let score = match_score(&profile, &job);
+        if score.score >= min_score {
+            ranked.push((job, score));
+        }
+    }
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score));
+
+    let total = ranked.len() as i64;
+    let jobs: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .skip(((page - 1) * limit) as usize)
+        .take(limit as usize)
+        .map(|(job, score)| serde_json::json!({
+            "job": job,
+            "score": score.score,
+            "breakdown": score.breakdown
+        }))
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "jobs": jobs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// Available job seekers ranked for one of my jobs. Premium job seekers rank higher.
+#[openapi(tag = "Job")]
+#[get("/job/<job_id>/recommended-candidates?<query..>")]
+pub async fn get_recommended_candidates(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    query: RecommendationQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let min_score = query.min_score.unwrap_or(0.0);
+
+    let job_oid = ObjectId::parse_str(&job_id)
+        .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+    let job = db.collection::<Job>("jobs")
+        .find_one(doc! { "_id": job_oid, "posted_by": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+
+    let applied: Vec<ObjectId> = db.collection::<JobApplication>("job_applications")
+        .distinct("job_seeker_profile_id", doc! { "job_id": job_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .into_iter()
+        .filter_map(|id| id.as_object_id())
+        .collect();
+
+    let mut cursor = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find(
+            doc! {
+                "is_available": true,
+                "_id": { "$nin": &applied },
+                "user_id": { "$ne": auth.user_id }
+            },
+            FindOptions::builder().sort(doc! { "updated_at": -1 }).limit(MATCH_POOL_SIZE).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut ranked = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let profile = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        let score = match_score(&profile, &job);
+        if score.score < min_score {
+            continue;
+        }
+        let boost = exposure_boost(&profile);
+        let rank_score = round1(score.score * (1.0 + boost));
+        ranked.push((profile, score, boost, rank_score));
+    }
+    ranked.sort_by(|a, b| b.3.total_cmp(&a.3));
+
+    let total = ranked.len() as i64;
+    let candidates: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .skip(((page - 1) * limit) as usize)
+        .take(limit as usize)
+        .map(|(profile, score, boost, rank_score)| serde_json::json!({
+            "profile": profile,
+            "score": score.score,
+            "premium_boost": boost,
+            "rank_score": rank_score,
+            "breakdown": score.breakdown
+        }))
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "candidates": candidates,
+        "already_applied": applied.len(),
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{from_document, DateTime};
+
+    fn profile() -> JobSeekerProfile {
+        from_document(doc! {
+            "user_id": ObjectId::new(),
+            "full_name": "Asha",
+            "headline": null,
+            "bio": null,
+            "skills": ["Electrician", "Wiring"],
+            "experience_years": 4,
+            "education": [],
+            "work_experience": [],
+            "preferred_categories": ["Electrical"],
+            "preferred_job_types": ["fulltime"],
+            "preferred_locations": ["Bengaluru"],
+            "expected_salary_min": 20000.0,
+            "expected_salary_max": 30000.0,
+            "willing_to_relocate": false,
+            "resume_url": null,
+            "portfolio_url": null,
+            "linkedin_url": null,
+            "subscription_plan": "none",
+            "subscription_expires_at": null,
+            "is_verified": true,
+            "is_available": true,
+            "profile_views": 0,
+            "applications_count": 0,
+            "created_at": DateTime::now(),
+            "updated_at": DateTime::now(),
+        })
+        .unwrap()
+    }
+
+    fn job() -> Job {
+        from_document(doc! {
+            "title": "Site electrician",
+            "company": "Acme",
+            "description": null,
+            "location": "Bengaluru",
+            "job_type": "fulltime",
+            "category": "Electrical",
+            "salary_min": 25000.0,
+            "salary_max": 35000.0,
+            "requirements": null,
+            "skills": ["electrician", "wiring"],
+            "experience_min": 2,
+            "status": "approved",
+            "rejection_reason": null,
+            "applications_count": 0,
+            "posted_by": null,
+            "published_at": null,
+            "closed_at": null,
+            "created_at": DateTime::now(),
+            "updated_at": DateTime::now(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn perfect_match_scores_full_marks() {
+        let score = match_score(&profile(), &job());
+        assert_eq!(score.score, 100.0);
+    }
+
+    #[test]
+    fn score_is_the_sum_of_the_factors() {
+        let mut job = job();
+        job.category = Some("Plumbing".to_string());
+        job.experience_min = Some(8);
+
+        let score = match_score(&profile(), &job);
+        let b = &score.breakdown;
+        assert_eq!(b.category.score, 0.0);
+        assert_eq!(b.experience.score, 5.0);
+        assert_eq!(
+            score.score,
+            round1(b.skills.score + b.category.score + b.job_type.score + b.location.score + b.salary.score + b.experience.score)
+        );
+        assert_eq!(score.score, 80.0);
+    }
+
+    #[test]
+    fn missing_preferences_are_neutral() {
+        let mut profile = profile();
+        profile.preferred_categories.clear();
+        profile.preferred_job_types.clear();
+        profile.preferred_locations.clear();
+        profile.expected_salary_min = None;
+        profile.expected_salary_max = None;
+
+        let b = match_score(&profile, &job()).breakdown;
+        assert_eq!(b.category.score, CATEGORY_WEIGHT * NEUTRAL);
+        assert_eq!(b.job_type.score, JOB_TYPE_WEIGHT * NEUTRAL);
+        assert_eq!(b.location.score, LOCATION_WEIGHT * NEUTRAL);
+        assert_eq!(b.salary.score, SALARY_WEIGHT * NEUTRAL);
+    }
+
+    #[test]
+    fn short_skills_match_whole_words_only() {
+        let mut profile = profile();
+        profile.skills = vec!["Go".to_string(), "C".to_string()];
+        let mut job = job();
+        job.skills = vec!["C++".to_string()];
+        job.requirements = Some(vec!["Good communication".to_string()]);
+
+        let b = match_score(&profile, &job).breakdown;
+        assert_eq!(b.skills.score, 0.0);
+        assert_eq!(b.skills.detail, "No matching skills");
+
+        job.requirements = Some(vec!["Experience with Go services".to_string()]);
+        let b = match_score(&profile, &job).breakdown;
+        assert_eq!(b.skills.score, round1(SKILLS_WEIGHT / 2.0));
+        assert_eq!(b.skills.detail, "Matches Go");
+    }
+
+    #[test]
+    fn requirements_count_towards_the_wanted_skills() {
+        let mut job = job();
+        job.requirements = Some(vec![
+            "Solar panel installation".to_string(),
+            "Industrial wiring".to_string(), // Already listed as a skill
+        ]);
+
+        let b = match_score(&profile(), &job).breakdown;
+        assert_eq!(b.skills.score, round1(SKILLS_WEIGHT * 2.0 / 3.0));
+
+        let mut profile = profile();
+        profile.skills.push("Solar panel installation".to_string());
+        let b = match_score(&profile, &job).breakdown;
+        assert_eq!(b.skills.score, SKILLS_WEIGHT);
+    }
+
+    #[test]
+    fn salary_short_of_expectation_gets_partial_credit() {
+        let mut job = job();
+        job.salary_min = Some(10000.0);
+        job.salary_max = Some(15000.0);
+
+        let b = match_score(&profile(), &job).breakdown;
+        assert_eq!(b.salary.score, 11.3);
+    }
+}
@@ -7,4 +7,21 @@ pub mod category;
 pub mod review;
 pub mod file_upload;
 pub mod service;
-pub mod admin;
\ No newline at end of file
+pub mod admin;
+pub mod subscription;
+pub mod invoice;
+pub mod coupon;
+pub mod analytics;
+pub mod booking;
+pub mod quote;
+pub mod dashboard;
+pub mod notification;
+pub mod onboarding;
+pub mod tracking;
+pub mod favorite;
+pub mod badge;
+pub mod job_application;
+pub mod matching;
+pub mod saved_search;
+pub mod profile_view;
+pub mod employer;
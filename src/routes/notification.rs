@@ -0,0 +1,172 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, oid::ObjectId};
+use mongodb::options::FindOptions;
+use crate::db::DbConn;
+use crate::models::{Notification, User};
+use crate::guards::AuthGuard;
+use crate::services::EmailService;
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// NOTIFICATION HELPERS
+// ============================================================================
+
+/// Store an in-app notification for `user_id` and email it in the background.
+/// Failures are logged; a notification never fails the action that caused it.
+pub async fn notify(
+    db: &DbConn,
+    user_id: ObjectId,
+    kind: &str,
+    title: &str,
+    message: &str,
+    data: Option<serde_json::Value>,
+) {
+    let notification = Notification {
+        id: None,
+        user_id,
+        kind: kind.to_string(),
+        title: title.to_string(),
+        message: message.to_string(),
+        data,
+        read_at: None,
+        created_at: DateTime::now(),
+    };
+
+    if let Err(e) = db.collection::<Notification>("notifications").insert_one(&notification, None).await {
+        error!("Failed to store notification for {}: {}", user_id, e);
+    }
+
+    let email = match db.collection::<User>("users").find_one(doc! { "_id": user_id }, None).await {
+        Ok(Some(user)) => user.email,
+        Ok(None) => None,
+        Err(e) => {
+            error!("Failed to load user {} for notification: {}", user_id, e);
+            None
+        }
+    };
+
+    if let Some(email) = email {
+        let (title, message) = (title.to_string(), message.to_string());
+        tokio::spawn(async move {
+            EmailService::send_notification_email(&email, &title, &message).await;
+        });
+    }
+}
+
+// ============================================================================
+// NOTIFICATION ENDPOINTS
+// ============================================================================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct NotificationListQuery {
+    pub unread: Option<bool>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Notification")]
+#[get("/notifications?<query..>")]
+pub async fn get_notifications(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: NotificationListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "user_id": auth.user_id };
+    if query.unread == Some(true) {
+        filter.insert("read_at", mongodb::bson::Bson::Null);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Notification>("notifications")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut notifications = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let notification = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        notifications.push(notification);
+    }
+
+    let total = db.collection::<Notification>("notifications")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    let unread = db.collection::<Notification>("notifications")
+        .count_documents(doc! { "user_id": auth.user_id, "read_at": null }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "notifications": notifications,
+        "unread": unread,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Notification")]
+#[post("/notifications/<notification_id>/read")]
+pub async fn mark_notification_read(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    notification_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&notification_id)
+        .map_err(|_| ApiError::bad_request("Invalid notification ID"))?;
+
+    let result = db.collection::<Notification>("notifications")
+        .update_one(
+            doc! { "_id": object_id, "user_id": auth.user_id, "read_at": null },
+            doc! { "$set": { "read_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update notification: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("Notification not found"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Notification marked as read"
+    }))))
+}
+
+#[openapi(tag = "Notification")]
+#[post("/notifications/read-all")]
+pub async fn mark_all_notifications_read(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let result = db.collection::<Notification>("notifications")
+        .update_many(
+            doc! { "user_id": auth.user_id, "read_at": null },
+            doc! { "$set": { "read_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update notifications: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Notifications marked as read",
+        "updated": result.modified_count
+    }))))
+}
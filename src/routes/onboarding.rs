@@ -0,0 +1,223 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Bson};
+use crate::db::DbConn;
+use crate::models::{
+    KycStatus, MainCategory, OnboardingChecklist, OnboardingEvent, OnboardingStatus, SubCategory,
+    User, WorkerProfile,
+};
+use crate::guards::AuthGuard;
+use crate::routes::notification::notify;
+use crate::utils::{ApiResponse, ApiError};
+
+// ============================================================================
+// ONBOARDING HELPERS
+// ============================================================================
+
+/// Categories and subcategories on the profile that require a trade license
+async fn licensed_categories(db: &DbConn, worker: &WorkerProfile) -> Result<Vec<String>, ApiError> {
+    let mut licensed = Vec::new();
+
+    let mut cursor = db.collection::<MainCategory>("main_categories")
+        .find(doc! { "name": { "$in": &worker.categories }, "requires_license": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let category = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        licensed.push(category.name);
+    }
+
+    let mut cursor = db.collection::<SubCategory>("sub_categories")
+        .find(doc! { "name": { "$in": &worker.subcategories }, "requires_license": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let subcategory = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        licensed.push(subcategory.name);
+    }
+
+    Ok(licensed)
+}
+
+pub async fn onboarding_checklist(db: &DbConn, worker: &WorkerProfile) -> Result<(OnboardingChecklist, Vec<String>), ApiError> {
+    let user = db.collection::<User>("users")
+        .find_one(doc! { "_id": worker.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    let licensed = licensed_categories(db, worker).await?;
+    let has_text = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.trim().is_empty());
+
+    let checklist = OnboardingChecklist {
+        kyc_approved: matches!(user.kyc_status, KycStatus::Approved),
+        location_set: worker.location_set_at.is_some(),
+        has_category: !worker.categories.is_empty(),
+        license_required: !licensed.is_empty(),
+        license_provided: has_text(&worker.license_number) && has_text(&worker.license_document),
+        profile_photo: has_text(&user.profile_photo),
+    };
+
+    Ok((checklist, licensed))
+}
+
+fn transition_message(to: OnboardingStatus, reasons: &[String]) -> (String, String) {
+    let reasons_text = reasons.iter().map(|r| format!("- {}", r)).collect::<Vec<_>>().join("\n");
+    match to {
+        OnboardingStatus::Draft => (
+            "Your worker profile is a draft".to_string(),
+            "Complete the checklist and submit your profile for review.".to_string(),
+        ),
+        OnboardingStatus::Submitted => (
+            "Worker profile submitted".to_string(),
+            "Thanks! We have received your profile and will review it shortly.".to_string(),
+        ),
+        OnboardingStatus::UnderReview => (
+            "Worker profile under review".to_string(),
+            "Our team is reviewing your profile and documents.".to_string(),
+        ),
+        OnboardingStatus::Verified => (
+            "You're verified!".to_string(),
+            "Your worker profile is verified and now visible to customers.".to_string(),
+        ),
+        OnboardingStatus::Rejected => (
+            "Worker profile needs changes".to_string(),
+            format!("We couldn't verify your profile:\n{}\nFix these and submit again.", reasons_text),
+        ),
+        OnboardingStatus::Suspended => (
+            "Worker profile suspended".to_string(),
+            format!("Your worker profile has been suspended:\n{}", reasons_text),
+        ),
+    }
+}
+
+/// Move a worker's onboarding to `to`, record the event and notify the worker.
+/// Conditional on the current state so racing reviews cannot both apply.
+pub async fn transition_onboarding(
+    db: &DbConn,
+    worker: &WorkerProfile,
+    to: OnboardingStatus,
+    reasons: Vec<String>,
+    note: Option<String>,
+    by_admin: bool,
+) -> Result<(), ApiError> {
+    let from = worker.onboarding();
+    let event = OnboardingEvent {
+        from,
+        to,
+        reasons: reasons.clone(),
+        note,
+        by_admin,
+        at: DateTime::now(),
+    };
+    let event_bson = mongodb::bson::to_bson(&event)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let current = match worker.onboarding_status {
+        Some(status) => Bson::String(status.as_str().to_string()),
+        None => Bson::Null,
+    };
+    let rejection_reasons = match to {
+        OnboardingStatus::Rejected | OnboardingStatus::Suspended => reasons.clone(),
+        _ => Vec::new(),
+    };
+
+    let result = db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id, "onboarding_status": current },
+            doc! {
+                "$set": {
+                    "onboarding_status": to.as_str(),
+                    "is_verified": to == OnboardingStatus::Verified,
+                    "rejection_reasons": rejection_reasons,
+                    "updated_at": DateTime::now()
+                },
+                "$push": { "onboarding_history": event_bson }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update onboarding: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Profile was updated by someone else, please refresh"));
+    }
+
+    let (title, message) = transition_message(to, &reasons);
+    notify(
+        db,
+        worker.user_id,
+        "onboarding",
+        &title,
+        &message,
+        Some(serde_json::json!({ "status": to, "reasons": reasons })),
+    )
+    .await;
+
+    Ok(())
+}
+
+// ============================================================================
+// WORKER ONBOARDING ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Worker")]
+#[get("/worker/onboarding")]
+pub async fn get_onboarding(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+
+    let (checklist, licensed) = onboarding_checklist(db, &worker).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "status": worker.onboarding(),
+        "checklist": checklist,
+        "complete": checklist.is_complete(),
+        "missing": checklist.missing(),
+        "licensed_categories": licensed,
+        "rejection_reasons": worker.rejection_reasons,
+        "history": worker.onboarding_history
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[post("/worker/onboarding/submit")]
+pub async fn submit_onboarding(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+
+    let status = worker.onboarding();
+    if !status.can_submit() {
+        return Err(ApiError::bad_request(format!("Profile is already {}", status.as_str())));
+    }
+
+    let (checklist, _) = onboarding_checklist(db, &worker).await?;
+    if !checklist.is_complete() {
+        return Err(ApiError::bad_request(format!(
+            "Complete your profile first. Missing: {}",
+            checklist.missing().join(", ")
+        )));
+    }
+
+    transition_onboarding(db, &worker, OnboardingStatus::Submitted, Vec::new(), None, false).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Profile submitted for review",
+        "status": OnboardingStatus::Submitted
+    }))))
+}
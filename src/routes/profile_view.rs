@@ -0,0 +1,226 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use crate::db::DbConn;
+use crate::models::{JobSeekerProfile, ProfileKind, ProfileView, WorkerProfile};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::{run_aggregation, DAY_MS};
+use crate::routes::dashboard::{bump_daily_stat, today_ist};
+use crate::utils::{ApiResponse, ApiError, is_duplicate_key};
+
+/// Window used for the "recent views" count shown on every plan
+const RECENT_VIEW_DAYS: i64 = 30;
+
+// ============================================================================
+// VIEW RECORDING
+// ============================================================================
+
+/// Anonymous visitors are told apart by IP. The hash is salted with the day so it can't be
+/// linked across days or reversed from the stored key.
+fn anonymous_key(ip: IpAddr, date: &str) -> String {
+    let digest = Sha256::digest(format!("{}|{}", date, ip).as_bytes());
+    format!("ip:{}", hex::encode(&digest[..12]))
+}
+
+/// Count a view of a public profile. Runs in the background.
+/// Owners viewing their own profile are ignored, and each viewer counts once per IST day.
+pub fn record_profile_view(
+    db: &DbConn,
+    kind: ProfileKind,
+    profile_id: ObjectId,
+    owner_id: ObjectId,
+    viewer_id: Option<ObjectId>,
+    ip: Option<IpAddr>,
+) {
+    if viewer_id == Some(owner_id) {
+        return;
+    }
+
+    let date = today_ist();
+    let viewer_key = match (viewer_id, ip) {
+        (Some(viewer_id), _) => format!("user:{}", viewer_id.to_hex()),
+        (None, Some(ip)) => anonymous_key(ip, &date),
+        // Nothing to dedupe on, so don't count it
+        (None, None) => return,
+    };
+
+    let db = db.clone();
+    tokio::spawn(async move {
+        let view = ProfileView {
+            id: None,
+            profile_kind: kind,
+            profile_id,
+            owner_id,
+            viewer_id,
+            viewer_key,
+            date: date.clone(),
+            viewed_at: DateTime::now(),
+        };
+
+        // The unique index on (profile_id, viewer_key, date) does the deduplication
+        match db.collection::<ProfileView>("profile_views").insert_one(&view, None).await {
+            Ok(_) => {}
+            Err(e) if is_duplicate_key(&e) => return,
+            Err(e) => {
+                warn!("Failed to record profile view of {}: {}", profile_id, e);
+                return;
+            }
+        }
+
+        let result = db.collection::<mongodb::bson::Document>(kind.collection())
+            .update_one(doc! { "_id": profile_id }, doc! { "$inc": { "profile_views": 1_i64 } }, None)
+            .await;
+        if let Err(e) = result {
+            warn!("Failed to count profile view of {}: {}", profile_id, e);
+        }
+
+        if kind == ProfileKind::Worker {
+            bump_daily_stat(&db, profile_id, &date, "profile_views").await;
+        }
+    });
+}
+
+// ============================================================================
+// PROFILE VIEW ENDPOINTS
+// ============================================================================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct ProfileViewsQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProfileViewer {
+    viewer_id: String,
+    name: Option<String>,
+    viewed_at: String,
+}
+
+/// View counts for every plan; the list of signed-in viewers only when `show_viewers` is set
+async fn profile_views_response(
+    db: &DbConn,
+    profile_id: ObjectId,
+    total_views: i64,
+    show_viewers: bool,
+    query: ProfileViewsQuery,
+) -> Result<serde_json::Value, ApiError> {
+    let since = DateTime::from_millis(DateTime::now().timestamp_millis() - RECENT_VIEW_DAYS * DAY_MS);
+    let recent_views = db.collection::<ProfileView>("profile_views")
+        .count_documents(doc! { "profile_id": profile_id, "viewed_at": { "$gte": since } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    let mut response = serde_json::json!({
+        "total_views": total_views,
+        "recent_views": recent_views,
+        "recent_days": RECENT_VIEW_DAYS,
+    });
+
+    if !show_viewers {
+        response["viewers"] = serde_json::Value::Null;
+        response["upgrade_required"] = serde_json::json!(true);
+        return Ok(response);
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let skip = (page - 1) * limit;
+
+    let filter = doc! { "profile_id": profile_id, "viewer_id": { "$ne": null } };
+    let total = db.collection::<ProfileView>("profile_views")
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    let anonymous_views = db.collection::<ProfileView>("profile_views")
+        .count_documents(doc! { "profile_id": profile_id, "viewer_id": null }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    let pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$sort": { "viewed_at": -1 } },
+        doc! { "$skip": skip },
+        doc! { "$limit": limit },
+        doc! { "$lookup": {
+            "from": "users",
+            "localField": "viewer_id",
+            "foreignField": "_id",
+            "as": "viewer"
+        } },
+        doc! { "$project": {
+            "_id": 0,
+            "viewer_id": { "$toString": "$viewer_id" },
+            "name": { "$first": "$viewer.name" },
+            "viewed_at": { "$dateToString": { "date": "$viewed_at", "format": "%Y-%m-%dT%H:%M:%S.%LZ" } }
+        } },
+    ];
+    let viewers: Vec<ProfileViewer> = run_aggregation(db, "profile_views", pipeline).await?;
+
+    response["viewers"] = serde_json::json!(viewers);
+    response["anonymous_views"] = serde_json::json!(anonymous_views);
+    response["pagination"] = serde_json::json!({
+        "page": page,
+        "limit": limit,
+        "total": total,
+        "pages": (total as f64 / limit as f64).ceil() as i64,
+    });
+    Ok(response)
+}
+
+/// Who viewed my job seeker profile. Premium job seekers get the viewer list, other plans the counts.
+#[openapi(tag = "JobSeeker")]
+#[get("/job-seeker/profile-views?<query..>")]
+pub async fn get_job_seeker_profile_views(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ProfileViewsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let profile = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job seeker profile not found"))?;
+    let profile_id = profile.id.ok_or_else(|| ApiError::internal_error("Profile has no ID"))?;
+
+    let response = profile_views_response(
+        db,
+        profile_id,
+        profile.profile_views as i64,
+        profile.has_premium(),
+        query,
+    ).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Who viewed my worker profile. Gold workers get the viewer list, other plans the counts.
+#[openapi(tag = "Worker")]
+#[get("/worker/profile-views?<query..>")]
+pub async fn get_worker_profile_views(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ProfileViewsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Profile has no ID"))?;
+
+    let current = worker.subscription_expires_at.is_none_or(|expires| expires > DateTime::now());
+    let response = profile_views_response(
+        db,
+        worker_id,
+        worker.profile_views,
+        current && worker.subscription_plan.shows_profile_viewers(),
+        query,
+    ).await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
@@ -0,0 +1,586 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::FindOptions;
+use crate::db::DbConn;
+use crate::models::{
+    Booking, BookingParty, BookingStatus, CounterQuoteDto, GeoLocation, LineItemDto, Quote,
+    QuoteLineItem, QuoteRevision, QuoteStatus, RejectQuoteDto, RequestQuoteDto, SubmitQuoteDto,
+    WorkerProfile, MAX_LINE_ITEMS, MAX_QUOTE_PHOTOS,
+};
+use crate::guards::AuthGuard;
+use crate::routes::booking::parse_booking_time;
+use crate::utils::{ApiResponse, ApiError};
+
+const DEFAULT_VALID_DAYS: i64 = 7;
+
+// ============================================================================
+// QUOTE HELPERS
+// ============================================================================
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+async fn find_quote(db: &DbConn, quote_id: &str) -> Result<Quote, ApiError> {
+    let object_id = ObjectId::parse_str(quote_id)
+        .map_err(|_| ApiError::bad_request("Invalid quote ID"))?;
+
+    db.collection::<Quote>("quotes")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Quote not found"))
+}
+
+fn quote_party(quote: &Quote, user_id: ObjectId) -> Result<BookingParty, ApiError> {
+    if quote.worker_user_id == user_id {
+        Ok(BookingParty::Worker)
+    } else if quote.customer_id == user_id {
+        Ok(BookingParty::Customer)
+    } else {
+        Err(ApiError::not_found("Quote not found"))
+    }
+}
+
+/// Load a quote that is still open and waiting on the caller
+async fn find_quote_for_turn(db: &DbConn, quote_id: &str, user_id: ObjectId) -> Result<(Quote, BookingParty), ApiError> {
+    let quote = find_quote(db, quote_id).await?;
+    let party = quote_party(&quote, user_id)?;
+
+    if !quote.status.is_open() {
+        return Err(ApiError::bad_request(format!("Quote is already {}", quote.status.as_str())));
+    }
+    if quote.status.waiting_on() != Some(party.clone()) {
+        return Err(ApiError::bad_request("Waiting for the other side to respond"));
+    }
+    if let Some(valid_until) = quote.valid_until()
+        && valid_until < DateTime::now()
+    {
+        mark_expired(db, &quote).await;
+        return Err(ApiError::bad_request("Quote has expired"));
+    }
+
+    Ok((quote, party))
+}
+
+async fn mark_expired(db: &DbConn, quote: &Quote) {
+    let result = db.collection::<Quote>("quotes")
+        .update_one(
+            doc! { "_id": quote.id, "status": quote.status.as_str() },
+            doc! { "$set": { "status": QuoteStatus::Expired.as_str(), "updated_at": DateTime::now() } },
+            None,
+        )
+        .await;
+
+    if let Err(e) = result {
+        error!("Failed to expire quote {:?}: {}", quote.id, e);
+    }
+}
+
+fn build_line_items(items: &[LineItemDto]) -> Result<Vec<QuoteLineItem>, ApiError> {
+    if items.is_empty() {
+        return Err(ApiError::bad_request("At least one line item is required"));
+    }
+    if items.len() > MAX_LINE_ITEMS {
+        return Err(ApiError::bad_request(format!("At most {} line items are allowed", MAX_LINE_ITEMS)));
+    }
+
+    items
+        .iter()
+        .map(|item| {
+            if item.description.trim().is_empty() {
+                return Err(ApiError::bad_request("Line item description is required"));
+            }
+            if item.quantity <= 0.0 || item.unit_price < 0.0 {
+                return Err(ApiError::bad_request("Line item quantity must be positive and price non-negative"));
+            }
+            Ok(QuoteLineItem {
+                kind: item.kind,
+                description: item.description.trim().to_string(),
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                amount: round2(item.quantity * item.unit_price),
+            })
+        })
+        .collect()
+}
+
+fn build_revision(
+    revision: i32,
+    author: BookingParty,
+    line_items: Vec<QuoteLineItem>,
+    tax_rate: f64,
+    estimated_hours: f64,
+    valid_until: Option<DateTime>,
+    message: Option<String>,
+) -> QuoteRevision {
+    let subtotal = round2(line_items.iter().map(|i| i.amount).sum());
+    let tax_amount = round2(subtotal * tax_rate / 100.0);
+
+    QuoteRevision {
+        revision,
+        author,
+        line_items,
+        subtotal,
+        tax_rate,
+        tax_amount,
+        total: round2(subtotal + tax_amount),
+        estimated_hours,
+        valid_until,
+        message,
+        created_at: DateTime::now(),
+    }
+}
+
+/// Append a revision and move the quote on. Conditional on the status and the
+/// number of revisions, so two racing answers cannot both land.
+async fn push_revision(db: &DbConn, quote: &Quote, revision: &QuoteRevision, to: QuoteStatus) -> Result<(), ApiError> {
+    let revision_bson = mongodb::bson::to_bson(revision)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let result = db.collection::<Quote>("quotes")
+        .update_one(
+            doc! {
+                "_id": quote.id,
+                "status": quote.status.as_str(),
+                "revisions": { "$size": quote.revisions.len() as i64 }
+            },
+            doc! {
+                "$push": { "revisions": revision_bson },
+                "$set": { "status": to.as_str(), "updated_at": DateTime::now() }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update quote: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Quote was updated by someone else, please refresh"));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// CUSTOMER ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Quote")]
+#[post("/quote/request", data = "<dto>")]
+pub async fn request_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<RequestQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker_id = ObjectId::parse_str(&dto.worker_id)
+        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    if worker.user_id == auth.user_id {
+        return Err(ApiError::bad_request("You cannot request a quote from yourself"));
+    }
+    if !worker.categories.iter().any(|c| c.eq_ignore_ascii_case(&dto.category)) {
+        return Err(ApiError::bad_request("Worker does not offer this category"));
+    }
+    if let Some(ref subcategory) = dto.subcategory
+        && !worker.subcategories.iter().any(|s| s.eq_ignore_ascii_case(subcategory))
+    {
+        return Err(ApiError::bad_request("Worker does not offer this subcategory"));
+    }
+
+    if dto.description.trim().is_empty() {
+        return Err(ApiError::bad_request("Describe the job so the worker can quote"));
+    }
+    if dto.address.trim().is_empty() {
+        return Err(ApiError::bad_request("Service address is required"));
+    }
+    if !(-90.0..=90.0).contains(&dto.latitude) || !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Invalid coordinates"));
+    }
+
+    let photos = dto.photos.clone().unwrap_or_default();
+    if photos.len() > MAX_QUOTE_PHOTOS {
+        return Err(ApiError::bad_request(format!("At most {} photos are allowed", MAX_QUOTE_PHOTOS)));
+    }
+    if photos.iter().any(|url| !url.starts_with("/uploads/images/")) {
+        return Err(ApiError::bad_request("Photos must be uploaded through /upload/image first"));
+    }
+
+    let preferred_start = parse_booking_time(&dto.preferred_start)?;
+    let now = DateTime::now();
+    if preferred_start < now {
+        return Err(ApiError::bad_request("Preferred start must be in the future"));
+    }
+
+    let quote = Quote {
+        id: None,
+        customer_id: auth.user_id,
+        worker_id,
+        worker_user_id: worker.user_id,
+        category: dto.category.clone(),
+        subcategory: dto.subcategory.clone(),
+        description: dto.description.trim().to_string(),
+        photos,
+        address: dto.address.trim().to_string(),
+        location: GeoLocation {
+            geo_type: "Point".to_string(),
+            coordinates: [dto.longitude, dto.latitude],
+        },
+        preferred_start,
+        status: QuoteStatus::Requested,
+        revisions: Vec::new(),
+        rejected_by: None,
+        rejection_reason: None,
+        booking_id: None,
+        accepted_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db.collection::<Quote>("quotes")
+        .insert_one(&quote, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create quote request: {}", e)))?;
+    let quote_id = result.inserted_id.as_object_id()
+        .ok_or_else(|| ApiError::internal_error("Invalid quote ID"))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote requested successfully".to_string(),
+        serde_json::json!({
+            "quote_id": quote_id.to_hex(),
+            "status": quote.status
+        })
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[post("/quote/<quote_id>/counter", data = "<dto>")]
+pub async fn counter_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<CounterQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote_for_turn(db, &quote_id, auth.user_id).await?;
+    if party != BookingParty::Customer {
+        return Err(ApiError::forbidden("Only the customer can counter-offer"));
+    }
+
+    // Status is Quoted here, so the worker has made at least one offer
+    let last = quote.latest().ok_or_else(|| ApiError::bad_request("Nothing to counter yet"))?;
+    let revision = build_revision(
+        quote.revisions.len() as i32 + 1,
+        BookingParty::Customer,
+        build_line_items(&dto.line_items)?,
+        last.tax_rate,
+        last.estimated_hours,
+        last.valid_until,
+        dto.message.clone(),
+    );
+
+    push_revision(db, &quote, &revision, QuoteStatus::Countered).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Counter-offer sent",
+        "quote_id": quote_id,
+        "status": QuoteStatus::Countered,
+        "revision": revision.revision,
+        "total": revision.total
+    }))))
+}
+
+// ============================================================================
+// WORKER ENDPOINTS
+// ============================================================================
+
+/// Send the first quote, or a revised one in answer to a counter-offer
+#[openapi(tag = "Quote")]
+#[post("/quote/<quote_id>/respond", data = "<dto>")]
+pub async fn respond_to_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<SubmitQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote_for_turn(db, &quote_id, auth.user_id).await?;
+    if party != BookingParty::Worker {
+        return Err(ApiError::forbidden("Only the worker can send a quote"));
+    }
+
+    if !(0.0..=28.0).contains(&dto.tax_rate) {
+        return Err(ApiError::bad_request("Tax rate must be between 0 and 28 percent"));
+    }
+    if dto.estimated_hours <= 0.0 || dto.estimated_hours > 24.0 {
+        return Err(ApiError::bad_request("Estimated hours must be between 0 and 24"));
+    }
+    let valid_days = dto.valid_days.unwrap_or(DEFAULT_VALID_DAYS);
+    if !(1..=30).contains(&valid_days) {
+        return Err(ApiError::bad_request("Validity must be between 1 and 30 days"));
+    }
+    let valid_until = DateTime::from_millis(
+        DateTime::now().timestamp_millis() + valid_days * 24 * 60 * 60 * 1000,
+    );
+
+    let revision = build_revision(
+        quote.revisions.len() as i32 + 1,
+        BookingParty::Worker,
+        build_line_items(&dto.line_items)?,
+        dto.tax_rate,
+        dto.estimated_hours,
+        Some(valid_until),
+        dto.message.clone(),
+    );
+
+    push_revision(db, &quote, &revision, QuoteStatus::Quoted).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Quote sent",
+        "quote_id": quote_id,
+        "status": QuoteStatus::Quoted,
+        "revision": revision.revision,
+        "subtotal": revision.subtotal,
+        "tax_amount": revision.tax_amount,
+        "total": revision.total,
+        "valid_until": valid_until
+    }))))
+}
+
+// ============================================================================
+// SHARED ENDPOINTS
+// ============================================================================
+
+/// Accept the other side's latest offer; this books the worker at that price
+#[openapi(tag = "Quote")]
+#[post("/quote/<quote_id>/accept")]
+pub async fn accept_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, _) = find_quote_for_turn(db, &quote_id, auth.user_id).await?;
+    let accepted = quote.latest().cloned().ok_or_else(|| ApiError::bad_request("No offer to accept yet"))?;
+
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": quote.worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    let now = DateTime::now();
+    let result = db.collection::<Quote>("quotes")
+        .update_one(
+            doc! {
+                "_id": quote.id,
+                "status": quote.status.as_str(),
+                "revisions": { "$size": quote.revisions.len() as i64 }
+            },
+            doc! { "$set": { "status": QuoteStatus::Accepted.as_str(), "accepted_at": now, "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to accept quote: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Quote was updated by someone else, please refresh"));
+    }
+
+    // Both sides agreed on the price, so the booking starts out accepted
+    let booking = Booking {
+        id: None,
+        customer_id: quote.customer_id,
+        worker_id: quote.worker_id,
+        worker_user_id: quote.worker_user_id,
+        category: quote.category.clone(),
+        subcategory: quote.subcategory.clone(),
+        description: Some(quote.description.clone()),
+        address: quote.address.clone(),
+        location: quote.location.clone(),
+        hourly_rate: worker.hourly_rate.unwrap_or(0.0),
+        estimated_hours: accepted.estimated_hours,
+        agreed_price: accepted.total,
+        preferred_start: quote.preferred_start,
+        quote_id: quote.id,
+        scheduled_start: None,
+        scheduled_end: None,
+        status: BookingStatus::Accepted,
+        decline_reason: None,
+        cancelled_by: None,
+        cancellation_reason: None,
+        requested_at: quote.created_at,
+        accepted_at: Some(now),
+        declined_at: None,
+        scheduled_at: None,
+        en_route_at: None,
+        started_at: None,
+        completed_at: None,
+        cancelled_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let inserted = db.collection::<Booking>("bookings")
+        .insert_one(&booking, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create booking: {}", e)))?;
+    let booking_id = inserted.inserted_id.as_object_id()
+        .ok_or_else(|| ApiError::internal_error("Invalid booking ID"))?;
+
+    db.collection::<Quote>("quotes")
+        .update_one(doc! { "_id": quote.id }, doc! { "$set": { "booking_id": booking_id } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to link booking: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote accepted and booking created".to_string(),
+        serde_json::json!({
+            "quote_id": quote_id,
+            "status": QuoteStatus::Accepted,
+            "booking_id": booking_id.to_hex(),
+            "agreed_price": accepted.total
+        })
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[post("/quote/<quote_id>/reject", data = "<dto>")]
+pub async fn reject_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<RejectQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let quote = find_quote(db, &quote_id).await?;
+    let party = quote_party(&quote, auth.user_id)?;
+
+    // Either side can walk away while the negotiation is open
+    if !quote.status.is_open() {
+        return Err(ApiError::bad_request(format!("Quote is already {}", quote.status.as_str())));
+    }
+
+    let result = db.collection::<Quote>("quotes")
+        .update_one(
+            doc! { "_id": quote.id, "status": quote.status.as_str() },
+            doc! {
+                "$set": {
+                    "status": QuoteStatus::Rejected.as_str(),
+                    "rejected_by": mongodb::bson::to_bson(&party).map_err(|e| ApiError::internal_error(e.to_string()))?,
+                    "rejection_reason": &dto.reason,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to reject quote: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Quote was updated by someone else, please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Quote rejected",
+        "quote_id": quote_id,
+        "status": QuoteStatus::Rejected
+    }))))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct QuoteListQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+async fn list_quotes(
+    db: &DbConn,
+    mut filter: Document,
+    query: QuoteListQuery,
+) -> Result<serde_json::Value, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "updated_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<Quote>("quotes")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut quotes = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let quote = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        quotes.push(quote);
+    }
+
+    let total = db.collection::<Quote>("quotes")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "quotes": quotes,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))
+}
+
+#[openapi(tag = "Quote")]
+#[get("/quote/customer?<query..>")]
+pub async fn get_customer_quotes(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: QuoteListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let quotes = list_quotes(db, doc! { "customer_id": auth.user_id }, query).await?;
+    Ok(Json(ApiResponse::success(quotes)))
+}
+
+#[openapi(tag = "Quote")]
+#[get("/quote/worker?<query..>")]
+pub async fn get_worker_quotes(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: QuoteListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let quotes = list_quotes(db, doc! { "worker_user_id": auth.user_id }, query).await?;
+    Ok(Json(ApiResponse::success(quotes)))
+}
+
+/// Full quote with every revision, oldest first
+#[openapi(tag = "Quote")]
+#[get("/quote/<quote_id>")]
+pub async fn get_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let quote = find_quote(db, &quote_id).await?;
+    quote_party(&quote, auth.user_id)?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "quote": quote,
+        "waiting_on": quote.status.waiting_on(),
+        "valid_until": quote.valid_until()
+    }))))
+}
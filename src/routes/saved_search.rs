@@ -0,0 +1,613 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket::response::content::RawHtml;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, Document, oid::ObjectId};
+use mongodb::options::FindOptions;
+use crate::db::DbConn;
+use crate::models::{
+    AlertFrequency, CreateSavedSearchDto, Employer, Job, JobSearchCriteria, JobSeekerProfile, JobSeekerSearchCriteria,
+    JobType, SavedSearch, SavedSearchKind, UpdateSavedSearchDto, User, MAX_SAVED_SEARCHES,
+};
+use crate::guards::AuthGuard;
+use crate::routes::analytics::DAY_MS;
+use crate::routes::job::{job_search_filter, job_seeker_search_filter};
+use crate::services::{EmailService, PushService};
+use crate::services::email::html_escape;
+use crate::utils::{ApiResponse, ApiError};
+
+/// Matches listed in a single alert; the rest are summarised as a count
+const ALERT_PREVIEW_SIZE: i64 = 5;
+
+const MAX_SEARCH_NAME_LENGTH: usize = 100;
+
+/// How long an alert run holds a search before another run may take it over
+const ALERT_LEASE_MS: i64 = 10 * 60 * 1000;
+
+// ============================================================================
+// SAVED SEARCH HELPERS
+// ============================================================================
+
+fn clean(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn clean_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Name is required"));
+    }
+    if name.chars().count() > MAX_SEARCH_NAME_LENGTH {
+        return Err(ApiError::bad_request(format!(
+            "Name can be at most {} characters",
+            MAX_SEARCH_NAME_LENGTH
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn clean_job_criteria(criteria: JobSearchCriteria) -> Result<JobSearchCriteria, ApiError> {
+    let criteria = JobSearchCriteria {
+        q: clean(criteria.q),
+        category: clean(criteria.category),
+        job_type: clean(criteria.job_type),
+        location: clean(criteria.location),
+        min_salary: criteria.min_salary,
+        max_salary: criteria.max_salary,
+    };
+    if let (Some(min), Some(max)) = (criteria.min_salary, criteria.max_salary)
+        && min > max
+    {
+        return Err(ApiError::bad_request("Minimum salary cannot exceed maximum salary"));
+    }
+    // Rejects anything the job feed would reject
+    job_search_filter(&criteria)?;
+    Ok(criteria)
+}
+
+fn clean_seeker_criteria(criteria: JobSeekerSearchCriteria) -> Result<JobSeekerSearchCriteria, ApiError> {
+    let job_type = match clean(criteria.job_type) {
+        Some(job_type) => Some(
+            JobType::parse(&job_type)
+                .ok_or_else(|| ApiError::bad_request("Job type must be fulltime, parttime, contract or freelance"))?
+                .as_str()
+                .to_string(),
+        ),
+        None => None,
+    };
+    let criteria = JobSeekerSearchCriteria {
+        skills: clean(criteria.skills),
+        category: clean(criteria.category),
+        min_experience: criteria.min_experience,
+        max_experience: criteria.max_experience,
+        location: clean(criteria.location),
+        job_type,
+    };
+    if let (Some(min), Some(max)) = (criteria.min_experience, criteria.max_experience)
+        && min > max
+    {
+        return Err(ApiError::bad_request("Minimum experience cannot exceed maximum experience"));
+    }
+    Ok(criteria)
+}
+
+/// Candidate alerts are an employer feature
+async fn ensure_employer(db: &DbConn, user_id: ObjectId) -> Result<(), ApiError> {
+    let employer = db.collection::<Employer>("employers")
+        .find_one(doc! { "owner_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if employer.is_none() {
+        return Err(ApiError::bad_request("Create your company profile before saving candidate searches"));
+    }
+    Ok(())
+}
+
+async fn find_own_search(db: &DbConn, search_id: &str, user_id: ObjectId) -> Result<SavedSearch, ApiError> {
+    let object_id = ObjectId::parse_str(search_id)
+        .map_err(|_| ApiError::bad_request("Invalid saved search ID"))?;
+
+    db.collection::<SavedSearch>("saved_searches")
+        .find_one(doc! { "_id": object_id, "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Saved search not found"))
+}
+
+fn unsubscribe_url(search: &SavedSearch) -> String {
+    format!(
+        "{}/api/v1/saved-searches/unsubscribe/{}",
+        crate::config::Config::public_api_url(),
+        search.unsubscribe_token
+    )
+}
+
+// ============================================================================
+// SAVED SEARCH ENDPOINTS
+// ============================================================================
+
+/// Save a job search (job seekers) or candidate search (employers) and get alerted on new matches
+#[openapi(tag = "SavedSearch")]
+#[post("/saved-searches", data = "<dto>")]
+pub async fn create_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateSavedSearchDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dto = dto.into_inner();
+    let name = clean_name(&dto.name)?;
+
+    let (job_criteria, seeker_criteria) = match dto.kind {
+        SavedSearchKind::Jobs => {
+            let criteria = dto.job_criteria
+                .ok_or_else(|| ApiError::bad_request("job_criteria is required for job searches"))?;
+            (Some(clean_job_criteria(criteria)?), None)
+        }
+        SavedSearchKind::JobSeekers => {
+            ensure_employer(db, auth.user_id).await?;
+            let criteria = dto.seeker_criteria
+                .ok_or_else(|| ApiError::bad_request("seeker_criteria is required for candidate searches"))?;
+            (None, Some(clean_seeker_criteria(criteria)?))
+        }
+    };
+
+    let existing = db.collection::<SavedSearch>("saved_searches")
+        .count_documents(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    if existing >= MAX_SAVED_SEARCHES {
+        return Err(ApiError::bad_request(format!(
+            "You can save at most {} searches",
+            MAX_SAVED_SEARCHES
+        )));
+    }
+
+    let now = DateTime::now();
+    let search = SavedSearch {
+        id: None,
+        user_id: auth.user_id,
+        name,
+        kind: dto.kind,
+        job_criteria,
+        seeker_criteria,
+        frequency: dto.frequency.unwrap_or(AlertFrequency::Daily),
+        email_alerts: dto.email_alerts.unwrap_or(true),
+        push_alerts: dto.push_alerts.unwrap_or(true),
+        is_active: true,
+        unsubscribe_token: uuid::Uuid::new_v4().simple().to_string(),
+        last_checked_at: now,
+        last_alerted_at: None,
+        lease_until: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db.collection::<SavedSearch>("saved_searches")
+        .insert_one(&search, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save search: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Search saved".to_string(),
+        serde_json::json!({
+            "saved_search_id": result.inserted_id.as_object_id().map(|id| id.to_hex())
+        }),
+    )))
+}
+
+#[openapi(tag = "SavedSearch")]
+#[get("/saved-searches")]
+pub async fn get_saved_searches(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let find_options = FindOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let mut cursor = db.collection::<SavedSearch>("saved_searches")
+        .find(doc! { "user_id": auth.user_id }, find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut searches = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let search = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        searches.push(search);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "saved_searches": searches,
+        "limit": MAX_SAVED_SEARCHES
+    }))))
+}
+
+/// Rename, change criteria, switch between instant and daily alerts, pick channels, or pause/resume
+#[openapi(tag = "SavedSearch")]
+#[put("/saved-searches/<search_id>", data = "<dto>")]
+pub async fn update_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    search_id: String,
+    dto: Json<UpdateSavedSearchDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let search = find_own_search(db, &search_id, auth.user_id).await?;
+    let dto = dto.into_inner();
+    let now = DateTime::now();
+
+    let mut update = doc! { "updated_at": now };
+    if let Some(ref name) = dto.name {
+        update.insert("name", clean_name(name)?);
+    }
+    if let Some(criteria) = dto.job_criteria {
+        if search.kind != SavedSearchKind::Jobs {
+            return Err(ApiError::bad_request("job_criteria only applies to job searches"));
+        }
+        let criteria = mongodb::bson::to_bson(&clean_job_criteria(criteria)?)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+        update.insert("job_criteria", criteria);
+    }
+    if let Some(criteria) = dto.seeker_criteria {
+        if search.kind != SavedSearchKind::JobSeekers {
+            return Err(ApiError::bad_request("seeker_criteria only applies to candidate searches"));
+        }
+        let criteria = mongodb::bson::to_bson(&clean_seeker_criteria(criteria)?)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+        update.insert("seeker_criteria", criteria);
+    }
+    if let Some(frequency) = dto.frequency {
+        let frequency = mongodb::bson::to_bson(&frequency)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+        update.insert("frequency", frequency);
+    }
+    if let Some(email_alerts) = dto.email_alerts {
+        update.insert("email_alerts", email_alerts);
+    }
+    if let Some(push_alerts) = dto.push_alerts {
+        update.insert("push_alerts", push_alerts);
+    }
+    if let Some(is_active) = dto.is_active {
+        update.insert("is_active", is_active);
+        // Resuming starts fresh rather than replaying everything missed while paused
+        if is_active && !search.is_active {
+            update.insert("last_checked_at", now);
+        }
+    }
+
+    db.collection::<SavedSearch>("saved_searches")
+        .update_one(doc! { "_id": search.id }, doc! { "$set": update }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update saved search: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Saved search updated",
+        "saved_search_id": search_id
+    }))))
+}
+
+#[openapi(tag = "SavedSearch")]
+#[delete("/saved-searches/<search_id>")]
+pub async fn delete_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    search_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let search = find_own_search(db, &search_id, auth.user_id).await?;
+
+    db.collection::<SavedSearch>("saved_searches")
+        .delete_one(doc! { "_id": search.id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to delete saved search: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Saved search deleted",
+        "saved_search_id": search_id
+    }))))
+}
+
+fn unsubscribe_page(title: &str, body: &str) -> RawHtml<String> {
+    RawHtml(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta name="viewport" content="width=device-width, initial-scale=1"><title>{title}</title></head>
+<body style="font-family: sans-serif; max-width: 480px; margin: 40px auto; padding: 0 16px;">
+    <h2>{title}</h2>
+    {body}
+</body>
+</html>"#
+    ))
+}
+
+/// Target of the link in alert emails. Only asks for confirmation, so link
+/// scanners that follow it do not unsubscribe anyone.
+#[openapi(tag = "SavedSearch")]
+#[get("/saved-searches/unsubscribe/<token>")]
+pub async fn confirm_unsubscribe_saved_search(
+    db: &State<DbConn>,
+    token: String,
+) -> Result<RawHtml<String>, ApiError> {
+    let search = db.collection::<SavedSearch>("saved_searches")
+        .find_one(doc! { "unsubscribe_token": &token }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Unsubscribe link is invalid or the search was deleted"))?;
+
+    let name = html_escape(&search.name);
+    if !search.is_active {
+        return Ok(unsubscribe_page(
+            "Already unsubscribed",
+            &format!("<p>You no longer get alerts for \"{}\".</p>", name),
+        ));
+    }
+    Ok(unsubscribe_page(
+        "Unsubscribe",
+        &format!(
+            r#"<p>Stop alerts for your saved search "{}"?</p>
+    <form method="post" action="{}"><button type="submit">Unsubscribe</button></form>"#,
+            name,
+            html_escape(&unsubscribe_url(&search)),
+        ),
+    ))
+}
+
+/// Unsubscribes the search. Posted by the confirmation page, or directly by mail
+/// clients that support one-click `List-Unsubscribe-Post`. No login needed; the
+/// token identifies the search.
+#[openapi(tag = "SavedSearch")]
+#[post("/saved-searches/unsubscribe/<token>")]
+pub async fn unsubscribe_saved_search(
+    db: &State<DbConn>,
+    token: String,
+) -> Result<RawHtml<String>, ApiError> {
+    let search = db.collection::<SavedSearch>("saved_searches")
+        .find_one_and_update(
+            doc! { "unsubscribe_token": &token },
+            doc! { "$set": { "is_active": false, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to unsubscribe: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Unsubscribe link is invalid or the search was deleted"))?;
+
+    Ok(unsubscribe_page(
+        "Unsubscribed",
+        &format!("<p>You will no longer get alerts for \"{}\".</p>", html_escape(&search.name)),
+    ))
+}
+
+// ============================================================================
+// ALERTS
+// ============================================================================
+
+#[derive(Debug, Default)]
+pub struct AlertRunSummary {
+    pub searches: usize,
+    pub alerted: usize,
+}
+
+/// One line per match plus how many there were in total
+struct AlertMatches {
+    total: u64,
+    lines: Vec<String>,
+}
+
+async fn new_job_matches(
+    db: &DbConn,
+    criteria: &JobSearchCriteria,
+    since: DateTime,
+    until: DateTime,
+) -> Result<AlertMatches, ApiError> {
+    let mut filter = job_search_filter(criteria)?;
+    filter.insert("published_at", doc! { "$gt": since, "$lte": until });
+
+    let total = db.collection::<Job>("jobs")
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    if total == 0 {
+        return Ok(AlertMatches { total, lines: Vec::new() });
+    }
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "published_at": -1 })
+        .limit(ALERT_PREVIEW_SIZE)
+        .build();
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(filter, find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut lines = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let job = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        let mut line = format!("• {}", job.title);
+        if let Some(company) = job.company {
+            line.push_str(&format!(" at {}", company));
+        }
+        if let Some(location) = job.location {
+            line.push_str(&format!(", {}", location));
+        }
+        lines.push(line);
+    }
+    Ok(AlertMatches { total, lines })
+}
+
+async fn new_job_seeker_matches(
+    db: &DbConn,
+    criteria: &JobSeekerSearchCriteria,
+    since: DateTime,
+    until: DateTime,
+) -> Result<AlertMatches, ApiError> {
+    let mut filter = job_seeker_search_filter(criteria);
+    filter.insert("verified_at", doc! { "$gt": since, "$lte": until });
+
+    let total = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    if total == 0 {
+        return Ok(AlertMatches { total, lines: Vec::new() });
+    }
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "verified_at": -1 })
+        .limit(ALERT_PREVIEW_SIZE)
+        .build();
+    let mut cursor = db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find(filter, find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut lines = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let profile = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        let mut line = format!("• {}", profile.full_name);
+        if let Some(headline) = profile.headline {
+            line.push_str(&format!(", {}", headline));
+        }
+        if let Some(years) = profile.experience_years {
+            line.push_str(&format!(" ({} yrs)", years));
+        }
+        lines.push(line);
+    }
+    Ok(AlertMatches { total, lines })
+}
+
+/// Finish a run: move the window on, or leave it for the next run to retry
+async fn release_saved_search(db: &DbConn, search: &SavedSearch, lease: DateTime, set: Document) -> Result<(), ApiError> {
+    let mut update = doc! { "$unset": { "lease_until": "" } };
+    if !set.is_empty() {
+        update.insert("$set", set);
+    }
+    db.collection::<SavedSearch>("saved_searches")
+        .update_one(doc! { "_id": search.id, "lease_until": lease }, update, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update saved search: {}", e)))?;
+    Ok(())
+}
+
+/// Check one search for matches since it was last checked and alert the owner.
+/// Returns whether an alert went out.
+async fn run_saved_search(db: &DbConn, search: &SavedSearch) -> Result<bool, ApiError> {
+    let since = search.last_checked_at;
+    let now = DateTime::now();
+    let lease = DateTime::from_millis(now.timestamp_millis() + ALERT_LEASE_MS);
+
+    // Lease the search so overlapping runs never alert twice for the same matches.
+    // `last_checked_at` only moves once the alert is out, so a failed send is retried.
+    let claimed = db.collection::<SavedSearch>("saved_searches")
+        .update_one(
+            doc! {
+                "_id": search.id,
+                "is_active": true,
+                "last_checked_at": since,
+                "$or": [{ "lease_until": null }, { "lease_until": { "$lte": now } }]
+            },
+            doc! { "$set": { "lease_until": lease } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update saved search: {}", e)))?;
+    if claimed.modified_count == 0 {
+        return Ok(false);
+    }
+
+    let (matches, noun) = match search.kind {
+        SavedSearchKind::Jobs => {
+            let criteria = search.job_criteria.clone().unwrap_or_default();
+            (new_job_matches(db, &criteria, since, now).await?, "job")
+        }
+        SavedSearchKind::JobSeekers => {
+            let criteria = search.seeker_criteria.clone().unwrap_or_default();
+            (new_job_seeker_matches(db, &criteria, since, now).await?, "candidate")
+        }
+    };
+    if matches.total == 0 {
+        release_saved_search(db, search, lease, doc! { "last_checked_at": now }).await?;
+        return Ok(false);
+    }
+
+    let user = db.collection::<User>("users")
+        .find_one(doc! { "_id": search.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    let Some(user) = user else {
+        release_saved_search(db, search, lease, doc! { "last_checked_at": now }).await?;
+        return Ok(false);
+    };
+
+    let plural = if matches.total == 1 { "" } else { "s" };
+    let title = format!("{} new {}{} for \"{}\"", matches.total, noun, plural, search.name);
+
+    let mut attempted = false;
+    let mut sent = false;
+    if search.email_alerts && let Some(ref email) = user.email {
+        let mut message = format!("New {}{} matching your saved search \"{}\":\n", noun, plural, search.name);
+        message.push_str(&matches.lines.join("\n"));
+        let shown = matches.lines.len() as u64;
+        if matches.total > shown {
+            message.push_str(&format!("\n…and {} more. Open the app to see them all.", matches.total - shown));
+        }
+        attempted = true;
+        sent |= EmailService::send_alert_email(email, &title, &message, &unsubscribe_url(search)).await;
+    }
+    if search.push_alerts && let Some(ref tokens) = user.fcm_token {
+        let data = serde_json::json!({
+            "type": "saved_search_alert",
+            "saved_search_id": search.id.map(|id| id.to_hex()),
+            "kind": search.kind,
+        });
+        let body = matches.lines.first().cloned().unwrap_or_default();
+        attempted = true;
+        sent |= PushService::send(tokens, &title, &body, data).await > 0;
+    }
+
+    let set = if sent {
+        doc! { "last_checked_at": now, "last_alerted_at": now }
+    } else if !attempted {
+        // Nowhere to deliver to, so there is nothing to retry
+        doc! { "last_checked_at": now }
+    } else {
+        doc! {}
+    };
+    release_saved_search(db, search, lease, set).await?;
+    Ok(sent)
+}
+
+/// Run every active search that is due: instant searches on every call, daily ones once a day
+pub async fn send_due_alerts(db: &DbConn) -> Result<AlertRunSummary, ApiError> {
+    let day_ago = DateTime::from_millis(DateTime::now().timestamp_millis() - DAY_MS);
+    let filter = doc! {
+        "is_active": true,
+        "$or": [
+            { "frequency": "instant" },
+            { "frequency": "daily", "last_checked_at": { "$lte": day_ago } }
+        ]
+    };
+
+    let mut cursor = db.collection::<SavedSearch>("saved_searches")
+        .find(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut due = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let search = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        due.push(search);
+    }
+
+    let mut summary = AlertRunSummary { searches: due.len(), alerted: 0 };
+    for search in &due {
+        match run_saved_search(db, search).await {
+            Ok(true) => summary.alerted += 1,
+            Ok(false) => {}
+            // One broken search shouldn't hold up everyone else's alerts
+            Err(e) => error!("Saved search {:?} failed: {}", search.id, e.message),
+        }
+    }
+    Ok(summary)
+}
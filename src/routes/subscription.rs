@@ -0,0 +1,1354 @@
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, Bson, DateTime, oid::ObjectId};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use crate::db::DbConn;
+use crate::models::{
+    Subscription, SubscriptionStatus, CancelSubscriptionDto, RefundPolicy, RenewalStatus,
+    UpdateAutoRenewDto, PlanDetails, PlanChangeKind, PendingPlanChange, ChangePlanDto, VerifyPlanChangeDto,
+    Payment, PaymentKind, PaymentStatus, Refund, RefundStatus,
+};
+use crate::guards::{AuthGuard, RazorpaySignature};
+use crate::routes::invoice::invoice_payment;
+use crate::services::{payment_gateway, MockGateway};
+use crate::utils::{ApiResponse, ApiError, is_duplicate_key};
+
+// ============================================================================
+// PAYMENTS LEDGER HELPERS
+// ============================================================================
+
+/// Record a freshly created Razorpay order in the payments ledger
+pub async fn record_order_payment(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_id: ObjectId,
+    kind: PaymentKind,
+    order: &serde_json::Value,
+    amount: f64,
+) -> Result<(), ApiError> {
+    let order_id = order["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::internal_error("Payment order is missing an id"))?;
+
+    let now = DateTime::now();
+    let payment = Payment {
+        id: None,
+        user_id,
+        subscription_id: Some(subscription_id),
+        kind,
+        razorpay_order_id: order_id.to_string(),
+        razorpay_payment_id: None,
+        amount,
+        currency: order["currency"].as_str().unwrap_or("INR").to_string(),
+        status: PaymentStatus::Created,
+        refunds: Vec::new(),
+        refunded_amount: 0.0,
+        captured_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    db.collection::<Payment>("payments")
+        .insert_one(&payment, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to record payment: {}", e)))?;
+
+    Ok(())
+}
+
+/// Mark a ledger entry as captured once its signature has been verified,
+/// then issue its GST invoice
+pub async fn record_captured_payment(
+    db: &DbConn,
+    order_id: &str,
+    payment_id: &str,
+) -> Result<(), ApiError> {
+    let payment = db.collection::<Payment>("payments")
+        .find_one_and_update(
+            doc! { "razorpay_order_id": order_id },
+            doc! {
+                "$set": {
+                    "razorpay_payment_id": payment_id,
+                    "status": "captured",
+                    "captured_at": DateTime::now(),
+                    "updated_at": DateTime::now()
+                }
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update payment: {}", e)))?;
+
+    if let Some(payment) = payment {
+        invoice_payment(db, &payment).await;
+    }
+
+    Ok(())
+}
+
+/// Refund owed for the unused part of a subscription, rounded down to the paisa
+pub fn prorated_refund(subscription: &Subscription, now: DateTime) -> f64 {
+    let total = subscription.expires_at.timestamp_millis() - subscription.starts_at.timestamp_millis();
+    let remaining = subscription.expires_at.timestamp_millis() - now.timestamp_millis();
+
+    if total <= 0 || remaining <= 0 {
+        return 0.0;
+    }
+
+    let amount = subscription.price * (remaining.min(total) as f64 / total as f64);
+    (amount * 100.0).floor() / 100.0
+}
+
+/// Status a payment should show once `refunded` of its `amount` has gone back
+fn refunded_payment_status(amount: f64, refunded: f64) -> PaymentStatus {
+    if refunded < 0.01 {
+        PaymentStatus::Captured
+    } else if amount - refunded < 0.01 {
+        PaymentStatus::Refunded
+    } else {
+        PaymentStatus::PartiallyRefunded
+    }
+}
+
+/// Issue a refund through Razorpay and append it to the payment's ledger entry.
+///
+/// The amount is reserved on the ledger before the gateway is called, with the refunded total in
+/// the filter, so concurrent refunds can never add up to more than was paid.
+pub async fn issue_refund(
+    db: &DbConn,
+    payment: &Payment,
+    amount: f64,
+    reason: Option<&str>,
+    initiated_by: Option<ObjectId>,
+) -> Result<Refund, ApiError> {
+    if !matches!(payment.status, PaymentStatus::Captured | PaymentStatus::PartiallyRefunded) {
+        return Err(ApiError::bad_request("Only captured payments can be refunded"));
+    }
+
+    let razorpay_payment_id = payment
+        .razorpay_payment_id
+        .as_deref()
+        .ok_or_else(|| ApiError::bad_request("Payment has no Razorpay payment ID"))?;
+
+    let refundable = payment.amount - payment.refunded_amount;
+    if amount <= 0.0 || amount > refundable + 0.001 {
+        return Err(ApiError::bad_request(format!(
+            "Refund amount must be between 0 and {:.2}",
+            refundable
+        )));
+    }
+
+    // Placeholder until the gateway assigns the real refund id
+    let placeholder_id = format!("pending_{}", uuid::Uuid::new_v4().simple());
+    let now = DateTime::now();
+    let reserved = Refund {
+        refund_id: placeholder_id.clone(),
+        amount,
+        status: RefundStatus::Pending,
+        reason: reason.map(|r| r.to_string()),
+        initiated_by,
+        created_at: now,
+        processed_at: None,
+    };
+    let reserved_bson = mongodb::bson::to_bson(&reserved)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize refund: {}", e)))?;
+
+    let reservation = db.collection::<Payment>("payments")
+        .find_one_and_update(
+            doc! {
+                "_id": payment.id,
+                "status": { "$in": ["captured", "partially_refunded"] },
+                "refunded_amount": { "$lte": payment.amount - amount + 0.001 }
+            },
+            doc! {
+                "$push": { "refunds": reserved_bson },
+                "$inc": { "refunded_amount": amount },
+                "$set": { "updated_at": now }
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to reserve refund: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Payment was refunded in the meantime, please refresh"))?;
+
+    let res = match payment_gateway().refund(razorpay_payment_id, (amount * 100.0).round() as i64, reason).await {
+        Ok(res) => res,
+        Err(e) => {
+            // Give the reserved amount back so the refund can be retried
+            let release = db.collection::<Payment>("payments")
+                .update_one(
+                    doc! { "_id": payment.id, "refunds.refund_id": &placeholder_id },
+                    doc! {
+                        "$pull": { "refunds": { "refund_id": &placeholder_id } },
+                        "$inc": { "refunded_amount": -amount },
+                        "$set": { "updated_at": DateTime::now() }
+                    },
+                    None,
+                )
+                .await;
+            if let Err(release_error) = release {
+                error!("Failed to release refund reservation on payment {:?}: {}", payment.id, release_error);
+            }
+            return Err(ApiError::internal_error(format!("Failed to create refund: {}", e)));
+        }
+    };
+
+    let refund_id = res["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::internal_error("Refund response is missing an id"))?;
+    let status = RefundStatus::from_razorpay(res["status"].as_str().unwrap_or("pending"));
+    let processed_at = if status == RefundStatus::Processed { Some(now) } else { None };
+
+    let refund = Refund {
+        refund_id: refund_id.to_string(),
+        status: status.clone(),
+        processed_at,
+        ..reserved
+    };
+
+    let payment_status = refunded_payment_status(reservation.amount, reservation.refunded_amount);
+    let refund_status_bson = mongodb::bson::to_bson(&status)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize status: {}", e)))?;
+    let status_bson = mongodb::bson::to_bson(&payment_status)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize status: {}", e)))?;
+
+    db.collection::<Payment>("payments")
+        .update_one(
+            doc! { "_id": payment.id, "refunds.refund_id": &placeholder_id },
+            doc! {
+                "$set": {
+                    "refunds.$.refund_id": refund_id,
+                    "refunds.$.status": refund_status_bson,
+                    "refunds.$.processed_at": processed_at,
+                    "status": status_bson,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to record refund: {}", e)))?;
+
+    if status == RefundStatus::Processed {
+        complete_refund(db, payment).await?;
+    }
+
+    Ok(refund)
+}
+
+/// Pull the latest refund state from Razorpay and apply it to the ledger
+pub async fn sync_refund(
+    db: &DbConn,
+    payment: &Payment,
+    refund_id: &str,
+) -> Result<RefundStatus, ApiError> {
+    let razorpay_payment_id = payment
+        .razorpay_payment_id
+        .as_deref()
+        .ok_or_else(|| ApiError::bad_request("Payment has no Razorpay payment ID"))?;
+
+    let res = payment_gateway().fetch_refund(razorpay_payment_id, refund_id)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to fetch refund: {}", e)))?;
+
+    let status = RefundStatus::from_razorpay(res["status"].as_str().unwrap_or("pending"));
+    apply_refund_status(db, payment, refund_id, &status).await?;
+
+    Ok(status)
+}
+
+/// Store a refund state reported by Razorpay, completing the refund when it has been processed
+async fn apply_refund_status(
+    db: &DbConn,
+    payment: &Payment,
+    refund_id: &str,
+    status: &RefundStatus,
+) -> Result<(), ApiError> {
+    let refund = payment.refunds.iter()
+        .find(|r| r.refund_id == refund_id)
+        .ok_or_else(|| ApiError::not_found("Refund not found"))?;
+
+    let status_bson = mongodb::bson::to_bson(status)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize status: {}", e)))?;
+
+    let mut set_doc = doc! {
+        "refunds.$.status": status_bson,
+        "updated_at": DateTime::now(),
+    };
+    if *status == RefundStatus::Processed {
+        set_doc.insert("refunds.$.processed_at", DateTime::now());
+    }
+
+    // A failed refund hands its amount back, once, by matching only refunds not yet marked failed
+    let mut filter = doc! { "_id": payment.id, "refunds.refund_id": refund_id };
+    let mut update = doc! { "$set": set_doc };
+    if *status == RefundStatus::Failed {
+        filter = doc! {
+            "_id": payment.id,
+            "refunds": { "$elemMatch": { "refund_id": refund_id, "status": { "$ne": "failed" } } }
+        };
+        update.insert("$inc", doc! { "refunded_amount": -refund.amount });
+    }
+
+    let updated = db
+        .collection::<Payment>("payments")
+        .find_one_and_update(
+            filter,
+            update,
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update refund: {}", e)))?;
+
+    if *status == RefundStatus::Failed
+        && let Some(updated) = updated
+    {
+        let payment_status = mongodb::bson::to_bson(&refunded_payment_status(updated.amount, updated.refunded_amount))
+            .map_err(|e| ApiError::internal_error(format!("Failed to serialize status: {}", e)))?;
+        db.collection::<Payment>("payments")
+            .update_one(doc! { "_id": payment.id }, doc! { "$set": { "status": payment_status } }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update payment: {}", e)))?;
+    }
+
+    if *status == RefundStatus::Processed {
+        complete_refund(db, payment).await?;
+    }
+
+    Ok(())
+}
+
+/// Once money is back with the user, a cancelled subscription no longer backs the profile plan
+async fn complete_refund(db: &DbConn, payment: &Payment) -> Result<(), ApiError> {
+    let Some(subscription_id) = payment.subscription_id else {
+        return Ok(());
+    };
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(doc! { "_id": subscription_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    match subscription {
+        Some(sub) if sub.status == SubscriptionStatus::Cancelled => downgrade_profile(db, &sub).await,
+        _ => Ok(()),
+    }
+}
+
+/// Reset the worker / job seeker profile plan that was granted by this subscription
+pub async fn downgrade_profile(db: &DbConn, subscription: &Subscription) -> Result<(), ApiError> {
+    db.collection::<mongodb::bson::Document>(subscription.subscription_type.profile_collection())
+        .update_one(
+            doc! { "user_id": subscription.user_id },
+            doc! {
+                "$set": {
+                    "subscription_plan": "none",
+                    "subscription_expires_at": DateTime::now(),
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to downgrade profile: {}", e)))?;
+
+    Ok(())
+}
+
+// ============================================================================
+// AUTO-RENEW HELPERS
+// ============================================================================
+
+/// Number of renewal cycles requested from Razorpay for one mandate
+const AUTO_RENEW_MAX_CYCLES: i64 = 10;
+
+/// Days an auto-renewing subscription stays active past expiry while Razorpay retries the charge
+pub const RENEWAL_GRACE_DAYS: i64 = 3;
+
+/// Look up (or lazily create) the Razorpay plan mirroring one of our catalog plans
+async fn ensure_gateway_plan(db: &DbConn, subscription: &Subscription, plan: &PlanDetails) -> Result<String, ApiError> {
+    let collection = db.collection::<mongodb::bson::Document>("gateway_plans");
+    let subscription_type = mongodb::bson::to_bson(&subscription.subscription_type)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize type: {}", e)))?;
+    let amount_paise = (plan.price * 100.0).round() as i64;
+
+    let filter = doc! {
+        "subscription_type": subscription_type.clone(),
+        "plan_name": plan.name,
+        "amount_paise": amount_paise,
+    };
+
+    let existing = collection
+        .find_one(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if let Some(plan_id) = existing.as_ref().and_then(|d| d.get_str("razorpay_plan_id").ok()) {
+        return Ok(plan_id.to_string());
+    }
+
+    let (period, interval) = plan.gateway_period();
+    let res = payment_gateway().create_plan(&format!("Mento {}", plan.name), period, interval, amount_paise)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create gateway plan: {}", e)))?;
+
+    let plan_id = res["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::internal_error("Gateway plan response is missing an id"))?
+        .to_string();
+
+    collection
+        .insert_one(
+            doc! {
+                "subscription_type": subscription_type,
+                "plan_name": plan.name,
+                "amount_paise": amount_paise,
+                "razorpay_plan_id": &plan_id,
+                "created_at": DateTime::now(),
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to store gateway plan: {}", e)))?;
+
+    Ok(plan_id)
+}
+
+/// Cancel a subscription immediately: the plan ends now and the gateway mandate is stopped
+pub async fn end_subscription(db: &DbConn, subscription: &Subscription, reason: Option<&str>) -> Result<(), ApiError> {
+    let now = DateTime::now();
+    let result = db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": subscription.id, "status": { "$in": ["active", "pending"] } },
+            doc! {
+                "$set": {
+                    "status": "cancelled",
+                    "auto_renew": false,
+                    "renewal_status": Bson::Null,
+                    "expires_at": now,
+                    "cancelled_at": now,
+                    "cancellation_reason": reason,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to cancel subscription: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Ok(());
+    }
+
+    downgrade_profile(db, subscription).await?;
+
+    // The subscription is already over on our side, so a mandate that can't be stopped here
+    // is logged for stopping from the gateway dashboard rather than failing the cancellation
+    if let Err(e) = stop_gateway_renewal(subscription).await {
+        error!("Failed to stop gateway renewal for cancelled subscription {:?}: {}", subscription.id, e.message);
+    }
+
+    Ok(())
+}
+
+/// Cancel the Razorpay recurring subscription so no further charges are made
+pub async fn stop_gateway_renewal(subscription: &Subscription) -> Result<(), ApiError> {
+    if let Some(ref razorpay_subscription_id) = subscription.razorpay_subscription_id
+        && subscription.renewal_status.is_some()
+    {
+        payment_gateway().cancel_subscription(razorpay_subscription_id)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to stop auto-renew: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// A scheduled downgrade rides on the renewal mandate, so it goes when the mandate is stopped
+async fn drop_scheduled_downgrade(db: &DbConn, sub_id: ObjectId) -> Result<(), ApiError> {
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": sub_id, "pending_change.kind": "downgrade" },
+            doc! { "$set": { "pending_change": Bson::Null } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Extend a subscription by one period after Razorpay charged a renewal
+async fn apply_renewal_charge(
+    db: &DbConn,
+    subscription: &Subscription,
+    payment_entity: &serde_json::Value,
+) -> Result<(), ApiError> {
+    let razorpay_payment_id = payment_entity["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::bad_request("Charged event is missing a payment"))?;
+
+    // A downgrade scheduled for this period end takes over from the renewed period
+    let plan_name = match subscription.pending_change {
+        Some(ref change) if change.kind == PlanChangeKind::Downgrade => change.plan_name.clone(),
+        _ => subscription.plan_name.clone(),
+    };
+
+    let duration_days = subscription
+        .subscription_type
+        .find_plan(&plan_name)
+        .map(|p| p.duration_days)
+        .ok_or_else(|| ApiError::internal_error("Subscription plan no longer exists"))?;
+
+    let now = DateTime::now();
+    let period_start = subscription.expires_at.max(now);
+    let period_end = DateTime::from_millis(period_start.timestamp_millis() + duration_days * 24 * 60 * 60 * 1000);
+    let amount = payment_entity["amount"].as_i64().unwrap_or(0) as f64 / 100.0;
+
+    let mut payment = Payment {
+        id: None,
+        user_id: subscription.user_id,
+        subscription_id: subscription.id,
+        kind: PaymentKind::Renewal,
+        razorpay_order_id: payment_entity["order_id"].as_str().unwrap_or_default().to_string(),
+        razorpay_payment_id: Some(razorpay_payment_id.to_string()),
+        amount,
+        currency: payment_entity["currency"].as_str().unwrap_or("INR").to_string(),
+        status: PaymentStatus::Captured,
+        refunds: Vec::new(),
+        refunded_amount: 0.0,
+        captured_at: Some(now),
+        created_at: now,
+        updated_at: now,
+    };
+
+    // Webhooks can be delivered more than once; the unique index on the gateway payment id
+    // makes recording the charge the claim on processing it
+    let result = match db.collection::<Payment>("payments").insert_one(&payment, None).await {
+        Ok(result) => result,
+        Err(e) if is_duplicate_key(&e) => return Ok(()),
+        Err(e) => return Err(ApiError::internal_error(format!("Failed to record payment: {}", e))),
+    };
+    payment.id = result.inserted_id.as_object_id();
+
+    let renewed = db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": subscription.id },
+            doc! {
+                "$set": {
+                    "status": "active",
+                    "plan_name": &plan_name,
+                    "starts_at": period_start,
+                    "expires_at": period_end,
+                    "price": amount,
+                    "payment_id": razorpay_payment_id,
+                    "auto_renew": true,
+                    "renewal_status": "active",
+                    "renewal_attempts": 0,
+                    "pending_change": Bson::Null,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await;
+    if let Err(e) = renewed {
+        // Drop the claim so the gateway's retry of this webhook renews the subscription
+        if let Err(e) = db.collection::<Payment>("payments").delete_one(doc! { "_id": payment.id }, None).await {
+            error!("Failed to release renewal payment {}: {}", razorpay_payment_id, e);
+        }
+        return Err(ApiError::internal_error(format!("Failed to renew subscription: {}", e)));
+    }
+
+    db.collection::<mongodb::bson::Document>(subscription.subscription_type.profile_collection())
+        .update_one(
+            doc! { "user_id": subscription.user_id },
+            doc! {
+                "$set": {
+                    "subscription_plan": &plan_name,
+                    "subscription_expires_at": period_end,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update profile: {}", e)))?;
+
+
+    invoice_payment(db, &payment).await;
+    Ok(())
+}
+
+// ============================================================================
+// CANCELLATION
+// ============================================================================
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/cancel", data = "<dto>")]
+pub async fn cancel_subscription(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CancelSubscriptionDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let sub_id = ObjectId::parse_str(&dto.subscription_id)
+        .map_err(|_| ApiError::bad_request("Invalid subscription ID"))?;
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "_id": sub_id,
+                "user_id": auth.user_id,
+                "status": "active"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Active subscription not found"))?;
+
+    let now = DateTime::now();
+
+    if !dto.immediate {
+        if subscription.cancel_at_period_end {
+            return Err(ApiError::bad_request("Subscription is already set to cancel at period end"));
+        }
+
+        stop_gateway_renewal(&subscription).await?;
+
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub_id },
+                doc! {
+                    "$set": {
+                        "cancel_at_period_end": true,
+                        "auto_renew": false,
+                        "renewal_status": Bson::Null,
+                        "cancelled_at": now,
+                        "cancellation_reason": &dto.reason,
+                        "updated_at": now
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        drop_scheduled_downgrade(db, sub_id).await?;
+
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "message": "Subscription will be cancelled at the end of the current period",
+            "subscription_id": sub_id.to_hex(),
+            "expires_at": subscription.expires_at
+        }))));
+    }
+
+    let refund_policy = subscription
+        .subscription_type
+        .find_plan(&subscription.plan_name)
+        .map(|p| p.refund_policy)
+        .unwrap_or(RefundPolicy::NonRefundable);
+
+    let refund_amount = match refund_policy {
+        RefundPolicy::Prorated => prorated_refund(&subscription, now),
+        RefundPolicy::NonRefundable => 0.0,
+    };
+
+    // After an upgrade the latest payment is only the prorated top-up, so the refund is
+    // spread over every payment on the subscription, newest first
+    let mut cursor = db
+        .collection::<Payment>("payments")
+        .find(
+            doc! {
+                "subscription_id": sub_id,
+                "status": { "$in": ["captured", "partially_refunded"] }
+            },
+            FindOptions::builder().sort(doc! { "created_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let mut payments = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(e.to_string()))? {
+        payments.push(cursor.deserialize_current().map_err(|e| ApiError::internal_error(e.to_string()))?);
+    }
+
+    // Refund before cancelling, so a failed refund leaves the subscription untouched
+    let mut refunds = Vec::new();
+    let mut outstanding = refund_amount;
+    for payment in &payments {
+        let amount = (outstanding.min(payment.amount - payment.refunded_amount) * 100.0).floor() / 100.0;
+        if amount < 0.01 {
+            continue;
+        }
+        match issue_refund(db, payment, amount, dto.reason.as_deref(), None).await {
+            Ok(refund) => {
+                outstanding -= refund.amount;
+                refunds.push(refund);
+            }
+            Err(e) if refunds.is_empty() => return Err(e),
+            Err(e) => {
+                // Part of the money is already on its way back, so the plan still ends;
+                // the rest can be refunded from the admin payments endpoint
+                error!(
+                    "Refund of {:.2} on payment {:?} failed while cancelling subscription {}: {}",
+                    amount, payment.id, sub_id, e.message
+                );
+                break;
+            }
+        }
+        if outstanding < 0.01 {
+            break;
+        }
+    }
+    let refunded: f64 = refunds.iter().map(|r| r.amount).sum();
+
+    end_subscription(db, &subscription, dto.reason.as_deref()).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Subscription cancelled",
+        "subscription_id": sub_id.to_hex(),
+        "refund_amount": (refunded * 100.0).round() / 100.0,
+        "refund_outstanding": ((refund_amount - refunded).max(0.0) * 100.0).round() / 100.0,
+        "refunds": refunds.iter().map(|r| serde_json::json!({
+            "refund_id": r.refund_id,
+            "amount": r.amount,
+            "status": r.status
+        })).collect::<Vec<_>>()
+    }))))
+}
+
+// ============================================================================
+// AUTO-RENEW
+// ============================================================================
+
+#[openapi(tag = "Subscription")]
+#[put("/subscription/auto-renew", data = "<dto>")]
+pub async fn update_auto_renew(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateAutoRenewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let sub_id = ObjectId::parse_str(&dto.subscription_id)
+        .map_err(|_| ApiError::bad_request("Invalid subscription ID"))?;
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "_id": sub_id,
+                "user_id": auth.user_id,
+                "status": "active"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Active subscription not found"))?;
+
+    if !dto.enabled {
+        stop_gateway_renewal(&subscription).await?;
+
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub_id },
+                doc! {
+                    "$set": {
+                        "auto_renew": false,
+                        "renewal_status": Bson::Null,
+                        "updated_at": DateTime::now()
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        drop_scheduled_downgrade(db, sub_id).await?;
+
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "message": "Auto-renew disabled",
+            "subscription_id": sub_id.to_hex(),
+            "expires_at": subscription.expires_at
+        }))));
+    }
+
+    if matches!(
+        subscription.renewal_status,
+        Some(RenewalStatus::AwaitingAuthorization | RenewalStatus::Active | RenewalStatus::Retrying)
+    ) {
+        return Err(ApiError::bad_request("Auto-renew is already enabled for this subscription"));
+    }
+
+    let plan = subscription
+        .subscription_type
+        .find_plan(&subscription.plan_name)
+        .ok_or_else(|| ApiError::bad_request("This plan does not support auto-renew"))?;
+
+    let gateway_plan_id = ensure_gateway_plan(db, &subscription, plan).await?;
+
+    // First recurring charge lands when the current period ends
+    let gateway_subscription = payment_gateway().create_subscription(
+        &gateway_plan_id,
+        AUTO_RENEW_MAX_CYCLES,
+        subscription.expires_at.timestamp_millis() / 1000,
+        serde_json::json!({ "subscription_id": sub_id.to_hex() }),
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to enable auto-renew: {}", e)))?;
+
+    let razorpay_subscription_id = gateway_subscription["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::internal_error("Gateway subscription response is missing an id"))?;
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": sub_id },
+            doc! {
+                "$set": {
+                    "razorpay_subscription_id": razorpay_subscription_id,
+                    "renewal_status": "awaiting_authorization",
+                    "renewal_attempts": 0,
+                    "cancel_at_period_end": false,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Authorize the mandate to turn on auto-renew".to_string(),
+        serde_json::json!({
+            "subscription_id": sub_id.to_hex(),
+            "razorpay_subscription_id": razorpay_subscription_id,
+            "authorization_url": gateway_subscription["short_url"]
+        }),
+    )))
+}
+
+// ============================================================================
+// PLAN CHANGES
+// ============================================================================
+
+/// Razorpay does not accept orders below ₹1
+const MIN_ORDER_AMOUNT: f64 = 1.0;
+
+/// Swap an active subscription onto a new plan, starting a fresh period now.
+/// The filter on the pending order makes the swap happen at most once.
+async fn apply_upgrade(
+    db: &DbConn,
+    subscription: &Subscription,
+    change: &PendingPlanChange,
+    payment_id: Option<&str>,
+) -> Result<DateTime, ApiError> {
+    let plan = subscription
+        .subscription_type
+        .find_plan(&change.plan_name)
+        .ok_or_else(|| ApiError::internal_error("Subscription plan no longer exists"))?;
+
+    let now = DateTime::now();
+    let expires_at = DateTime::from_millis(now.timestamp_millis() + plan.duration_days * 24 * 60 * 60 * 1000);
+
+    let mut filter = doc! { "_id": subscription.id, "status": "active" };
+    match change.razorpay_order_id {
+        Some(ref order_id) => filter.insert("pending_change.razorpay_order_id", order_id),
+        None => filter.insert("pending_change.kind", "upgrade"),
+    };
+
+    let mut set_doc = doc! {
+        "plan_name": plan.name,
+        "price": plan.price,
+        "starts_at": now,
+        "expires_at": expires_at,
+        "pending_change": Bson::Null,
+        // The running mandate is for the old plan, so auto-renew has to be re-enabled
+        "auto_renew": false,
+        "renewal_status": Bson::Null,
+        "updated_at": now,
+    };
+    if let Some(payment_id) = payment_id {
+        set_doc.insert("payment_id", payment_id);
+    }
+
+    let result = db
+        .collection::<Subscription>("subscriptions")
+        .update_one(filter, doc! { "$set": set_doc }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to change plan: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Plan change has already been applied or was replaced"));
+    }
+
+    if let Err(e) = stop_gateway_renewal(subscription).await {
+        error!("Failed to stop old auto-renew mandate for {:?}: {}", subscription.id, e.message);
+    }
+
+    db.collection::<mongodb::bson::Document>(subscription.subscription_type.profile_collection())
+        .update_one(
+            doc! { "user_id": subscription.user_id },
+            doc! {
+                "$set": {
+                    "subscription_plan": plan.name,
+                    "subscription_expires_at": expires_at,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update profile: {}", e)))?;
+
+    Ok(expires_at)
+}
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/change-plan", data = "<dto>")]
+pub async fn change_plan(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<ChangePlanDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let sub_id = ObjectId::parse_str(&dto.subscription_id)
+        .map_err(|_| ApiError::bad_request("Invalid subscription ID"))?;
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "_id": sub_id,
+                "user_id": auth.user_id,
+                "status": "active"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Active subscription not found"))?;
+
+    if subscription.cancel_at_period_end {
+        return Err(ApiError::bad_request("Subscription is set to cancel at period end"));
+    }
+
+    let new_plan = subscription
+        .subscription_type
+        .find_plan(&dto.plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan"))?;
+
+    let now = DateTime::now();
+
+    // Choosing the current plan again withdraws a scheduled change
+    if new_plan.name == subscription.plan_name.to_lowercase() {
+        if subscription.pending_change.is_none() {
+            return Err(ApiError::bad_request("You are already on this plan"));
+        }
+
+        // A scheduled downgrade already moved the mandate onto the cheaper plan, so move it back
+        if subscription.pending_change.as_ref().is_some_and(|c| c.kind == PlanChangeKind::Downgrade)
+            && matches!(subscription.renewal_status, Some(RenewalStatus::Active | RenewalStatus::Retrying))
+            && let Some(ref razorpay_subscription_id) = subscription.razorpay_subscription_id
+        {
+            let gateway_plan_id = ensure_gateway_plan(db, &subscription, new_plan).await?;
+            payment_gateway().update_subscription_plan(razorpay_subscription_id, &gateway_plan_id)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Failed to restore auto-renew plan: {}", e)))?;
+        }
+
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub_id },
+                doc! { "$set": { "pending_change": Bson::Null, "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "message": "Pending plan change withdrawn",
+            "subscription_id": sub_id.to_hex()
+        }))));
+    }
+
+    let current_price = subscription
+        .subscription_type
+        .find_plan(&subscription.plan_name)
+        .map(|p| p.price)
+        .unwrap_or(subscription.price);
+
+    if new_plan.price < current_price {
+        // Downgrades happen at renewal, so without a running mandate there is nothing to switch
+        let Some(razorpay_subscription_id) = subscription
+            .razorpay_subscription_id
+            .as_deref()
+            .filter(|_| matches!(subscription.renewal_status, Some(RenewalStatus::Active | RenewalStatus::Retrying)))
+        else {
+            return Err(ApiError::bad_request(format!(
+                "Downgrades take effect at renewal. Turn on auto-renew first, or subscribe to {} once this plan ends",
+                new_plan.name
+            )));
+        };
+
+        let change = PendingPlanChange {
+            plan_name: new_plan.name.to_string(),
+            kind: PlanChangeKind::Downgrade,
+            price: new_plan.price,
+            credit: 0.0,
+            amount_due: 0.0,
+            razorpay_order_id: None,
+            effective_at: subscription.expires_at,
+            requested_at: now,
+        };
+
+        // Point the recurring mandate at the cheaper plan from the next cycle
+        let gateway_plan_id = ensure_gateway_plan(db, &subscription, new_plan).await?;
+        payment_gateway().update_subscription_plan(razorpay_subscription_id, &gateway_plan_id)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update auto-renew plan: {}", e)))?;
+
+        let change_bson = mongodb::bson::to_bson(&change)
+            .map_err(|e| ApiError::internal_error(format!("Failed to serialize plan change: {}", e)))?;
+
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub_id },
+                doc! { "$set": { "pending_change": change_bson, "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "message": format!("Your plan will change to {} at the end of the current period", new_plan.name),
+            "subscription_id": sub_id.to_hex(),
+            "effective_at": subscription.expires_at
+        }))));
+    }
+
+    // Upgrade: the unused part of the current period is credited against the new plan
+    let credit = prorated_refund(&subscription, now).min(subscription.price);
+    let amount_due = ((new_plan.price - credit) * 100.0).ceil() / 100.0;
+
+    let mut change = PendingPlanChange {
+        plan_name: new_plan.name.to_string(),
+        kind: PlanChangeKind::Upgrade,
+        price: new_plan.price,
+        credit,
+        amount_due: 0.0,
+        razorpay_order_id: None,
+        effective_at: now,
+        requested_at: now,
+    };
+
+    // Below the gateway minimum the difference is waived rather than rounded up
+    if amount_due < MIN_ORDER_AMOUNT {
+        let change_bson = mongodb::bson::to_bson(&change)
+            .map_err(|e| ApiError::internal_error(format!("Failed to serialize plan change: {}", e)))?;
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub_id },
+                doc! { "$set": { "pending_change": change_bson } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        let expires_at = apply_upgrade(db, &subscription, &change, None).await?;
+
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "message": format!("Plan changed to {}", new_plan.name),
+            "subscription_id": sub_id.to_hex(),
+            "credit": credit,
+            "amount_due": 0.0,
+            "expires_at": expires_at
+        }))));
+    }
+
+    let order = payment_gateway().create_order(amount_due)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create payment order: {}", e)))?;
+
+    let order_id = order["id"]
+        .as_str()
+        .ok_or_else(|| ApiError::internal_error("Payment order is missing an id"))?;
+
+    change.amount_due = amount_due;
+    change.razorpay_order_id = Some(order_id.to_string());
+
+    let change_bson = mongodb::bson::to_bson(&change)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize plan change: {}", e)))?;
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": sub_id },
+            doc! { "$set": { "pending_change": change_bson, "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    record_order_payment(db, auth.user_id, sub_id, PaymentKind::Upgrade, &order, amount_due).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "subscription_id": sub_id.to_hex(),
+        "order": order,
+        "plan_name": new_plan.name,
+        "price": new_plan.price,
+        "credit": credit,
+        "amount_due": amount_due
+    }))))
+}
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/change-plan/verify", data = "<dto>")]
+pub async fn verify_plan_change(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<VerifyPlanChangeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let valid = payment_gateway().verify_payment_signature(
+        &dto.razorpay_order_id,
+        &dto.razorpay_payment_id,
+        &dto.razorpay_signature,
+    )
+    .map_err(ApiError::internal_error)?;
+
+    if !valid {
+        return Err(ApiError::bad_request("Invalid payment signature"));
+    }
+
+    let sub_id = ObjectId::parse_str(&dto.subscription_id)
+        .map_err(|_| ApiError::bad_request("Invalid subscription ID"))?;
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "_id": sub_id,
+                "user_id": auth.user_id,
+                "pending_change.razorpay_order_id": &dto.razorpay_order_id
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Pending plan change not found"))?;
+
+    let change = subscription
+        .pending_change
+        .clone()
+        .ok_or_else(|| ApiError::not_found("Pending plan change not found"))?;
+
+    let expires_at = apply_upgrade(db, &subscription, &change, Some(&dto.razorpay_payment_id)).await?;
+
+    record_captured_payment(db, &dto.razorpay_order_id, &dto.razorpay_payment_id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Payment verified successfully",
+        "subscription": {
+            "id": sub_id.to_hex(),
+            "plan_name": change.plan_name,
+            "status": "active",
+            "expires_at": expires_at
+        }
+    }))))
+}
+
+// ============================================================================
+// RAZORPAY WEBHOOK
+// ============================================================================
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/webhook", data = "<body>")]
+pub async fn razorpay_webhook(
+    db: &State<DbConn>,
+    signature: RazorpaySignature,
+    body: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let event = payment_gateway()
+        .parse_webhook(&body, &signature.0)
+        .map_err(ApiError::unauthorized)?;
+    let event_name = event.event.as_str();
+    let payload = &event.payload;
+
+    if event_name.starts_with("refund.") {
+        let refund = &payload["refund"]["entity"];
+        let (Some(refund_id), Some(payment_id)) = (refund["id"].as_str(), refund["payment_id"].as_str()) else {
+            return Err(ApiError::bad_request("Refund event is missing ids"));
+        };
+
+        let payment = db
+            .collection::<Payment>("payments")
+            .find_one(doc! { "razorpay_payment_id": payment_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        if let Some(payment) = payment {
+            let status = RefundStatus::from_razorpay(refund["status"].as_str().unwrap_or("pending"));
+            // Refunds issued from the Razorpay dashboard are not in our ledger
+            match apply_refund_status(db, &payment, refund_id, &status).await {
+                Ok(()) => {}
+                Err(e) if e.status == rocket::http::Status::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+    } else if event_name.starts_with("subscription.") {
+        let Some(razorpay_subscription_id) = payload["subscription"]["entity"]["id"].as_str() else {
+            return Err(ApiError::bad_request("Subscription event is missing an id"));
+        };
+
+        let subscription = db
+            .collection::<Subscription>("subscriptions")
+            .find_one(doc! { "razorpay_subscription_id": razorpay_subscription_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        if let Some(subscription) = subscription {
+            let update = match event_name {
+                "subscription.authenticated" | "subscription.activated" => Some(doc! {
+                    "auto_renew": true,
+                    "renewal_status": "active",
+                }),
+                "subscription.charged" => {
+                    apply_renewal_charge(db, &subscription, &payload["payment"]["entity"]).await?;
+                    None
+                }
+                "subscription.pending" => Some(doc! {
+                    "renewal_status": "retrying",
+                    "renewal_attempts": subscription.renewal_attempts + 1,
+                }),
+                // Retries exhausted: stop renewing and let the expiry sweep end the plan
+                "subscription.halted" => Some(doc! {
+                    "auto_renew": false,
+                    "renewal_status": "halted",
+                }),
+                "subscription.cancelled" | "subscription.completed" => Some(doc! {
+                    "auto_renew": false,
+                    "renewal_status": Bson::Null,
+                }),
+                _ => None,
+            };
+
+            if let Some(mut update) = update {
+                update.insert("updated_at", DateTime::now());
+                db.collection::<Subscription>("subscriptions")
+                    .update_one(doc! { "_id": subscription.id }, doc! { "$set": update }, None)
+                    .await
+                    .map_err(|e| ApiError::internal_error(e.to_string()))?;
+            }
+        }
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "received": true
+    }))))
+}
+
+// ============================================================================
+// MOCK GATEWAY CHECKOUT
+// ============================================================================
+
+/// Stand-in for Razorpay Checkout when `PAYMENT_GATEWAY=mock`: "pays" an order
+/// and returns the ids and signature to send to the matching verify endpoint
+#[openapi(tag = "Subscription")]
+#[post("/subscription/mock-checkout/<order_id>")]
+pub async fn mock_checkout(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    order_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if payment_gateway().name() != "mock" {
+        return Err(ApiError::not_found("Mock checkout is only available with the mock gateway"));
+    }
+
+    db.collection::<Payment>("payments")
+        .find_one(doc! { "razorpay_order_id": &order_id, "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Order not found"))?;
+
+    let (payment_id, signature) = MockGateway::pay_order(&order_id).map_err(ApiError::internal_error)?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "razorpay_order_id": order_id,
+        "razorpay_payment_id": payment_id,
+        "razorpay_signature": signature
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SubscriptionStatus, SubscriptionType};
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    /// A ₹365 plan running from day 0 to day 365
+    fn subscription() -> Subscription {
+        Subscription {
+            id: None,
+            user_id: ObjectId::new(),
+            subscription_type: SubscriptionType::Worker,
+            plan_name: "silver".to_string(),
+            price: 365.0,
+            status: SubscriptionStatus::Active,
+            starts_at: DateTime::from_millis(0),
+            expires_at: DateTime::from_millis(365 * DAY_MS),
+            auto_renew: false,
+            payment_id: None,
+            cancel_at_period_end: false,
+            cancelled_at: None,
+            cancellation_reason: None,
+            razorpay_subscription_id: None,
+            renewal_status: None,
+            renewal_attempts: 0,
+            pending_change: None,
+            coupon_code: None,
+            discount: 0.0,
+            created_at: DateTime::from_millis(0),
+            updated_at: DateTime::from_millis(0),
+        }
+    }
+
+    #[test]
+    fn refunds_unused_days() {
+        assert_eq!(prorated_refund(&subscription(), DateTime::from_millis(65 * DAY_MS)), 300.0);
+    }
+
+    #[test]
+    fn refund_is_rounded_down_to_paise() {
+        let mut sub = subscription();
+        sub.price = 100.0;
+        // A third of the period left is ₹33.333...
+        sub.expires_at = DateTime::from_millis(3 * DAY_MS);
+        assert_eq!(prorated_refund(&sub, DateTime::from_millis(2 * DAY_MS)), 33.33);
+    }
+
+    #[test]
+    fn full_refund_before_the_period_starts() {
+        assert_eq!(prorated_refund(&subscription(), DateTime::from_millis(-DAY_MS)), 365.0);
+    }
+
+    #[test]
+    fn nothing_refunded_after_expiry() {
+        assert_eq!(prorated_refund(&subscription(), DateTime::from_millis(365 * DAY_MS)), 0.0);
+        assert_eq!(prorated_refund(&subscription(), DateTime::from_millis(400 * DAY_MS)), 0.0);
+    }
+
+    #[test]
+    fn empty_period_refunds_nothing() {
+        let mut sub = subscription();
+        sub.expires_at = sub.starts_at;
+        assert_eq!(prorated_refund(&sub, DateTime::from_millis(-DAY_MS)), 0.0);
+    }
+}
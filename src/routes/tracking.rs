@@ -0,0 +1,310 @@
+use rocket::futures::stream::BoxStream;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::openapi;
+use mongodb::bson::{doc, DateTime, oid::ObjectId};
+use mongodb::options::{FindOneOptions, FindOptions};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::config::Config;
+use crate::db::DbConn;
+use crate::models::{Booking, BookingStatus, LiveLocation, LocationPoint, LocationUpdateDto};
+use crate::guards::AuthGuard;
+use crate::routes::booking::{booking_party, find_booking, find_worker_booking};
+use crate::routes::notification::notify;
+use crate::utils::{ApiResponse, ApiError};
+
+/// Positions closer together than this are streamed but not stored
+const TRAIL_MIN_INTERVAL_SECS: i64 = 15;
+
+/// How often an idle stream re-checks that the booking is still active
+const STREAM_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Positions buffered per booking for slow subscribers
+const CHANNEL_CAPACITY: usize = 16;
+
+// ============================================================================
+// LIVE LOCATION HUB
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum LiveEvent {
+    Location(LiveLocation),
+    Ended(BookingStatus),
+}
+
+/// Fans worker positions out to the customers watching a booking.
+/// Channels exist only while someone is subscribed.
+#[derive(Default)]
+pub struct LocationHub {
+    channels: Mutex<HashMap<ObjectId, broadcast::Sender<LiveEvent>>>,
+}
+
+impl LocationHub {
+    fn subscribe(&self, booking_id: ObjectId) -> broadcast::Receiver<LiveEvent> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .entry(booking_id)
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    fn publish(&self, booking_id: ObjectId, event: LiveEvent) {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sender) = channels.get(&booking_id)
+            && sender.send(event).is_err()
+        {
+            // Every watcher has gone away
+            channels.remove(&booking_id);
+        }
+    }
+
+    /// Tell watchers the booking is over and close their streams
+    pub fn end(&self, booking_id: ObjectId, status: BookingStatus) {
+        self.publish(booking_id, LiveEvent::Ended(status));
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels.remove(&booking_id);
+    }
+}
+
+// ============================================================================
+// TRACKING HELPERS
+// ============================================================================
+
+fn live_location(booking: &Booking, point: &LocationPoint) -> LiveLocation {
+    let distance_km = booking.location.distance_km(point.latitude, point.longitude);
+    let speed_kmph = Config::travel_speed_kmph().max(1.0);
+
+    LiveLocation {
+        latitude: point.latitude,
+        longitude: point.longitude,
+        distance_km: (distance_km * 100.0).round() / 100.0,
+        eta_minutes: (distance_km / speed_kmph * 60.0).ceil() as i64,
+        recorded_at: point.recorded_at.try_to_rfc3339_string().unwrap_or_default(),
+    }
+}
+
+async fn latest_point(db: &DbConn, booking_id: ObjectId) -> Result<Option<LocationPoint>, ApiError> {
+    db.collection::<LocationPoint>("booking_locations")
+        .find_one(
+            doc! { "booking_id": booking_id },
+            FindOneOptions::builder().sort(doc! { "recorded_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))
+}
+
+// ============================================================================
+// WORKER ENDPOINTS
+// ============================================================================
+
+/// Worker has set off for a scheduled booking. Starts location sharing.
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/en-route")]
+pub async fn set_booking_en_route(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    if booking.status != BookingStatus::Scheduled {
+        return Err(ApiError::bad_request(format!(
+            "Only scheduled bookings can be marked en route, this one is {}",
+            booking.status.as_str()
+        )));
+    }
+
+    let now = DateTime::now();
+    let result = db.collection::<Booking>("bookings")
+        .update_one(
+            doc! { "_id": booking.id, "status": "scheduled", "en_route_at": null },
+            doc! { "$set": { "en_route_at": now, "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update booking: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("Booking is already en route"));
+    }
+
+    notify(
+        db,
+        booking.customer_id,
+        "booking_en_route",
+        "Your worker is on the way",
+        "Follow their live location from your booking.",
+        Some(serde_json::json!({ "booking_id": booking_id })),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Booking marked en route",
+        "booking_id": booking_id,
+        "en_route_at": now
+    }))))
+}
+
+/// Report the worker's position while en route or working
+#[openapi(tag = "Booking")]
+#[post("/booking/<booking_id>/location", data = "<dto>")]
+pub async fn update_booking_location(
+    db: &State<DbConn>,
+    hub: &State<LocationHub>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<LocationUpdateDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if !(-90.0..=90.0).contains(&dto.latitude) || !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Coordinates out of range"));
+    }
+
+    let booking = find_worker_booking(db, &booking_id, auth.user_id).await?;
+    if !booking.is_sharing_location() {
+        return Err(ApiError::bad_request(
+            "Location is only shared while en route or in progress",
+        ));
+    }
+    let object_id = booking.id.ok_or_else(|| ApiError::internal_error("Booking has no ID"))?;
+
+    let point = LocationPoint {
+        id: None,
+        booking_id: object_id,
+        worker_user_id: auth.user_id,
+        latitude: dto.latitude,
+        longitude: dto.longitude,
+        accuracy_m: dto.accuracy_m,
+        recorded_at: DateTime::now(),
+    };
+
+    // Keep the stored trail coarse; the stream still gets every update
+    let stored = match latest_point(db, object_id).await? {
+        Some(last) => {
+            point.recorded_at.timestamp_millis() - last.recorded_at.timestamp_millis()
+                >= TRAIL_MIN_INTERVAL_SECS * 1000
+        }
+        None => true,
+    };
+    if stored {
+        db.collection::<LocationPoint>("booking_locations")
+            .insert_one(&point, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to save location: {}", e)))?;
+    }
+
+    let live = live_location(&booking, &point);
+    hub.publish(object_id, LiveEvent::Location(live.clone()));
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "location": live,
+        "stored": stored
+    }))))
+}
+
+// ============================================================================
+// CUSTOMER ENDPOINTS
+// ============================================================================
+
+/// Server-sent events with the worker's position and ETA.
+/// Sends `location` events, then a final `ended` event when the booking finishes.
+#[openapi(tag = "Booking")]
+#[get("/booking/<booking_id>/location/stream")]
+pub async fn stream_booking_location(
+    db: &State<DbConn>,
+    hub: &State<LocationHub>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<EventStream<BoxStream<'static, Event>>, ApiError> {
+    let booking = find_booking(db, &booking_id).await?;
+    booking_party(&booking, auth.user_id)?;
+    if !booking.is_sharing_location() {
+        return Err(ApiError::bad_request("The worker is not sharing their location for this booking"));
+    }
+    let object_id = booking.id.ok_or_else(|| ApiError::internal_error("Booking has no ID"))?;
+
+    let mut receiver = hub.subscribe(object_id);
+    let initial = latest_point(db, object_id).await?.map(|point| live_location(&booking, &point));
+    let db = db.inner().clone();
+
+    let events = rocket::async_stream::stream! {
+        if let Some(location) = initial {
+            yield Event::json(&location).event("location");
+        }
+
+        loop {
+            match tokio::time::timeout(STREAM_CHECK_INTERVAL, receiver.recv()).await {
+                Ok(Ok(LiveEvent::Location(location))) => {
+                    yield Event::json(&location).event("location");
+                }
+                Ok(Ok(LiveEvent::Ended(status))) => {
+                    yield Event::json(&serde_json::json!({ "status": status })).event("ended");
+                    break;
+                }
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                Ok(Err(broadcast::error::RecvError::Closed)) => break,
+                Err(_) => {
+                    // Quiet for a while; make sure the booking hasn't ended elsewhere
+                    let current = db.collection::<Booking>("bookings")
+                        .find_one(doc! { "_id": object_id }, None)
+                        .await;
+                    match current {
+                        Ok(Some(current)) if current.is_sharing_location() => {
+                            yield Event::comment("keep-alive");
+                        }
+                        Ok(Some(current)) => {
+                            yield Event::json(&serde_json::json!({ "status": current.status })).event("ended");
+                            break;
+                        }
+                        Ok(None) => break,
+                        Err(e) => warn!("Failed to re-check booking {}: {}", object_id, e),
+                    }
+                }
+            }
+        }
+    };
+
+    Ok(EventStream::from(Box::pin(events) as BoxStream<'static, Event>))
+}
+
+/// Stored positions for a booking, kept for resolving disputes
+#[openapi(tag = "Booking")]
+#[get("/booking/<booking_id>/location/trail")]
+pub async fn get_booking_location_trail(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_booking(db, &booking_id).await?;
+    booking_party(&booking, auth.user_id)?;
+
+    let mut cursor = db.collection::<LocationPoint>("booking_locations")
+        .find(
+            doc! { "booking_id": booking.id },
+            FindOptions::builder().sort(doc! { "recorded_at": 1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut trail = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let point = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        trail.push(serde_json::json!({
+            "latitude": point.latitude,
+            "longitude": point.longitude,
+            "accuracy_m": point.accuracy_m,
+            "recorded_at": point.recorded_at
+        }));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "booking_id": booking_id,
+        "status": booking.status,
+        "en_route_at": booking.en_route_at,
+        "started_at": booking.started_at,
+        "trail": trail
+    }))))
+}
@@ -3,15 +3,19 @@ use rocket::serde::json::Json;
 use rocket::{State, Request};
 use rocket_okapi::openapi;
 use mongodb::bson::{doc, DateTime};
-use mongodb::options::FindOptions;
 use crate::db::DbConn;
-use crate::models::{CreateWorkerProfileDto, Subscription, WorkerSubscriptionPlan, UpdateWorkerProfileDto, WorkerProfile, SubscriptionType, SubscriptionStatus, NearbyWorkerQuery, GeoLocation, UpdateLocationDto};
+use crate::models::{CreateWorkerProfileDto, Subscription, WorkerSubscriptionPlan, UpdateWorkerProfileDto, WorkerProfile, SubscriptionType, SubscriptionStatus, NearbyWorkerQuery, GeoLocation, UpdateLocationDto, PaymentKind, Booking, WorkerSchedule, UpdateScheduleDto, SlotsQuery, MinuteRange, DEFAULT_SLOT_MINUTES, parse_date, ist_instant, PortfolioItem, PortfolioStage, AddPortfolioItemDto, UpdatePortfolioItemDto, ReorderPortfolioDto, PairPortfolioDto, OnboardingStatus, CoverageMode, ServiceCoverage, UpdateCoverageDto, ServingWorkerQuery, normalize_ring, MAX_COVERAGE_RADIUS_KM, MAX_COVERAGE_POLYGONS, ProfileKind};
 use crate::guards::{AuthGuard, KycGuard};
 use crate::utils::{ApiResponse, ApiError};
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
-use crate::services::RazorpayService;
+use crate::services::payment_gateway;
+use crate::routes::subscription::{record_order_payment, record_captured_payment};
+use crate::routes::dashboard::record_search_impressions;
+use crate::routes::profile_view::record_profile_view;
+use crate::routes::onboarding::transition_onboarding;
+use crate::routes::coupon::{reserve_coupon, attach_coupon_redemption, release_coupon_redemption, confirm_coupon_redemption};
 use rocket::http::Status;
+use std::net::IpAddr;
+use chrono::NaiveDate;
 
 // ============================================================================
 // SUBSCRIPTION ENDPOINTS (Fixed)
@@ -24,22 +28,21 @@ pub struct CreateSubscriptionResponse {
 }
 
 #[openapi(tag = "Subscription")]
-#[post("/subscription/create/<plan_name>")]
+#[post("/subscription/create/<plan_name>?<coupon>")]
 pub async fn create_subscription(
     db: &State<DbConn>,
     auth: AuthGuard,
     plan_name: String,
+    coupon: Option<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     // Validate plan and get price
-    let (price, plan_type) = match plan_name.to_lowercase().as_str() {
-        "silver" => (1.0, WorkerSubscriptionPlan::Silver),
-        "gold" => (2.0, WorkerSubscriptionPlan::Gold),
-        _ => return Err(ApiError::bad_request("Invalid plan. Choose 'silver' or 'gold'")),
-    };
+    let plan = SubscriptionType::Worker
+        .find_plan(&plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan. Choose 'silver' or 'gold'"))?;
 
     let now = DateTime::now();
     let expires_at = DateTime::from_millis(
-        chrono::Utc::now().timestamp_millis() + 365 * 24 * 60 * 60 * 1000,
+        chrono::Utc::now().timestamp_millis() + plan.duration_days * 24 * 60 * 60 * 1000,
     );
  
     // Check if user already has an active subscription
@@ -59,23 +62,46 @@ pub async fn create_subscription(
         return Err(ApiError::bad_request("You already have an active subscription"));
     }
 
+    // Reserve the coupon before creating the order so its limits hold
+    let applied_coupon = match coupon.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(code) => Some(reserve_coupon(db, code, auth.user_id, &SubscriptionType::Worker, plan).await?),
+        None => None,
+    };
+    let discount = applied_coupon.as_ref().map_or(0.0, |c| c.discount);
+    let price = applied_coupon.as_ref().map_or(plan.price, |c| c.final_price);
+
     // Create Razorpay order first
-    let order = RazorpayService::create_order(price as i64)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Failed to create payment order: {}", e)))?;
+    let order = match payment_gateway().create_order(price).await {
+        Ok(order) => order,
+        Err(e) => {
+            if let Some(ref applied) = applied_coupon {
+                release_coupon_redemption(db, applied.redemption_id).await?;
+            }
+            return Err(ApiError::internal_error(format!("Failed to create payment order: {}", e)));
+        }
+    };
 
     // Create subscription with pending status
     let subscription = Subscription {
         id: None,
         user_id: auth.user_id,
         subscription_type: SubscriptionType::Worker,
-        plan_name: plan_name.clone(),
+        plan_name: plan.name.to_string(),
         price,
-        status: SubscriptionStatus::Cancelled, // Will be updated after payment
+        status: SubscriptionStatus::Pending, // Will be updated after payment
         starts_at: now,
         expires_at,
         auto_renew: false,
         payment_id: None,
+        cancel_at_period_end: false,
+        cancelled_at: None,
+        cancellation_reason: None,
+        razorpay_subscription_id: None,
+        renewal_status: None,
+        renewal_attempts: 0,
+        pending_change: None,
+        coupon_code: applied_coupon.as_ref().map(|c| c.code.clone()),
+        discount,
         created_at: now,
         updated_at: now,
     };
@@ -86,14 +112,22 @@ pub async fn create_subscription(
         .await
         .map_err(|e| ApiError::internal_error(format!("Failed to create subscription: {}", e)))?;
 
-    let subscription_id = sub_res.inserted_id.as_object_id()
-        .ok_or_else(|| ApiError::internal_error("Invalid subscription ID"))?
-        .to_hex();
+    let subscription_oid = sub_res.inserted_id.as_object_id()
+        .ok_or_else(|| ApiError::internal_error("Invalid subscription ID"))?;
+
+    if let Some(ref applied) = applied_coupon {
+        attach_coupon_redemption(db, applied.redemption_id, subscription_oid).await?;
+    }
+
+    record_order_payment(db, auth.user_id, subscription_oid, PaymentKind::Purchase, &order, price).await?;
 
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "subscription_id": subscription_id,
+        "subscription_id": subscription_oid.to_hex(),
         "order": order,
-        "plan_name": plan_name,
+        "plan_name": plan.name,
+        "original_price": plan.price,
+        "discount": discount,
+        "coupon": applied_coupon.as_ref().map(|c| &c.code),
         "price": price
     }))))
 }
@@ -115,18 +149,11 @@ pub async fn verify_subscription_payment(
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     
     // Verify Razorpay signature
-    let secret = std::env::var("RAZORPAY_KEY_SECRET")
-        .map_err(|_| ApiError::internal_error("Missing Razorpay secret"))?;
-
-    let payload = format!("{}|{}", dto.razorpay_order_id, dto.razorpay_payment_id);
-    
-    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
-        .map_err(|_| ApiError::internal_error("Invalid HMAC key"))?;
-    
-    mac.update(payload.as_bytes());
-    let expected_signature = hex::encode(mac.finalize().into_bytes());
+    let valid = payment_gateway()
+        .verify_payment_signature(&dto.razorpay_order_id, &dto.razorpay_payment_id, &dto.razorpay_signature)
+        .map_err(ApiError::internal_error)?;
 
-    if expected_signature != dto.razorpay_signature {
+    if !valid {
         return Err(ApiError::bad_request("Invalid payment signature"));
     }
 
@@ -157,6 +184,9 @@ pub async fn verify_subscription_payment(
         return Err(ApiError::not_found("Subscription not found"));
     }
 
+    record_captured_payment(db, &dto.razorpay_order_id, &dto.razorpay_payment_id).await?;
+    confirm_coupon_redemption(db, sub_id).await?;
+
     // Get the subscription details
     let subscription = db
         .collection::<Subscription>("subscriptions")
@@ -203,7 +233,9 @@ pub async fn get_subscription_status(
                 "plan_name": sub.plan_name,
                 "status": format!("{:?}", sub.status),
                 "expires_at": sub.expires_at,
-                "auto_renew": sub.auto_renew
+                "auto_renew": sub.auto_renew,
+                "renewal_status": sub.renewal_status,
+                "cancel_at_period_end": sub.cancel_at_period_end
             }
         }))))
     } else {
@@ -251,6 +283,12 @@ pub async fn create_worker_profile(
         _ => WorkerSubscriptionPlan::None,
     };
     
+    if let Some(ref license_document) = dto.license_document
+        && !license_document.starts_with("/uploads/documents/")
+    {
+        return Err(ApiError::bad_request("License document must be uploaded through /upload/document first"));
+    }
+
     let location = GeoLocation {
         geo_type: String::from("Point"),
         coordinates: [dto.longitude.unwrap_or(72.8311), dto.latitude.unwrap_or(21.1702)]
@@ -276,14 +314,27 @@ pub async fn create_worker_profile(
         description: dto.description.clone(),
         hourly_rate: dto.hourly_rate,
         license_number: dto.license_number.clone(),
+        license_document: dto.license_document.clone(),
         service_areas: dto.service_areas.clone(),
         subscription_plan,
         subscription_expires_at: Some(subscription.expires_at),
         is_verified: false,
+        onboarding_status: Some(OnboardingStatus::Draft),
+        onboarding_history: Vec::new(),
+        rejection_reasons: Vec::new(),
+        location_set_at: (dto.latitude.is_some() && dto.longitude.is_some()).then(DateTime::now),
         is_available: true,
+        accepting_bookings: Some(true),
+        schedule: None,
+        portfolio: Vec::new(),
+        coverage: None,
+        coverage_area: None,
         rating: 0.0,
         total_reviews: 0,
         total_jobs_completed: 0,
+        favorite_count: 0,
+        profile_views: 0,
+        badges: Vec::new(),
         created_at: DateTime::now(),
         location,
         updated_at: DateTime::now(),
@@ -306,6 +357,8 @@ pub async fn create_worker_profile(
 #[get("/worker/profile/<worker_id>")]
 pub async fn get_worker_profile_by_id(
     db: &State<DbConn>,
+    viewer: Option<AuthGuard>,
+    ip: Option<IpAddr>,
     worker_id: String,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     let object_id = ObjectId::parse_str(&worker_id)
@@ -316,8 +369,14 @@ pub async fn get_worker_profile_by_id(
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
         .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+
+    record_profile_view(db, ProfileKind::Worker, object_id, worker.user_id, viewer.map(|v| v.user_id), ip);
+
+    let portfolio_limit = worker.subscription_plan.portfolio_limit();
+    let mut response = serde_json::json!(worker);
+    response["portfolio_limit"] = serde_json::json!(portfolio_limit);
     
-    Ok(Json(ApiResponse::success(serde_json::json!(worker))))
+    Ok(Json(ApiResponse::success(response)))
 }
 
 #[openapi(tag = "Worker")]
@@ -342,6 +401,8 @@ pub async fn update_worker_profile(
     auth: AuthGuard,
     dto: Json<UpdateWorkerProfileDto>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_own_profile(db, auth.user_id).await?;
+
     let mut update_doc = doc! {
         "updated_at": DateTime::now()
     };
@@ -364,7 +425,17 @@ pub async fn update_worker_profile(
     if let Some(ref areas) = dto.service_areas {
         update_doc.insert("service_areas", areas);
     }
+    if let Some(ref license_number) = dto.license_number {
+        update_doc.insert("license_number", license_number.trim());
+    }
+    if let Some(ref license_document) = dto.license_document {
+        if !license_document.starts_with("/uploads/documents/") {
+            return Err(ApiError::bad_request("License document must be uploaded through /upload/document first"));
+        }
+        update_doc.insert("license_document", license_document);
+    }
     if let Some(available) = dto.is_available {
+        update_doc.insert("accepting_bookings", available);
         update_doc.insert("is_available", available);
     }
     
@@ -380,9 +451,47 @@ pub async fn update_worker_profile(
     if result.matched_count == 0 {
         return Err(ApiError::not_found("Worker profile not found"));
     }
+
+    // Verification covered the trades and license on file, so changing them needs a new review
+    let same_names = |new: &Option<Vec<String>>, current: &[String]| {
+        new.as_ref().is_none_or(|new| {
+            new.len() == current.len() && new.iter().all(|n| current.iter().any(|c| c.eq_ignore_ascii_case(n.trim())))
+        })
+    };
+    let same_text = |new: &Option<String>, current: &Option<String>| {
+        new.as_ref().is_none_or(|new| Some(new.trim()) == current.as_deref().map(str::trim))
+    };
+    let reverify = !same_names(&dto.categories, &worker.categories)
+        || !same_names(&dto.subcategories, &worker.subcategories)
+        || !same_text(&dto.license_number, &worker.license_number)
+        || !same_text(&dto.license_document, &worker.license_document);
+
+    let back_to_review = reverify && worker.onboarding() == OnboardingStatus::Verified;
+    if back_to_review {
+        transition_onboarding(
+            db,
+            &worker,
+            OnboardingStatus::UnderReview,
+            vec!["Categories or trade license changed after verification".to_string()],
+            None,
+            false,
+        )
+        .await?;
+    }
+
+    // Switching on only takes effect inside scheduled working hours
+    if dto.is_available == Some(true) {
+        let worker = find_own_profile(db, auth.user_id).await?;
+        sync_availability(db, &worker).await?;
+    }
     
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "message": "Worker profile updated successfully"
+        "message": if back_to_review {
+            "Worker profile updated and sent back for verification"
+        } else {
+            "Worker profile updated successfully"
+        },
+        "onboarding_status": if back_to_review { OnboardingStatus::UnderReview } else { worker.onboarding() }
     }))))
 }
 
@@ -395,53 +504,188 @@ pub async fn search_workers(
     let page = query.page.unwrap_or(1).max(1);
     let limit = query.limit.unwrap_or(20).min(100);
     let skip = (page - 1) * limit;
-    
+
+    let text = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let sort = match query.sort.as_deref() {
+        None => None,
+        Some(value) => Some(
+            WorkerSort::parse(value)
+                .ok_or_else(|| ApiError::bad_request("Invalid sort. Use rating, price_asc, price_desc, experience or newest"))?,
+        ),
+    };
+
+    // Filters shared by results and facets. `$text` has to be in the first stage.
     let mut filter = doc! {
         "is_available": true,
         "is_verified": true,
     };
-    
-    if let Some(category) = query.category {
-        filter.insert("categories", category);
-    }
-    
-    if let Some(subcategory) = query.subcategory {
-        filter.insert("subcategories", subcategory);
+
+    if let Some(text) = text {
+        filter.insert("$text", doc! { "$search": text });
     }
-    
+
     if let Some(min_rating) = query.min_rating {
         filter.insert("rating", doc! { "$gte": min_rating });
     }
-    
-    let find_options = FindOptions::builder()
-        .skip(skip as u64)
-        .limit(limit)
-        .sort(doc! { 
-            "subscription_plan": -1,
-            "rating": -1,
-            "total_reviews": -1
-        })
-        .build();
-    
-    let mut cursor = db.collection::<WorkerProfile>("worker_profiles")
-        .find(filter.clone(), find_options)
+
+    let mut rate = doc! {};
+    if let Some(min_rate) = query.min_rate {
+        rate.insert("$gte", min_rate);
+    }
+    if let Some(max_rate) = query.max_rate {
+        rate.insert("$lte", max_rate);
+    }
+    if !rate.is_empty() {
+        filter.insert("hourly_rate", rate);
+    }
+
+    let mut experience = doc! {};
+    if let Some(min_experience) = query.min_experience {
+        experience.insert("$gte", min_experience);
+    }
+    if let Some(max_experience) = query.max_experience {
+        experience.insert("$lte", max_experience);
+    }
+    if !experience.is_empty() {
+        filter.insert("experience_years", experience);
+    }
+
+    let mut pipeline = vec![doc! { "$match": filter }];
+
+    // City is either one of the worker's service areas or the city on their account
+    if let Some(city) = query.city.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        let city = format!("^{}$", regex::escape(city));
+        pipeline.push(doc! {
+            "$lookup": {
+                "from": "users",
+                "localField": "user_id",
+                "foreignField": "_id",
+                "as": "account"
+            }
+        });
+        pipeline.push(doc! {
+            "$match": {
+                "$or": [
+                    { "service_areas": { "$regex": &city, "$options": "i" } },
+                    { "account.city": { "$regex": &city, "$options": "i" } }
+                ]
+            }
+        });
+    }
+
+    if text.is_some() {
+        pipeline.push(doc! { "$addFields": { "text_score": { "$meta": "textScore" } } });
+    }
+
+    let mut selected = doc! {};
+    if let Some(ref category) = query.category {
+        selected.insert("categories", category);
+    }
+    if let Some(ref subcategory) = query.subcategory {
+        selected.insert("subcategories", subcategory);
+    }
+
+    // Each facet ignores its own filter so the UI can offer the alternatives
+    let category_facet_match = match query.subcategory {
+        Some(ref subcategory) => doc! { "subcategories": subcategory },
+        None => doc! {},
+    };
+    let subcategory_facet_match = match query.category {
+        Some(ref category) => doc! { "categories": category },
+        None => doc! {},
+    };
+
+    let sort_doc = match sort {
+        Some(WorkerSort::Rating) => doc! { "rating": -1, "total_reviews": -1, "favorite_count": -1 },
+        Some(WorkerSort::PriceAsc) => doc! { "rate_missing": 1, "hourly_rate": 1, "rating": -1 },
+        Some(WorkerSort::PriceDesc) => doc! { "rate_missing": 1, "hourly_rate": -1, "rating": -1 },
+        Some(WorkerSort::Experience) => doc! { "experience_years": -1, "rating": -1 },
+        Some(WorkerSort::Newest) => doc! { "created_at": -1 },
+        None if text.is_some() => doc! { "text_score": -1, "rating": -1, "favorite_count": -1 },
+        None => doc! { "subscription_plan": -1, "rating": -1, "favorite_count": -1, "total_reviews": -1 },
+    };
+
+    pipeline.push(doc! {
+        "$facet": {
+            "workers": [
+                { "$match": selected.clone() },
+                // Workers without a rate go last whichever way prices are sorted
+                { "$addFields": { "rate_missing": { "$cond": [{ "$eq": [{ "$ifNull": ["$hourly_rate", null] }, null] }, 1, 0] } } },
+                { "$sort": sort_doc },
+                { "$skip": skip },
+                { "$limit": limit }
+            ],
+            "total": [
+                { "$match": selected },
+                { "$count": "count" }
+            ],
+            "categories": [
+                { "$match": category_facet_match },
+                { "$unwind": "$categories" },
+                { "$group": { "_id": "$categories", "count": { "$sum": 1 } } },
+                { "$sort": { "count": -1, "_id": 1 } }
+            ],
+            "subcategories": [
+                { "$match": subcategory_facet_match },
+                { "$unwind": "$subcategories" },
+                { "$group": { "_id": "$subcategories", "count": { "$sum": 1 } } },
+                { "$sort": { "count": -1, "_id": 1 } }
+            ]
+        }
+    });
+
+    let mut cursor = db.collection::<mongodb::bson::Document>("worker_profiles")
+        .aggregate(pipeline, None)
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
-    
+
+    let result = if cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?
+    } else {
+        mongodb::bson::Document::new()
+    };
+
     let mut workers = Vec::new();
-    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
-        let worker = cursor.deserialize_current()
+    for worker in result.get_array("workers").map(|a| a.as_slice()).unwrap_or_default() {
+        let Some(worker) = worker.as_document() else {
+            continue;
+        };
+        let worker: WorkerProfile = mongodb::bson::from_document(worker.clone())
             .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
         workers.push(worker);
     }
-    
-    let total = db.collection::<WorkerProfile>("worker_profiles")
-        .count_documents(filter, None)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
-    
+
+    let total = result
+        .get_array("total")
+        .ok()
+        .and_then(|t| t.first())
+        .and_then(|t| t.as_document())
+        .and_then(|t| t.get_i32("count").ok())
+        .unwrap_or(0) as i64;
+
+    record_search_impressions(db, workers.iter().filter_map(|w| w.id).collect());
+
+    let facet = |name: &str| -> Vec<serde_json::Value> {
+        result
+            .get_array(name)
+            .map(|a| a.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|entry| entry.as_document())
+            .map(|entry| serde_json::json!({
+                "value": entry.get_str("_id").unwrap_or_default(),
+                "count": entry.get_i32("count").unwrap_or(0)
+            }))
+            .collect()
+    };
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "workers": workers,
+        "facets": {
+            "categories": facet("categories"),
+            "subcategories": facet("subcategories")
+        },
         "pagination": {
             "page": page,
             "limit": limit,
@@ -453,14 +697,83 @@ pub async fn search_workers(
 
 #[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct SearchWorkersQuery {
+    /// Free text over description, categories and service areas
+    pub q: Option<String>,
     pub category: Option<String>,
     pub subcategory: Option<String>,
     pub city: Option<String>,
     pub min_rating: Option<f64>,
+    pub min_rate: Option<f64>,
+    pub max_rate: Option<f64>,
+    pub min_experience: Option<i32>,
+    pub max_experience: Option<i32>,
+    /// rating | price_asc | price_desc | experience | newest
+    pub sort: Option<String>,
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
 
+#[derive(Debug, Clone, Copy)]
+enum WorkerSort {
+    Rating,
+    PriceAsc,
+    PriceDesc,
+    Experience,
+    Newest,
+}
+
+impl WorkerSort {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rating" => Some(WorkerSort::Rating),
+            "price_asc" => Some(WorkerSort::PriceAsc),
+            "price_desc" => Some(WorkerSort::PriceDesc),
+            "experience" => Some(WorkerSort::Experience),
+            "newest" => Some(WorkerSort::Newest),
+            _ => None,
+        }
+    }
+}
+
+/// Workers and total from a `$geoNear` + `$facet` result, with distance in km
+/// and a rough travel time added to each worker
+fn geo_results(db: &DbConn, result: &mongodb::bson::Document) -> Result<(Vec<serde_json::Value>, i64), ApiError> {
+    let speed_kmph = crate::config::Config::travel_speed_kmph().max(1.0);
+    let mut workers = Vec::new();
+    let mut shown = Vec::new();
+    for entry in result.get_array("workers").map(|a| a.as_slice()).unwrap_or_default() {
+        let Some(entry) = entry.as_document() else {
+            continue;
+        };
+        let distance_km = entry.get_f64("distance").unwrap_or(0.0) / 1000.0;
+        let worker: WorkerProfile = mongodb::bson::from_document(entry.clone())
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        shown.extend(worker.id);
+
+        let mut worker = serde_json::json!(worker);
+        worker["distance_km"] = serde_json::json!((distance_km * 100.0).round() / 100.0);
+        worker["travel_minutes"] = serde_json::json!((distance_km / speed_kmph * 60.0).ceil() as i64);
+        workers.push(worker);
+    }
+
+    let total = result
+        .get_array("total")
+        .ok()
+        .and_then(|t| t.first())
+        .and_then(|t| t.as_document())
+        .and_then(|t| t.get_i32("count").ok())
+        .unwrap_or(0) as i64;
+
+    record_search_impressions(db, shown);
+
+    Ok((workers, total))
+}
+
+pub const DEFAULT_NEARBY_RADIUS_KM: f64 = 10.0;
+pub const MAX_NEARBY_RADIUS_KM: f64 = 50.0;
+
+/// Workers are shown out to the reach of their plan, Gold the furthest.
+/// Within that, results are nearest first.
 #[openapi(tag = "Worker")]
 #[get("/worker/nearby?<query..>")]
 pub async fn find_nearby_workers(
@@ -471,21 +784,43 @@ pub async fn find_nearby_workers(
     let limit = query.limit.unwrap_or(20).min(50);
     let skip = (page - 1) * limit;
 
-    // CRITICAL FIX: Build filter WITHOUT geo query first
-    let mut match_filter = doc! {
+    if !(-90.0..=90.0).contains(&query.latitude) || !(-180.0..=180.0).contains(&query.longitude) {
+        return Err(ApiError::bad_request("Invalid coordinates"));
+    }
+    let radius_km = query.radius_km.unwrap_or(DEFAULT_NEARBY_RADIUS_KM);
+    if radius_km <= 0.0 || radius_km > MAX_NEARBY_RADIUS_KM {
+        return Err(ApiError::bad_request(format!(
+            "Radius must be more than 0 and at most {} km",
+            MAX_NEARBY_RADIUS_KM
+        )));
+    }
+
+    let mut query_filter = doc! {
         "is_verified": true,
         "is_available": true,
     };
 
     if let Some(category) = &query.category {
-        match_filter.insert("categories", category);
+        query_filter.insert("categories", category);
     }
 
     if let Some(subcategory) = &query.subcategory {
-        match_filter.insert("subcategories", subcategory);
+        query_filter.insert("subcategories", subcategory);
     }
 
-    // Use aggregation pipeline with $geoNear (works better than find with $nearSphere)
+    if let Some(min_rating) = query.min_rating {
+        query_filter.insert("rating", doc! { "$gte": min_rating });
+    }
+
+    if let Some(max_rate) = query.max_rate {
+        query_filter.insert("hourly_rate", doc! { "$lte": max_rate });
+    }
+
+    let reach = |plan: WorkerSubscriptionPlan| plan.search_reach_km() * 1000.0;
+    let rank = |plan: WorkerSubscriptionPlan| plan.rank();
+
+    // `$geoNear` filters with `query` and stops at the radius; the plan reach
+    // is applied per worker afterwards
     let pipeline = vec![
         doc! {
             "$geoNear": {
@@ -494,82 +829,69 @@ pub async fn find_nearby_workers(
                     "coordinates": [query.longitude, query.latitude]
                 },
                 "distanceField": "distance",
-                "maxDistance": 10000,
+                "maxDistance": radius_km * 1000.0,
+                "query": query_filter,
                 "spherical": true,
                 "key": "location"
             }
         },
         doc! {
-            "$match": match_filter.clone()
-        },
-        doc! {
-            "$skip": skip
-        },
-        doc! {
-            "$limit": limit
-        },
-        doc! {
-            "$sort": {
-                "distance": 1,
-                "subscription_plan": -1,
-                "rating": -1
-            }
-        }
-    ];
-
-    let mut cursor = db
-        .collection::<WorkerProfile>("worker_profiles")
-        .aggregate(pipeline.clone(), None)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
-
-    let mut workers = Vec::new();
-    while cursor.advance().await.map_err(|e| ApiError::internal_error(e.to_string()))? {
-        let doc = cursor.deserialize_current()
-            .map_err(|e| ApiError::internal_error(e.to_string()))?;
-        workers.push(doc);
-    }
-
-    // Count total (without skip/limit)
-    let count_pipeline = vec![
-        doc! {
-            "$geoNear": {
-                "near": {
-                    "type": "Point",
-                    "coordinates": [query.longitude, query.latitude]
+            "$addFields": {
+                "plan_reach": {
+                    "$switch": {
+                        "branches": [
+                            { "case": { "$eq": ["$subscription_plan", "gold"] }, "then": reach(WorkerSubscriptionPlan::Gold) },
+                            { "case": { "$eq": ["$subscription_plan", "silver"] }, "then": reach(WorkerSubscriptionPlan::Silver) }
+                        ],
+                        "default": reach(WorkerSubscriptionPlan::None)
+                    }
                 },
-                "distanceField": "distance",
-                "maxDistance": 10000,
-                "spherical": true,
-                "key": "location"
+                "plan_rank": {
+                    "$switch": {
+                        "branches": [
+                            { "case": { "$eq": ["$subscription_plan", "gold"] }, "then": rank(WorkerSubscriptionPlan::Gold) },
+                            { "case": { "$eq": ["$subscription_plan", "silver"] }, "then": rank(WorkerSubscriptionPlan::Silver) }
+                        ],
+                        "default": rank(WorkerSubscriptionPlan::None)
+                    }
+                }
             }
         },
         doc! {
-            "$match": match_filter
+            "$match": { "$expr": { "$lte": ["$distance", "$plan_reach"] } }
         },
         doc! {
-            "$count": "total"
-        }
+            "$facet": {
+                "workers": [
+                    { "$sort": { "distance": 1, "plan_rank": -1, "rating": -1, "favorite_count": -1, "_id": 1 } },
+                    { "$skip": skip },
+                    { "$limit": limit }
+                ],
+                "total": [
+                    { "$count": "count" }
+                ]
+            }
+        },
     ];
 
-    let mut count_cursor = db
+    let mut cursor = db
         .collection::<mongodb::bson::Document>("worker_profiles")
-        .aggregate(count_pipeline, None)
+        .aggregate(pipeline, None)
         .await
-        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
 
-    let total = if count_cursor.advance().await.unwrap_or(false) {
-        count_cursor
-            .deserialize_current()
-            .ok()
-            .and_then(|doc| doc.get_i64("total").ok())
-            .unwrap_or(0)
+    let result = if cursor.advance().await.map_err(|e| ApiError::internal_error(e.to_string()))? {
+        cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
     } else {
-        0
+        mongodb::bson::Document::new()
     };
 
+    let (workers, total) = geo_results(db, &result)?;
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "workers": workers,
+        "radius_km": radius_km,
         "pagination": {
             "page": page,
             "limit": limit,
@@ -604,6 +926,7 @@ pub async fn update_worker_location(
                         "type": "Point",
                         "coordinates": vec![dto.longitude, dto.latitude]
                     },
+                    "location_set_at": DateTime::now(),
                     "updated_at": DateTime::now()
                 }
             },
@@ -616,7 +939,711 @@ pub async fn update_worker_location(
         return Err(ApiError::not_found("Worker profile not found"));
     }
 
+    // A radius coverage moves with the base location
+    let worker = find_own_profile(db, auth.user_id).await?;
+    if let Some(ref coverage) = worker.coverage
+        && coverage.mode == CoverageMode::Radius
+    {
+        save_coverage_area(db, &worker, coverage).await?;
+    }
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Location updated successfully"
     })))) 
-}
\ No newline at end of file
+}
+
+// ============================================================================
+// AVAILABILITY SCHEDULE ENDPOINTS
+// ============================================================================
+
+async fn find_own_profile(db: &DbConn, user_id: ObjectId) -> Result<WorkerProfile, ApiError> {
+    db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))
+}
+
+/// Bring `is_available` in line with the manual switch and the schedule right now
+pub async fn sync_availability(db: &DbConn, worker: &WorkerProfile) -> Result<bool, ApiError> {
+    let available = worker.scheduled_availability(DateTime::now());
+    if available != worker.is_available {
+        db.collection::<WorkerProfile>("worker_profiles")
+            .update_one(
+                doc! { "_id": worker.id },
+                doc! { "$set": { "is_available": available, "updated_at": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to update availability: {}", e)))?;
+    }
+    Ok(available)
+}
+
+#[openapi(tag = "Worker")]
+#[put("/worker/schedule", data = "<dto>")]
+pub async fn update_worker_schedule(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateScheduleDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let mut worker = find_own_profile(db, auth.user_id).await?;
+
+    let dto = dto.into_inner();
+    let schedule = WorkerSchedule {
+        weekly: dto.weekly,
+        holidays: dto.holidays.unwrap_or_default(),
+        blackouts: dto.blackouts.unwrap_or_default(),
+        slot_minutes: dto.slot_minutes.unwrap_or(DEFAULT_SLOT_MINUTES),
+        updated_at: DateTime::now(),
+    };
+    schedule.validate().map_err(ApiError::bad_request)?;
+
+    let schedule_bson = mongodb::bson::to_bson(&schedule)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    // Keep the manual switch as it was before the schedule took over `is_available`
+    let accepting = worker.accepting_bookings.unwrap_or(worker.is_available);
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id },
+            doc! {
+                "$set": {
+                    "schedule": schedule_bson,
+                    "accepting_bookings": accepting,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save schedule: {}", e)))?;
+
+    worker.schedule = Some(schedule);
+    worker.accepting_bookings = Some(accepting);
+    let is_available = sync_availability(db, &worker).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Schedule updated successfully",
+        "is_available": is_available
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[get("/worker/schedule")]
+pub async fn get_worker_schedule(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_own_profile(db, auth.user_id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "schedule": worker.schedule,
+        "accepting_bookings": worker.accepting_bookings.unwrap_or(worker.is_available),
+        "is_available": worker.is_available
+    }))))
+}
+
+/// Drop the schedule; availability goes back to the manual switch only
+#[openapi(tag = "Worker")]
+#[delete("/worker/schedule")]
+pub async fn delete_worker_schedule(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let mut worker = find_own_profile(db, auth.user_id).await?;
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id },
+            doc! { "$unset": { "schedule": "" }, "$set": { "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to remove schedule: {}", e)))?;
+
+    worker.schedule = None;
+    let is_available = sync_availability(db, &worker).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Schedule removed",
+        "is_available": is_available
+    }))))
+}
+
+/// Bookable slots of a worker on `date`, with committed bookings and time already past removed.
+/// None when the worker has no schedule or has stopped taking bookings.
+pub(crate) async fn worker_free_slots<'a>(
+    db: &DbConn,
+    worker: &'a WorkerProfile,
+    date: NaiveDate,
+) -> Result<Option<(&'a WorkerSchedule, Vec<MinuteRange>)>, ApiError> {
+    let Some(schedule) = worker.schedule.as_ref().filter(|_| worker.accepting_bookings.unwrap_or(worker.is_available)) else {
+        return Ok(None);
+    };
+
+    let day_start = ist_instant(date, 0);
+    let day_end = ist_instant(date, 24 * 60);
+    let now = DateTime::now();
+
+    // Committed bookings overlapping the day
+    let mut cursor = db.collection::<Booking>("bookings")
+        .find(
+            doc! {
+                "worker_id": worker.id,
+                "status": { "$in": ["scheduled", "in_progress"] },
+                "scheduled_start": { "$lt": day_end },
+                "scheduled_end": { "$gt": day_start }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let to_minute = |at: DateTime| -> i32 {
+        ((at.timestamp_millis() - day_start.timestamp_millis()) / 60_000).clamp(0, 24 * 60) as i32
+    };
+
+    let mut busy = Vec::new();
+    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
+        let booking = cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        if let (Some(start), Some(end)) = (booking.scheduled_start, booking.scheduled_end) {
+            busy.push((to_minute(start), to_minute(end)));
+        }
+    }
+
+    // Round partial minutes up so a slot never starts in the past
+    let not_before = if now > day_start {
+        ((now.timestamp_millis() - day_start.timestamp_millis() + 59_999) / 60_000) as i32
+    } else {
+        0
+    };
+
+    Ok(Some((schedule, schedule.free_slots(date, &busy, not_before))))
+}
+
+#[openapi(tag = "Worker")]
+#[get("/worker/<worker_id>/slots?<query..>")]
+pub async fn get_worker_slots(
+    db: &State<DbConn>,
+    worker_id: String,
+    query: SlotsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id = ObjectId::parse_str(&worker_id)
+        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+    let date = parse_date(&query.date).map_err(ApiError::bad_request)?;
+
+    let worker = db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+
+    if ist_instant(date, 24 * 60) <= DateTime::now() {
+        return Err(ApiError::bad_request("Date is in the past"));
+    }
+
+    let Some((schedule, free)) = worker_free_slots(db, &worker, date).await? else {
+        return Ok(Json(ApiResponse::success(serde_json::json!({
+            "date": query.date,
+            "slots": [],
+            "message": "Worker has no bookable schedule"
+        }))));
+    };
+
+    let slots: Vec<serde_json::Value> = free
+        .into_iter()
+        .map(|(start, end)| serde_json::json!({
+            "start": ist_instant(date, start).try_to_rfc3339_string().unwrap_or_default(),
+            "end": ist_instant(date, end).try_to_rfc3339_string().unwrap_or_default()
+        }))
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "date": query.date,
+        "timezone": "Asia/Kolkata",
+        "slot_minutes": schedule.slot_minutes,
+        "slots": slots
+    }))))
+}
+
+// ============================================================================
+// PORTFOLIO ENDPOINTS
+// ============================================================================
+
+fn check_portfolio_category(worker: &WorkerProfile, category: &str, subcategory: Option<&str>) -> Result<(), ApiError> {
+    if !worker.categories.iter().any(|c| c.eq_ignore_ascii_case(category)) {
+        return Err(ApiError::bad_request("Category must be one of your profile categories"));
+    }
+    if let Some(subcategory) = subcategory
+        && !worker.subcategories.iter().any(|s| s.eq_ignore_ascii_case(subcategory))
+    {
+        return Err(ApiError::bad_request("Subcategory must be one of your profile subcategories"));
+    }
+    Ok(())
+}
+
+fn parse_item_id(item_id: &str) -> Result<ObjectId, ApiError> {
+    ObjectId::parse_str(item_id).map_err(|_| ApiError::bad_request("Invalid portfolio item ID"))
+}
+
+/// Write back a rearranged portfolio. Guarded on `updated_at` so a concurrent
+/// edit is not silently overwritten.
+async fn save_portfolio(db: &DbConn, worker: &WorkerProfile, portfolio: &[PortfolioItem]) -> Result<(), ApiError> {
+    let portfolio_bson = mongodb::bson::to_bson(portfolio)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let result = db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id, "updated_at": worker.updated_at },
+            doc! { "$set": { "portfolio": portfolio_bson, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update portfolio: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Profile was updated elsewhere, please refresh"));
+    }
+
+    Ok(())
+}
+
+/// Break the before/after pair `item_id` belongs to, if any
+fn unpair(portfolio: &mut [PortfolioItem], item_id: ObjectId) {
+    for item in portfolio.iter_mut() {
+        if item.id == item_id || item.paired_with == Some(item_id) {
+            item.stage = None;
+            item.paired_with = None;
+        }
+    }
+}
+
+#[openapi(tag = "Worker")]
+#[post("/worker/portfolio", data = "<dto>")]
+pub async fn add_portfolio_item(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<AddPortfolioItemDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_own_profile(db, auth.user_id).await?;
+    check_portfolio_category(&worker, &dto.category, dto.subcategory.as_deref())?;
+
+    // Only images that went through the upload pipeline
+    let Some(path) = dto.image_url.strip_prefix('/').filter(|p| p.starts_with("uploads/images/") && !p.contains("..")) else {
+        return Err(ApiError::bad_request("Images must be uploaded through /upload/image first"));
+    };
+    if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+        return Err(ApiError::bad_request("Uploaded image not found"));
+    }
+    if worker.portfolio.iter().any(|item| item.image_url == dto.image_url) {
+        return Err(ApiError::bad_request("Image is already in your portfolio"));
+    }
+
+    let limit = worker.subscription_plan.portfolio_limit();
+    let item = PortfolioItem {
+        id: ObjectId::new(),
+        image_url: dto.image_url.clone(),
+        caption: dto.caption.clone().filter(|c| !c.trim().is_empty()),
+        category: dto.category.clone(),
+        subcategory: dto.subcategory.clone(),
+        stage: None,
+        paired_with: None,
+        created_at: DateTime::now(),
+    };
+    let item_bson = mongodb::bson::to_bson(&item)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    // The size check is part of the update so parallel uploads cannot exceed the cap
+    let result = db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! {
+                "_id": worker.id,
+                "$expr": { "$lt": [{ "$size": { "$ifNull": ["$portfolio", []] } }, limit as i64] }
+            },
+            doc! {
+                "$push": { "portfolio": item_bson },
+                "$set": { "updated_at": DateTime::now() }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to add portfolio item: {}", e)))?;
+
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request(format!(
+            "Your plan allows {} portfolio images. Upgrade to add more.",
+            limit
+        )));
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Portfolio item added".to_string(),
+        serde_json::json!({
+            "item": item,
+            "limit": limit
+        })
+    )))
+}
+
+#[openapi(tag = "Worker")]
+#[put("/worker/portfolio/<item_id>", data = "<dto>")]
+pub async fn update_portfolio_item(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    item_id: String,
+    dto: Json<UpdatePortfolioItemDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let item_id = parse_item_id(&item_id)?;
+    let worker = find_own_profile(db, auth.user_id).await?;
+    let item = worker.portfolio.iter()
+        .find(|item| item.id == item_id)
+        .ok_or_else(|| ApiError::not_found("Portfolio item not found"))?;
+
+    let category = dto.category.as_deref().unwrap_or(&item.category);
+    let subcategory = dto.subcategory.as_deref().or(item.subcategory.as_deref());
+    check_portfolio_category(&worker, category, subcategory)?;
+
+    let mut update_doc = doc! { "updated_at": DateTime::now() };
+    if let Some(ref caption) = dto.caption {
+        update_doc.insert("portfolio.$.caption", Some(caption.trim()).filter(|c| !c.is_empty()));
+    }
+    if let Some(ref category) = dto.category {
+        update_doc.insert("portfolio.$.category", category);
+    }
+    if let Some(ref subcategory) = dto.subcategory {
+        update_doc.insert("portfolio.$.subcategory", subcategory);
+    }
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id, "portfolio.id": item_id },
+            doc! { "$set": update_doc },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update portfolio item: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Portfolio item updated"
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[delete("/worker/portfolio/<item_id>")]
+pub async fn delete_portfolio_item(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    item_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let item_id = parse_item_id(&item_id)?;
+    let worker = find_own_profile(db, auth.user_id).await?;
+    if !worker.portfolio.iter().any(|item| item.id == item_id) {
+        return Err(ApiError::not_found("Portfolio item not found"));
+    }
+
+    let mut portfolio = worker.portfolio.clone();
+    unpair(&mut portfolio, item_id);
+    portfolio.retain(|item| item.id != item_id);
+    save_portfolio(db, &worker, &portfolio).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Portfolio item removed"
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[put("/worker/portfolio/order", data = "<dto>")]
+pub async fn reorder_portfolio(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<ReorderPortfolioDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_own_profile(db, auth.user_id).await?;
+
+    let ids = dto.item_ids.iter()
+        .map(|id| parse_item_id(id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut remaining = worker.portfolio.clone();
+    let mut portfolio = Vec::with_capacity(remaining.len());
+    for id in ids {
+        let position = remaining.iter()
+            .position(|item| item.id == id)
+            .ok_or_else(|| ApiError::bad_request("Order must list each portfolio item exactly once"))?;
+        portfolio.push(remaining.remove(position));
+    }
+    if !remaining.is_empty() {
+        return Err(ApiError::bad_request("Order must list each portfolio item exactly once"));
+    }
+
+    save_portfolio(db, &worker, &portfolio).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Portfolio reordered"
+    }))))
+}
+
+/// Link two items as the before and after shots of the same job
+#[openapi(tag = "Worker")]
+#[post("/worker/portfolio/pair", data = "<dto>")]
+pub async fn pair_portfolio_items(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<PairPortfolioDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let before_id = parse_item_id(&dto.before_id)?;
+    let after_id = parse_item_id(&dto.after_id)?;
+    if before_id == after_id {
+        return Err(ApiError::bad_request("Pick two different images"));
+    }
+
+    let worker = find_own_profile(db, auth.user_id).await?;
+    let mut portfolio = worker.portfolio.clone();
+    if !portfolio.iter().any(|item| item.id == before_id) || !portfolio.iter().any(|item| item.id == after_id) {
+        return Err(ApiError::not_found("Portfolio item not found"));
+    }
+
+    unpair(&mut portfolio, before_id);
+    unpair(&mut portfolio, after_id);
+    for item in portfolio.iter_mut() {
+        if item.id == before_id {
+            item.stage = Some(PortfolioStage::Before);
+            item.paired_with = Some(after_id);
+        } else if item.id == after_id {
+            item.stage = Some(PortfolioStage::After);
+            item.paired_with = Some(before_id);
+        }
+    }
+    save_portfolio(db, &worker, &portfolio).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Before/after pair saved"
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[delete("/worker/portfolio/<item_id>/pair")]
+pub async fn unpair_portfolio_item(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    item_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let item_id = parse_item_id(&item_id)?;
+    let worker = find_own_profile(db, auth.user_id).await?;
+    if !worker.portfolio.iter().any(|item| item.id == item_id) {
+        return Err(ApiError::not_found("Portfolio item not found"));
+    }
+
+    let mut portfolio = worker.portfolio.clone();
+    unpair(&mut portfolio, item_id);
+    save_portfolio(db, &worker, &portfolio).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Before/after pair removed"
+    }))))
+}
+
+// ============================================================================
+// SERVICE COVERAGE ENDPOINTS
+// ============================================================================
+
+async fn save_coverage_area(db: &DbConn, worker: &WorkerProfile, coverage: &ServiceCoverage) -> Result<(), ApiError> {
+    let coverage_bson = mongodb::bson::to_bson(coverage)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let area_bson = mongodb::bson::to_bson(&coverage.area(&worker.location))
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker.id },
+            doc! {
+                "$set": {
+                    "coverage": coverage_bson,
+                    "coverage_area": area_bson,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| match *e.kind {
+            // The 2dsphere index rejects self-intersecting or degenerate shapes
+            mongodb::error::ErrorKind::Write(_) => ApiError::bad_request("Coverage area is not a valid shape"),
+            _ => ApiError::internal_error(format!("Failed to save coverage: {}", e)),
+        })?;
+
+    Ok(())
+}
+
+#[openapi(tag = "Worker")]
+#[put("/worker/coverage", data = "<dto>")]
+pub async fn update_worker_coverage(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateCoverageDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = find_own_profile(db, auth.user_id).await?;
+    let dto = dto.into_inner();
+
+    let coverage = match (dto.radius_km, dto.polygons) {
+        (Some(radius_km), None) => {
+            if radius_km <= 0.0 || radius_km > MAX_COVERAGE_RADIUS_KM {
+                return Err(ApiError::bad_request(format!(
+                    "Radius must be more than 0 and at most {} km",
+                    MAX_COVERAGE_RADIUS_KM
+                )));
+            }
+            ServiceCoverage {
+                mode: CoverageMode::Radius,
+                radius_km: Some(radius_km),
+                polygons: Vec::new(),
+                updated_at: DateTime::now(),
+            }
+        }
+        (None, Some(polygons)) => {
+            if polygons.is_empty() || polygons.len() > MAX_COVERAGE_POLYGONS {
+                return Err(ApiError::bad_request(format!(
+                    "Draw between 1 and {} areas",
+                    MAX_COVERAGE_POLYGONS
+                )));
+            }
+            let polygons = polygons
+                .into_iter()
+                .map(normalize_ring)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ApiError::bad_request)?;
+            ServiceCoverage {
+                mode: CoverageMode::Polygons,
+                radius_km: None,
+                polygons,
+                updated_at: DateTime::now(),
+            }
+        }
+        _ => return Err(ApiError::bad_request("Provide either radius_km or polygons")),
+    };
+
+    save_coverage_area(db, &worker, &coverage).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Service coverage updated",
+        "coverage": coverage
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[delete("/worker/coverage")]
+pub async fn delete_worker_coverage(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let result = db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "user_id": auth.user_id },
+            doc! {
+                "$unset": { "coverage": "", "coverage_area": "" },
+                "$set": { "updated_at": DateTime::now() }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to remove coverage: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("Worker profile not found"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Service coverage removed"
+    }))))
+}
+
+/// Workers whose coverage includes the point, wherever they are based
+#[openapi(tag = "Worker")]
+#[get("/worker/serving?<query..>")]
+pub async fn find_serving_workers(
+    db: &State<DbConn>,
+    query: ServingWorkerQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(50);
+    let skip = (page - 1) * limit;
+
+    if !(-90.0..=90.0).contains(&query.latitude) || !(-180.0..=180.0).contains(&query.longitude) {
+        return Err(ApiError::bad_request("Invalid coordinates"));
+    }
+
+    let point = doc! { "type": "Point", "coordinates": [query.longitude, query.latitude] };
+    let mut query_filter = doc! {
+        "is_verified": true,
+        "is_available": true,
+        "coverage_area": { "$geoIntersects": { "$geometry": point.clone() } },
+    };
+
+    if let Some(category) = &query.category {
+        query_filter.insert("categories", category);
+    }
+
+    if let Some(subcategory) = &query.subcategory {
+        query_filter.insert("subcategories", subcategory);
+    }
+
+    if let Some(min_rating) = query.min_rating {
+        query_filter.insert("rating", doc! { "$gte": min_rating });
+    }
+
+    if let Some(max_rate) = query.max_rate {
+        query_filter.insert("hourly_rate", doc! { "$lte": max_rate });
+    }
+
+    // `$geoNear` on the base location only adds the distance; coverage decides who is in
+    let pipeline = vec![
+        doc! {
+            "$geoNear": {
+                "near": point,
+                "distanceField": "distance",
+                "query": query_filter,
+                "spherical": true,
+                "key": "location"
+            }
+        },
+        doc! {
+            "$facet": {
+                "workers": [
+                    { "$sort": { "distance": 1, "rating": -1, "favorite_count": -1, "_id": 1 } },
+                    { "$skip": skip },
+                    { "$limit": limit }
+                ],
+                "total": [
+                    { "$count": "count" }
+                ]
+            }
+        },
+    ];
+
+    let mut cursor = db
+        .collection::<mongodb::bson::Document>("worker_profiles")
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    let result = if cursor.advance().await.map_err(|e| ApiError::internal_error(e.to_string()))? {
+        cursor.deserialize_current()
+            .map_err(|e| ApiError::internal_error(e.to_string()))?
+    } else {
+        mongodb::bson::Document::new()
+    };
+
+    let (workers, total) = geo_results(db, &result)?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "workers": workers,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64
+        }
+    }))))
+}
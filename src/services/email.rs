@@ -1,10 +1,49 @@
 use lettre::{
     Message, SmtpTransport, Transport,
-    message::{header::ContentType, Mailbox},
+    message::{
+        header::{ContentType, Header, HeaderName, HeaderValue},
+        Attachment, Mailbox, MultiPart, SinglePart,
+    },
     transport::smtp::authentication::Credentials,
 };
 use log::{info, error, warn};
 
+/// Lets mail clients offer their own unsubscribe button
+#[derive(Clone)]
+struct ListUnsubscribe(String);
+
+impl Header for ListUnsubscribe {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe")
+    }
+
+    fn parse(s: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self(s.trim_matches(|c| c == '<' || c == '>').to_string()))
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), format!("<{}>", self.0))
+    }
+}
+
+/// RFC 8058 one-click: the client POSTs to the `List-Unsubscribe` URL
+#[derive(Clone)]
+struct ListUnsubscribePost;
+
+impl Header for ListUnsubscribePost {
+    fn name() -> HeaderName {
+        HeaderName::new_from_ascii_str("List-Unsubscribe-Post")
+    }
+
+    fn parse(_: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self)
+    }
+
+    fn display(&self) -> HeaderValue {
+        HeaderValue::new(Self::name(), "List-Unsubscribe=One-Click".to_string())
+    }
+}
+
 pub struct EmailService;
 
 impl EmailService {
@@ -164,4 +203,146 @@ impl EmailService {
         mailer.send(&email_message)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    pub async fn send_invoice_email(email: &str, invoice_number: &str, html: &str, pdf: Vec<u8>) -> bool {
+        match Self::try_send_invoice(email, invoice_number, html, pdf).await {
+            Ok(_) => {
+                info!("Invoice {} emailed to {}", invoice_number, email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to email invoice {} to {}: {}", invoice_number, email, e);
+                false
+            }
+        }
+    }
+
+    async fn try_send_invoice(email: &str, invoice_number: &str, html: &str, pdf: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        let mail_user = crate::config::Config::mail_user();
+        let mail_password = crate::config::Config::mail_password();
+
+        if mail_user.is_empty() || mail_password.is_empty() {
+            warn!("Email credentials not configured. Skipping email send.");
+            return Err("Email not configured".into());
+        }
+
+        let from_mailbox: Mailbox = crate::config::Config::mail_from().parse()?;
+        let to_mailbox: Mailbox = email.parse()?;
+
+        let filename = format!("invoice-{}.pdf", invoice_number.replace('/', "-"));
+        let attachment = Attachment::new(filename).body(pdf, ContentType::parse("application/pdf")?);
+
+        let email_message = Message::builder()
+            .from(from_mailbox)
+            .to(to_mailbox)
+            .subject(format!("Your Mento Services invoice {}", invoice_number))
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::html(html.to_string()))
+                    .singlepart(attachment),
+            )?;
+
+        let creds = Credentials::new(mail_user, mail_password);
+        let mailer = SmtpTransport::relay(&crate::config::Config::mail_host())?
+            .credentials(creds)
+            .build();
+
+        mailer.send(&email_message)?;
+        Ok(())
+    }
+
+    pub async fn send_notification_email(email: &str, subject: &str, message: &str) -> bool {
+        match Self::try_send_notification(email, subject, message, None).await {
+            Ok(_) => {
+                info!("Notification '{}' emailed to {}", subject, email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to email notification '{}' to {}: {}", subject, email, e);
+                false
+            }
+        }
+    }
+
+    /// Notification email with an unsubscribe link in the footer
+    pub async fn send_alert_email(email: &str, subject: &str, message: &str, unsubscribe_url: &str) -> bool {
+        match Self::try_send_notification(email, subject, message, Some(unsubscribe_url)).await {
+            Ok(_) => {
+                info!("Alert '{}' emailed to {}", subject, email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to email alert '{}' to {}: {}", subject, email, e);
+                false
+            }
+        }
+    }
+
+    async fn try_send_notification(email: &str, subject: &str, message: &str, unsubscribe_url: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let mail_user = crate::config::Config::mail_user();
+        let mail_password = crate::config::Config::mail_password();
+
+        if mail_user.is_empty() || mail_password.is_empty() {
+            warn!("Email credentials not configured. Skipping email send.");
+            return Err("Email not configured".into());
+        }
+
+        let from_mailbox: Mailbox = crate::config::Config::mail_from().parse()?;
+        let to_mailbox: Mailbox = email.parse()?;
+
+        let paragraphs: String = message
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| format!("<p>{}</p>", html_escape(line)))
+            .collect();
+
+        let email_body = format!(
+            r#"
+            <!DOCTYPE html>
+            <html>
+            <body>
+                <h2>{}</h2>
+                {}
+                <p>Best regards,<br><strong>Mento Services Team</strong></p>
+                {}
+            </body>
+            </html>
+            "#,
+            html_escape(subject),
+            paragraphs,
+            unsubscribe_url
+                .map(|url| format!(
+                    r#"<p style="color: #666; font-size: 12px;">Don't want these emails? <a href="{}">Unsubscribe</a></p>"#,
+                    html_escape(url)
+                ))
+                .unwrap_or_default()
+        );
+
+        let mut builder = Message::builder()
+            .from(from_mailbox)
+            .to(to_mailbox)
+            .subject(subject)
+            .header(ContentType::TEXT_HTML);
+        if let Some(url) = unsubscribe_url {
+            builder = builder
+                .header(ListUnsubscribe(url.to_string()))
+                .header(ListUnsubscribePost);
+        }
+        let email_message = builder.body(email_body)?;
+
+        let creds = Credentials::new(mail_user, mail_password);
+        let mailer = SmtpTransport::relay(&crate::config::Config::mail_host())?
+            .credentials(creds)
+            .build();
+
+        mailer.send(&email_message)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
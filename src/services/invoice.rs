@@ -0,0 +1,226 @@
+use chrono::{Datelike, FixedOffset, TimeZone};
+use mongodb::bson::DateTime;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use rocket_dyn_templates::tera::{Context, Tera};
+use serde_json::json;
+
+use crate::models::{GstSupplyType, Invoice};
+
+const INVOICE_TEMPLATE: &str = include_str!("../../templates/invoice.html.tera");
+
+/// Indian Standard Time, used for invoice dates and financial years
+const IST_OFFSET_SECS: i32 = 5 * 3600 + 30 * 60;
+
+pub struct InvoiceService;
+
+/// Tax split of a GST-inclusive amount
+pub struct GstBreakdown {
+    pub taxable_value: f64,
+    pub cgst: f64,
+    pub sgst: f64,
+    pub igst: f64,
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl InvoiceService {
+    /// Split a GST-inclusive `total` into taxable value and CGST/SGST or IGST.
+    /// Rounding differences go to the second component so the parts add up to `total`.
+    pub fn gst_breakdown(total: f64, rate: f64, supply_type: &GstSupplyType) -> GstBreakdown {
+        let taxable_value = round2(total / (1.0 + rate / 100.0));
+        let tax = round2(total - taxable_value);
+
+        match supply_type {
+            GstSupplyType::Intrastate => {
+                let cgst = round2(tax / 2.0);
+                GstBreakdown { taxable_value, cgst, sgst: round2(tax - cgst), igst: 0.0 }
+            }
+            GstSupplyType::Interstate => GstBreakdown { taxable_value, cgst: 0.0, sgst: 0.0, igst: tax },
+        }
+    }
+
+    /// Same state supply is taxed as CGST + SGST, anything else as IGST.
+    /// Customers without a known state are treated as being in the supplier's state.
+    pub fn supply_type(supplier_state: &str, customer_state: Option<&str>) -> GstSupplyType {
+        match customer_state {
+            Some(state) if !state.trim().eq_ignore_ascii_case(supplier_state.trim()) => GstSupplyType::Interstate,
+            _ => GstSupplyType::Intrastate,
+        }
+    }
+
+    /// Indian financial year (April - March) of a date, e.g. "2026-27"
+    pub fn financial_year(date: DateTime) -> String {
+        let ist = Self::to_ist(date);
+        let start = if ist.month() >= 4 { ist.year() } else { ist.year() - 1 };
+        format!("{}-{:02}", start, (start + 1) % 100)
+    }
+
+    fn to_ist(date: DateTime) -> chrono::DateTime<FixedOffset> {
+        FixedOffset::east_opt(IST_OFFSET_SECS)
+            .unwrap()
+            .timestamp_millis_opt(date.timestamp_millis())
+            .unwrap()
+    }
+
+    fn issued_on(invoice: &Invoice) -> String {
+        Self::to_ist(invoice.issued_at).format("%d %b %Y").to_string()
+    }
+
+    pub fn render_html(invoice: &Invoice) -> Result<String, String> {
+        let mut tera = Tera::default();
+        tera.add_raw_template("invoice.html", INVOICE_TEMPLATE)
+            .map_err(|e| e.to_string())?;
+
+        let items: Vec<serde_json::Value> = invoice
+            .items
+            .iter()
+            .map(|item| {
+                json!({
+                    "description": item.description,
+                    "sac_code": item.sac_code,
+                    "quantity": item.quantity,
+                    "taxable_value": format!("{:.2}", item.taxable_value)
+                })
+            })
+            .collect();
+
+        let mut context = Context::new();
+        context.insert("invoice", invoice);
+        context.insert("issued_on", &Self::issued_on(invoice));
+        context.insert("items", &items);
+        context.insert("amounts", &json!({
+            "rate": invoice.gst_rate,
+            "half_rate": invoice.gst_rate / 2.0,
+            "cgst": format!("{:.2}", invoice.cgst),
+            "sgst": format!("{:.2}", invoice.sgst),
+            "igst": format!("{:.2}", invoice.igst),
+            "total": format!("{:.2}", invoice.total)
+        }));
+
+        tera.render("invoice.html", &context).map_err(|e| e.to_string())
+    }
+
+    /// Single page A4 PDF with the same content as the HTML invoice
+    pub fn render_pdf(invoice: &Invoice) -> Result<Vec<u8>, String> {
+        let (doc, page, layer) = PdfDocument::new(
+            format!("Tax Invoice {}", invoice.invoice_number),
+            Mm(210.0),
+            Mm(297.0),
+            "Invoice",
+        );
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let mut pdf = PdfWriter { layer, y: 275.0 };
+
+        pdf.line("Tax Invoice", 18.0, 20.0, &bold);
+        pdf.gap(4.0);
+        pdf.line(&format!("Invoice No: {}", invoice.invoice_number), 10.0, 20.0, &regular);
+        pdf.line(&format!("Date: {}", Self::issued_on(invoice)), 10.0, 20.0, &regular);
+        if let Some(ref payment_id) = invoice.razorpay_payment_id {
+            pdf.line(&format!("Payment Ref: {}", payment_id), 10.0, 20.0, &regular);
+        }
+        pdf.line(&format!("Place of Supply: {}", invoice.place_of_supply), 10.0, 20.0, &regular);
+        pdf.gap(6.0);
+
+        let top = pdf.y;
+        for (x, title, party) in [(20.0, "Supplier", &invoice.supplier), (110.0, "Billed To", &invoice.customer)] {
+            pdf.y = top;
+            pdf.line(title, 11.0, x, &bold);
+            pdf.line(&party.name, 10.0, x, &regular);
+            for value in [&party.address, &party.state, &party.email].into_iter().flatten() {
+                pdf.line(value, 10.0, x, &regular);
+            }
+            if let Some(ref gstin) = party.gstin {
+                pdf.line(&format!("GSTIN: {}", gstin), 10.0, x, &regular);
+            }
+        }
+        pdf.gap(10.0);
+
+        pdf.row(&["Description", "SAC", "Qty", &format!("Taxable ({})", invoice.currency)], &bold);
+        for item in &invoice.items {
+            pdf.row(
+                &[&item.description, &item.sac_code, &item.quantity.to_string(), &format!("{:.2}", item.taxable_value)],
+                &regular,
+            );
+        }
+        pdf.gap(2.0);
+
+        match invoice.supply_type {
+            GstSupplyType::Intrastate => {
+                let half_rate = invoice.gst_rate / 2.0;
+                pdf.row(&[&format!("CGST @ {}%", half_rate), "", "", &format!("{:.2}", invoice.cgst)], &regular);
+                pdf.row(&[&format!("SGST @ {}%", half_rate), "", "", &format!("{:.2}", invoice.sgst)], &regular);
+            }
+            GstSupplyType::Interstate => {
+                pdf.row(&[&format!("IGST @ {}%", invoice.gst_rate), "", "", &format!("{:.2}", invoice.igst)], &regular);
+            }
+        }
+        pdf.row(&["Total", "", "", &format!("{:.2}", invoice.total)], &bold);
+
+        pdf.gap(12.0);
+        pdf.line("This is a computer generated invoice and does not require a signature.", 8.0, 20.0, &regular);
+
+        doc.save_to_bytes().map_err(|e| e.to_string())
+    }
+}
+
+/// Minimal top-down text cursor over a printpdf layer
+struct PdfWriter {
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn line(&mut self, text: &str, size: f32, x: f32, font: &IndirectFontRef) {
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+        self.y -= size * 0.5;
+    }
+
+    fn row(&mut self, columns: &[&str], font: &IndirectFontRef) {
+        for (text, x) in columns.iter().zip([20.0, 110.0, 140.0, 160.0]) {
+            self.layer.use_text(*text, 10.0, Mm(x), Mm(self.y), font);
+        }
+        self.y -= 7.0;
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.y -= height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intrastate_splits_tax_into_cgst_and_sgst() {
+        let breakdown = InvoiceService::gst_breakdown(118.0, 18.0, &GstSupplyType::Intrastate);
+
+        assert_eq!(breakdown.taxable_value, 100.0);
+        assert_eq!(breakdown.cgst, 9.0);
+        assert_eq!(breakdown.sgst, 9.0);
+        assert_eq!(breakdown.igst, 0.0);
+    }
+
+    #[test]
+    fn interstate_charges_igst() {
+        let breakdown = InvoiceService::gst_breakdown(118.0, 18.0, &GstSupplyType::Interstate);
+
+        assert_eq!(breakdown.taxable_value, 100.0);
+        assert_eq!(breakdown.cgst, 0.0);
+        assert_eq!(breakdown.sgst, 0.0);
+        assert_eq!(breakdown.igst, 18.0);
+    }
+
+    #[test]
+    fn rounding_leaves_parts_adding_up_to_total() {
+        for total in [1.0, 1.5, 99.99, 499.0, 1234.57] {
+            let b = InvoiceService::gst_breakdown(total, 18.0, &GstSupplyType::Intrastate);
+            assert!((b.taxable_value + b.cgst + b.sgst - total).abs() < 0.001, "total {}", total);
+        }
+    }
+}
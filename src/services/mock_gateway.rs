@@ -0,0 +1,177 @@
+use mongodb::bson::oid::ObjectId;
+use serde_json::json;
+use super::payment_gateway::{hmac_sha256_hex, parse_webhook_body, verify_hmac_sha256_hex, PaymentGateway, WebhookEvent};
+
+/// Fixed secrets so signatures produced by the mock are reproducible in tests
+pub const MOCK_KEY_SECRET: &str = "mock_key_secret";
+pub const MOCK_WEBHOOK_SECRET: &str = "mock_webhook_secret";
+
+/// Offline gateway for local development and integration tests.
+///
+/// Orders, payments, refunds and subscriptions are made up on the spot and
+/// succeed immediately; nothing leaves the process.
+pub struct MockGateway;
+
+fn mock_id(prefix: &str) -> String {
+    format!("{}_mock{}", prefix, ObjectId::new().to_hex())
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+impl MockGateway {
+    /// Simulate a successful checkout for an order: a payment id and the signature
+    /// the client would receive from Razorpay Checkout
+    pub fn pay_order(order_id: &str) -> Result<(String, String), String> {
+        let payment_id = mock_id("pay");
+        let signature = hmac_sha256_hex(MOCK_KEY_SECRET, &format!("{}|{}", order_id, payment_id))?;
+        Ok((payment_id, signature))
+    }
+
+    /// Signature header for a webhook body sent to the mock gateway
+    #[cfg(test)]
+    pub fn sign_webhook(body: &str) -> Result<String, String> {
+        hmac_sha256_hex(MOCK_WEBHOOK_SECRET, body)
+    }
+}
+
+#[rocket::async_trait]
+impl PaymentGateway for MockGateway {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": mock_id("order"),
+            "entity": "order",
+            "amount": (amount * 100.0).round() as i64,
+            "currency": "INR",
+            "status": "created",
+            "created_at": now_secs()
+        }))
+    }
+
+    fn verify_payment_signature(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<bool, String> {
+        verify_hmac_sha256_hex(MOCK_KEY_SECRET, &format!("{}|{}", order_id, payment_id), signature)
+    }
+
+    async fn fetch_payment(&self, payment_id: &str) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": payment_id,
+            "entity": "payment",
+            "status": "captured",
+            "currency": "INR",
+            "captured": true
+        }))
+    }
+
+    async fn refund(&self, payment_id: &str, amount_paise: i64, reason: Option<&str>) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": mock_id("rfnd"),
+            "entity": "refund",
+            "payment_id": payment_id,
+            "amount": amount_paise,
+            "currency": "INR",
+            "status": "processed",
+            "notes": { "reason": reason.unwrap_or("") },
+            "created_at": now_secs()
+        }))
+    }
+
+    async fn fetch_refund(&self, payment_id: &str, refund_id: &str) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": refund_id,
+            "entity": "refund",
+            "payment_id": payment_id,
+            "status": "processed"
+        }))
+    }
+
+    fn parse_webhook(&self, body: &str, signature: &str) -> Result<WebhookEvent, String> {
+        if !verify_hmac_sha256_hex(MOCK_WEBHOOK_SECRET, body, signature)? {
+            return Err("Invalid webhook signature".to_string());
+        }
+
+        parse_webhook_body(body)
+    }
+
+    async fn create_plan(&self, name: &str, period: &str, interval: i64, amount_paise: i64) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": mock_id("plan"),
+            "entity": "plan",
+            "period": period,
+            "interval": interval,
+            "item": { "name": name, "amount": amount_paise, "currency": "INR" }
+        }))
+    }
+
+    async fn create_subscription(
+        &self,
+        plan_id: &str,
+        total_count: i64,
+        start_at: i64,
+        notes: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let id = mock_id("sub");
+        Ok(json!({
+            "id": &id,
+            "entity": "subscription",
+            "plan_id": plan_id,
+            "status": "created",
+            "total_count": total_count,
+            "start_at": start_at,
+            "notes": notes,
+            "short_url": format!("http://localhost/mock/subscriptions/{}", id)
+        }))
+    }
+
+    async fn cancel_subscription(&self, subscription_id: &str) -> Result<serde_json::Value, String> {
+        Ok(json!({ "id": subscription_id, "entity": "subscription", "status": "cancelled" }))
+    }
+
+    async fn update_subscription_plan(&self, subscription_id: &str, plan_id: &str) -> Result<serde_json::Value, String> {
+        Ok(json!({ "id": subscription_id, "entity": "subscription", "plan_id": plan_id, "status": "active" }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkout_signature_round_trips() {
+        let (payment_id, signature) = MockGateway::pay_order("order_123").unwrap();
+
+        assert!(MockGateway.verify_payment_signature("order_123", &payment_id, &signature).unwrap());
+        assert!(!MockGateway.verify_payment_signature("order_456", &payment_id, &signature).unwrap());
+    }
+
+    #[test]
+    fn malformed_checkout_signature_is_rejected() {
+        let (payment_id, signature) = MockGateway::pay_order("order_123").unwrap();
+
+        assert!(!MockGateway.verify_payment_signature("order_123", &payment_id, "not hex").unwrap());
+        assert!(!MockGateway.verify_payment_signature("order_123", &payment_id, &signature[..32]).unwrap());
+    }
+
+    #[test]
+    fn webhook_round_trips() {
+        let body = r#"{"event":"refund.processed","payload":{"refund":{"entity":{"id":"rfnd_1"}}}}"#;
+        let signature = MockGateway::sign_webhook(body).unwrap();
+
+        let event = MockGateway.parse_webhook(body, &signature).unwrap();
+        assert_eq!(event.event, "refund.processed");
+        assert_eq!(event.payload["refund"]["entity"]["id"], "rfnd_1");
+    }
+
+    #[test]
+    fn tampered_webhook_is_rejected() {
+        let body = r#"{"event":"refund.processed","payload":{}}"#;
+        let signature = MockGateway::sign_webhook(body).unwrap();
+
+        let tampered = body.replace("processed", "failed");
+        assert!(MockGateway.parse_webhook(&tampered, &signature).is_err());
+    }
+}
@@ -1,9 +1,16 @@
 pub mod email;
+pub mod invoice;
 pub mod jwt;
+pub mod mock_gateway;
 pub mod msg91;
+pub mod payment_gateway;
+pub mod push;
 pub mod razorpay;
 
-pub use razorpay::RazorpayService;
+pub use payment_gateway::payment_gateway;
+pub use mock_gateway::MockGateway;
 pub use email::EmailService;
+pub use invoice::InvoiceService;
 pub use jwt::JwtService;
-pub use msg91::Msg91Service;
\ No newline at end of file
+pub use msg91::Msg91Service;
+pub use push::PushService;
\ No newline at end of file
@@ -0,0 +1,97 @@
+use std::sync::OnceLock;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::config::Config;
+use super::mock_gateway::MockGateway;
+use super::razorpay::RazorpayGateway;
+
+/// A verified webhook delivery
+pub struct WebhookEvent {
+    pub event: String,              // e.g. "refund.processed", "subscription.charged"
+    pub payload: serde_json::Value, // Razorpay-shaped `payload` object
+}
+
+/// Everything the subscription flow needs from a payment provider.
+///
+/// Amounts passed to `create_order` are in INR, everything else uses paise like
+/// the Razorpay API. Responses are Razorpay-shaped JSON for every implementation.
+#[rocket::async_trait]
+pub trait PaymentGateway: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String>;
+
+    /// Verify the checkout signature returned for a paid order
+    fn verify_payment_signature(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<bool, String>;
+
+    async fn fetch_payment(&self, payment_id: &str) -> Result<serde_json::Value, String>;
+
+    async fn refund(&self, payment_id: &str, amount_paise: i64, reason: Option<&str>) -> Result<serde_json::Value, String>;
+
+    async fn fetch_refund(&self, payment_id: &str, refund_id: &str) -> Result<serde_json::Value, String>;
+
+    /// Verify a webhook body against its signature header and parse it
+    fn parse_webhook(&self, body: &str, signature: &str) -> Result<WebhookEvent, String>;
+
+    async fn create_plan(&self, name: &str, period: &str, interval: i64, amount_paise: i64) -> Result<serde_json::Value, String>;
+
+    async fn create_subscription(
+        &self,
+        plan_id: &str,
+        total_count: i64,
+        start_at: i64,
+        notes: serde_json::Value,
+    ) -> Result<serde_json::Value, String>;
+
+    async fn cancel_subscription(&self, subscription_id: &str) -> Result<serde_json::Value, String>;
+
+    async fn update_subscription_plan(&self, subscription_id: &str, plan_id: &str) -> Result<serde_json::Value, String>;
+}
+
+/// Gateway selected by `PAYMENT_GATEWAY` ("razorpay" or "mock"), chosen once at first use
+pub fn payment_gateway() -> &'static dyn PaymentGateway {
+    static GATEWAY: OnceLock<Box<dyn PaymentGateway>> = OnceLock::new();
+
+    GATEWAY
+        .get_or_init(|| match Config::payment_gateway().as_str() {
+            "mock" => {
+                warn!("Using the mock payment gateway, no real payments will be taken");
+                Box::new(MockGateway)
+            }
+            _ => Box::new(RazorpayGateway),
+        })
+        .as_ref()
+}
+
+/// Hex encoded HMAC-SHA256, the signature scheme used for checkout and webhooks
+pub fn hmac_sha256_hex(secret: &str, message: &str) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| "Invalid HMAC key".to_string())?;
+
+    mac.update(message.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check a hex encoded HMAC-SHA256 signature in constant time
+pub fn verify_hmac_sha256_hex(secret: &str, message: &str, signature: &str) -> Result<bool, String> {
+    let Ok(signature) = hex::decode(signature) else {
+        return Ok(false);
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| "Invalid HMAC key".to_string())?;
+
+    mac.update(message.as_bytes());
+    Ok(mac.verify_slice(&signature).is_ok())
+}
+
+/// Parse a webhook body whose signature has already been checked
+pub fn parse_webhook_body(body: &str) -> Result<WebhookEvent, String> {
+    let mut value: serde_json::Value = serde_json::from_str(body)
+        .map_err(|_| "Invalid webhook payload".to_string())?;
+
+    Ok(WebhookEvent {
+        event: value["event"].as_str().unwrap_or_default().to_string(),
+        payload: value["payload"].take(),
+    })
+}
@@ -0,0 +1,177 @@
+use std::sync::OnceLock;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+use crate::models::FcmToken;
+
+const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+/// Refresh the access token this long before Google expires it
+const TOKEN_REFRESH_MARGIN_SECS: i64 = 60;
+
+/// The fields we need from a Firebase service-account key file
+#[derive(Deserialize)]
+struct ServiceAccount {
+    project_id: Option<String>,
+    client_email: String,
+    private_key: String,
+    token_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AssertionClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: i64,
+}
+
+struct AccessToken {
+    token: String,
+    expires_at: i64,
+}
+
+pub struct PushService;
+
+impl PushService {
+    /// Push a notification to every device registered on the token set.
+    /// Returns how many devices accepted it.
+    pub async fn send(tokens: &FcmToken, title: &str, body: &str, data: serde_json::Value) -> usize {
+        let path = crate::config::Config::fcm_service_account_file();
+        if path.is_empty() {
+            warn!("FCM service account not configured. Skipping push notification.");
+            return 0;
+        }
+
+        let client = Client::new();
+        let (project_id, access_token) = match Self::credentials(&client, &path).await {
+            Ok(credentials) => credentials,
+            Err(e) => {
+                error!("Failed to authenticate with FCM: {}", e);
+                return 0;
+            }
+        };
+
+        let mut delivered = 0;
+        for token in [&tokens.android, &tokens.ios].into_iter().flatten() {
+            match Self::try_send(&client, &project_id, &access_token, token, title, body, &data).await {
+                Ok(()) => delivered += 1,
+                Err(e) => error!("Failed to push '{}': {}", title, e),
+            }
+        }
+        delivered
+    }
+
+    /// Project to send through and a current OAuth2 access token, cached until shortly before it expires
+    async fn credentials(client: &Client, path: &str) -> Result<(String, String), String> {
+        static TOKEN: OnceLock<Mutex<Option<AccessToken>>> = OnceLock::new();
+
+        let account: ServiceAccount = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path, e))
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| format!("invalid service account key: {}", e)))?;
+
+        let project_id = Some(crate::config::Config::fcm_project_id())
+            .filter(|id| !id.is_empty())
+            .or(account.project_id.clone())
+            .ok_or("FCM project ID not configured")?;
+
+        let mut cached = TOKEN.get_or_init(|| Mutex::new(None)).lock().await;
+        let now = chrono::Utc::now().timestamp();
+        if let Some(ref token) = *cached
+            && token.expires_at - TOKEN_REFRESH_MARGIN_SECS > now
+        {
+            return Ok((project_id, token.token.clone()));
+        }
+
+        let token = Self::fetch_access_token(client, &account, now).await?;
+        let access_token = token.token.clone();
+        *cached = Some(token);
+        Ok((project_id, access_token))
+    }
+
+    /// Exchange a JWT signed with the service account's key for an access token
+    async fn fetch_access_token(client: &Client, account: &ServiceAccount, now: i64) -> Result<AccessToken, String> {
+        let token_uri = account.token_uri.as_deref().unwrap_or(GOOGLE_TOKEN_URL);
+        let claims = AssertionClaims {
+            iss: &account.client_email,
+            scope: FCM_SCOPE,
+            aud: token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let key = EncodingKey::from_rsa_pem(account.private_key.as_bytes()).map_err(|e| e.to_string())?;
+        let assertion = encode(&Header::new(Algorithm::RS256), &claims, &key).map_err(|e| e.to_string())?;
+
+        let res = client
+            .post(token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            return Err(res.text().await.unwrap_or_else(|_| "Token exchange failed".to_string()));
+        }
+
+        let token: TokenResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(AccessToken {
+            token: token.access_token,
+            expires_at: now + token.expires_in,
+        })
+    }
+
+    async fn try_send(
+        client: &Client,
+        project_id: &str,
+        access_token: &str,
+        token: &str,
+        title: &str,
+        body: &str,
+        data: &serde_json::Value,
+    ) -> Result<(), String> {
+        // HTTP v1 only accepts string values in `data`
+        let data: serde_json::Map<String, serde_json::Value> = data
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| {
+                let value = value.as_str().map_or_else(|| value.to_string(), str::to_string);
+                (key.clone(), serde_json::Value::String(value))
+            })
+            .collect();
+
+        let res = client
+            .post(format!("https://fcm.googleapis.com/v1/projects/{}/messages:send", project_id))
+            .bearer_auth(access_token)
+            .json(&json!({
+                "message": {
+                    "token": token,
+                    "notification": { "title": title, "body": body },
+                    "data": data,
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            return Err(res.text().await.unwrap_or_else(|_| "FCM send failed".to_string()));
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use reqwest::Client;
 use serde_json::json;
+use super::payment_gateway::{parse_webhook_body, verify_hmac_sha256_hex, PaymentGateway, WebhookEvent};
 
-pub struct RazorpayService;
+const RAZORPAY_BASE: &str = "https://api.razorpay.com/v1";
 
-impl RazorpayService {
+pub struct RazorpayGateway;
+
+impl RazorpayGateway {
     fn key_id() -> String {
         std::env::var("RAZORPAY_KEY_ID").unwrap()
     }
@@ -12,14 +15,36 @@ impl RazorpayService {
         std::env::var("RAZORPAY_KEY_SECRET").unwrap()
     }
 
-    pub async fn create_order(amount: i64) -> Result<serde_json::Value, String> {
+    async fn parse_response(res: reqwest::Response) -> Result<serde_json::Value, String> {
+        let status = res.status();
+        let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
+
+        if !status.is_success() {
+            let description = body["error"]["description"]
+                .as_str()
+                .unwrap_or("Razorpay request failed");
+            return Err(description.to_string());
+        }
+
+        Ok(body)
+    }
+}
+
+#[rocket::async_trait]
+impl PaymentGateway for RazorpayGateway {
+    fn name(&self) -> &'static str {
+        "razorpay"
+    }
+
+    /// Create a one-off order. `amount` is in INR and converted to paise for Razorpay.
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String> {
         let client = Client::new();
 
         let res = client
-            .post("https://api.razorpay.com/v1/orders")
+            .post(format!("{}/orders", RAZORPAY_BASE))
             .basic_auth(Self::key_id(), Some(Self::key_secret()))
             .json(&json!({
-                "amount": amount * 100,
+                "amount": (amount * 100.0).round() as i64,
                 "currency": "INR",
                 "payment_capture": 1
             }))
@@ -27,6 +52,150 @@ impl RazorpayService {
             .await
             .map_err(|e| e.to_string())?;
 
-        Ok(res.json().await.map_err(|e| e.to_string())?)
+        Self::parse_response(res).await
+    }
+
+    /// Refund a captured payment. `amount_paise` is in the smallest currency unit.
+    async fn refund(&self, payment_id: &str, amount_paise: i64, reason: Option<&str>) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .post(format!("{}/payments/{}/refund", RAZORPAY_BASE, payment_id))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&json!({
+                "amount": amount_paise,
+                "speed": "normal",
+                "notes": { "reason": reason.unwrap_or("") }
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    /// Fetch the current state of a refund ("pending", "processed" or "failed")
+    async fn fetch_refund(&self, payment_id: &str, refund_id: &str) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .get(format!("{}/payments/{}/refunds/{}", RAZORPAY_BASE, payment_id, refund_id))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    /// Create a recurring plan. `period` is one of "daily", "weekly", "monthly" or "yearly".
+    async fn create_plan(&self, name: &str, period: &str, interval: i64, amount_paise: i64) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .post(format!("{}/plans", RAZORPAY_BASE))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&json!({
+                "period": period,
+                "interval": interval,
+                "item": {
+                    "name": name,
+                    "amount": amount_paise,
+                    "currency": "INR"
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    /// Create a recurring subscription on a plan. The first charge happens at `start_at` (unix seconds).
+    async fn create_subscription(
+        &self,
+        plan_id: &str,
+        total_count: i64,
+        start_at: i64,
+        notes: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .post(format!("{}/subscriptions", RAZORPAY_BASE))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&json!({
+                "plan_id": plan_id,
+                "total_count": total_count,
+                "start_at": start_at,
+                "customer_notify": 1,
+                "notes": notes
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    async fn cancel_subscription(&self, subscription_id: &str) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .post(format!("{}/subscriptions/{}/cancel", RAZORPAY_BASE, subscription_id))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&json!({ "cancel_at_cycle_end": 0 }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    /// Switch a recurring subscription to another plan from the next billing cycle
+    async fn update_subscription_plan(&self, subscription_id: &str, plan_id: &str) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .patch(format!("{}/subscriptions/{}", RAZORPAY_BASE, subscription_id))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&json!({
+                "plan_id": plan_id,
+                "schedule_change_at": "cycle_end"
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    async fn fetch_payment(&self, payment_id: &str) -> Result<serde_json::Value, String> {
+        let client = Client::new();
+
+        let res = client
+            .get(format!("{}/payments/{}", RAZORPAY_BASE, payment_id))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::parse_response(res).await
+    }
+
+    fn verify_payment_signature(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<bool, String> {
+        let secret = std::env::var("RAZORPAY_KEY_SECRET").map_err(|_| "Missing Razorpay secret".to_string())?;
+
+        verify_hmac_sha256_hex(&secret, &format!("{}|{}", order_id, payment_id), signature)
+    }
+
+    fn parse_webhook(&self, body: &str, signature: &str) -> Result<WebhookEvent, String> {
+        let secret = std::env::var("RAZORPAY_WEBHOOK_SECRET").map_err(|_| "Missing webhook secret".to_string())?;
+
+        if !verify_hmac_sha256_hex(&secret, body, signature)? {
+            return Err("Invalid webhook signature".to_string());
+        }
+
+        parse_webhook_body(body)
     }
 }
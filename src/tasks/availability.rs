@@ -0,0 +1,34 @@
+use mongodb::bson::doc;
+use crate::db::DbConn;
+use crate::models::WorkerProfile;
+use crate::routes::worker::sync_availability;
+
+/// Flip `is_available` for workers whose schedule opened or closed since the last run
+pub async fn refresh_worker_availability(db: &DbConn) {
+    let filter = doc! { "schedule": { "$ne": null } };
+
+    let mut cursor = match db.collection::<WorkerProfile>("worker_profiles").find(filter, None).await {
+        Ok(cursor) => cursor,
+        Err(e) => {
+            error!("Worker availability refresh failed: {}", e);
+            return;
+        }
+    };
+
+    let mut changed = 0;
+    while cursor.advance().await.unwrap_or(false) {
+        let Ok(worker) = cursor.deserialize_current() else {
+            continue;
+        };
+
+        match sync_availability(db, &worker).await {
+            Ok(available) if available != worker.is_available => changed += 1,
+            Ok(_) => {}
+            Err(e) => error!("Failed to refresh availability for worker {:?}: {}", worker.id, e.message),
+        }
+    }
+
+    if changed > 0 {
+        info!("Updated availability for {} scheduled workers", changed);
+    }
+}
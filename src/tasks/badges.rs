@@ -0,0 +1,16 @@
+use crate::db::DbConn;
+use crate::routes::badge::evaluate_badges;
+
+/// Re-evaluate every worker against the badge rules
+pub async fn award_badges(db: &DbConn) {
+    match evaluate_badges(db).await {
+        Ok(summary) if summary.awarded > 0 || summary.revoked > 0 => {
+            info!(
+                "Badge run over {} workers: {} awarded, {} revoked",
+                summary.workers, summary.awarded, summary.revoked
+            );
+        }
+        Ok(_) => {}
+        Err(e) => error!("Badge run failed: {}", e.message),
+    }
+}
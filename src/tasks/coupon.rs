@@ -0,0 +1,46 @@
+use mongodb::bson::{doc, DateTime};
+use crate::db::DbConn;
+use crate::models::CouponRedemption;
+use crate::routes::coupon::release_coupon_redemption;
+
+/// How long an unpaid order keeps its coupon reserved
+const RESERVATION_HOURS: i64 = 24;
+
+/// Give back coupon uses held by orders that were never paid
+pub async fn release_abandoned_reservations(db: &DbConn) {
+    let cutoff = DateTime::from_millis(
+        chrono::Utc::now().timestamp_millis() - RESERVATION_HOURS * 60 * 60 * 1000,
+    );
+
+    let filter = doc! {
+        "status": "reserved",
+        "created_at": { "$lte": cutoff }
+    };
+
+    let mut cursor = match db.collection::<CouponRedemption>("coupon_redemptions").find(filter, None).await {
+        Ok(cursor) => cursor,
+        Err(e) => {
+            error!("Coupon reservation sweep failed: {}", e);
+            return;
+        }
+    };
+
+    let mut released = 0;
+    while cursor.advance().await.unwrap_or(false) {
+        let Ok(redemption) = cursor.deserialize_current() else {
+            continue;
+        };
+        let Some(redemption_id) = redemption.id else {
+            continue;
+        };
+
+        match release_coupon_redemption(db, redemption_id).await {
+            Ok(_) => released += 1,
+            Err(e) => error!("Failed to release coupon reservation {}: {}", redemption_id, e.message),
+        }
+    }
+
+    if released > 0 {
+        info!("Released {} abandoned coupon reservations", released);
+    }
+}
@@ -0,0 +1,74 @@
+pub mod availability;
+pub mod badges;
+pub mod coupon;
+pub mod profile_views;
+pub mod saved_search;
+pub mod subscription;
+pub mod tracking;
+
+use rocket::fairing::AdHoc;
+use std::time::Duration;
+use crate::db::DbConn;
+
+/// How often the periodic maintenance jobs run
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Worker schedules are kept in step with the clock more often
+const AVAILABILITY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Badges depend on slow-moving metrics, so the engine runs hourly
+const BADGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Instant saved search alerts go out within this window; daily ones piggyback on it
+const ALERT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub fn init() -> AdHoc {
+    AdHoc::on_liftoff("Background tasks", |rocket| {
+        Box::pin(async move {
+            let Some(db) = rocket.state::<DbConn>().cloned() else {
+                error!("✗ Background tasks not started: MongoDB unavailable");
+                return;
+            };
+
+            let availability_db = db.clone();
+            let badge_db = db.clone();
+            let alert_db = db.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    subscription::expire_subscriptions(&db).await;
+                    coupon::release_abandoned_reservations(&db).await;
+                    tracking::purge_location_trails(&db).await;
+                    profile_views::purge_profile_views(&db).await;
+                }
+            });
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(AVAILABILITY_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    availability::refresh_worker_availability(&availability_db).await;
+                }
+            });
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(BADGE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    badges::award_badges(&badge_db).await;
+                }
+            });
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(ALERT_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    saved_search::send_saved_search_alerts(&alert_db).await;
+                }
+            });
+
+            info!("✓ Background tasks started");
+        })
+    })
+}
@@ -0,0 +1,24 @@
+use mongodb::bson::{doc, DateTime};
+use crate::db::DbConn;
+use crate::models::ProfileView;
+
+/// How long individual view events are kept. The counters on the profiles are not affected.
+const VIEW_RETENTION_DAYS: i64 = 90;
+
+/// Drop profile view events past the retention window
+pub async fn purge_profile_views(db: &DbConn) {
+    let cutoff = DateTime::from_millis(
+        chrono::Utc::now().timestamp_millis() - VIEW_RETENTION_DAYS * 24 * 60 * 60 * 1000,
+    );
+
+    match db.collection::<ProfileView>("profile_views")
+        .delete_many(doc! { "viewed_at": { "$lt": cutoff } }, None)
+        .await
+    {
+        Ok(result) if result.deleted_count > 0 => {
+            info!("Purged {} old profile view events", result.deleted_count);
+        }
+        Ok(_) => {}
+        Err(e) => error!("Profile view purge failed: {}", e),
+    }
+}
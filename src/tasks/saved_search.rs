@@ -0,0 +1,13 @@
+use crate::db::DbConn;
+use crate::routes::saved_search::send_due_alerts;
+
+/// Alert owners of saved searches that have new matches
+pub async fn send_saved_search_alerts(db: &DbConn) {
+    match send_due_alerts(db).await {
+        Ok(summary) if summary.alerted > 0 => {
+            info!("Saved search alerts: {} of {} due searches alerted", summary.alerted, summary.searches);
+        }
+        Ok(_) => {}
+        Err(e) => error!("Saved search alert run failed: {}", e.message),
+    }
+}
@@ -0,0 +1,66 @@
+use mongodb::bson::{doc, DateTime};
+use crate::db::DbConn;
+use crate::models::Subscription;
+use crate::routes::subscription::{downgrade_profile, RENEWAL_GRACE_DAYS};
+
+/// Expire subscriptions past their end date and drop the profile plan they granted.
+/// Auto-renewing subscriptions get a grace period while Razorpay retries the charge.
+pub async fn expire_subscriptions(db: &DbConn) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let grace_cutoff = DateTime::from_millis(now - RENEWAL_GRACE_DAYS * 24 * 60 * 60 * 1000);
+
+    let filter = doc! {
+        "status": "active",
+        "expires_at": { "$lte": DateTime::from_millis(now) },
+        "$or": [
+            { "auto_renew": false },
+            { "renewal_status": "halted" },
+            { "expires_at": { "$lte": grace_cutoff } }
+        ]
+    };
+
+    let mut cursor = match db.collection::<Subscription>("subscriptions").find(filter, None).await {
+        Ok(cursor) => cursor,
+        Err(e) => {
+            error!("Subscription expiry sweep failed: {}", e);
+            return;
+        }
+    };
+
+    let mut expired = 0;
+    while cursor.advance().await.unwrap_or(false) {
+        let Ok(subscription) = cursor.deserialize_current() else {
+            continue;
+        };
+
+        let result = db
+            .collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": subscription.id, "status": "active" },
+                doc! {
+                    "$set": {
+                        "status": "expired",
+                        "auto_renew": false,
+                        "updated_at": DateTime::now()
+                    }
+                },
+                None,
+            )
+            .await;
+
+        match result {
+            Ok(res) if res.modified_count > 0 => {
+                if let Err(e) = downgrade_profile(db, &subscription).await {
+                    error!("Failed to downgrade profile for {:?}: {}", subscription.id, e.message);
+                }
+                expired += 1;
+            }
+            Ok(_) => {}
+            Err(e) => error!("Failed to expire subscription {:?}: {}", subscription.id, e),
+        }
+    }
+
+    if expired > 0 {
+        info!("Expired {} subscriptions", expired);
+    }
+}
@@ -0,0 +1,24 @@
+use mongodb::bson::{doc, DateTime};
+use crate::db::DbConn;
+use crate::models::LocationPoint;
+
+/// How long live location trails are kept for disputes
+const TRAIL_RETENTION_DAYS: i64 = 90;
+
+/// Drop location trails past the retention window
+pub async fn purge_location_trails(db: &DbConn) {
+    let cutoff = DateTime::from_millis(
+        chrono::Utc::now().timestamp_millis() - TRAIL_RETENTION_DAYS * 24 * 60 * 60 * 1000,
+    );
+
+    match db.collection::<LocationPoint>("booking_locations")
+        .delete_many(doc! { "recorded_at": { "$lt": cutoff } }, None)
+        .await
+    {
+        Ok(result) if result.deleted_count > 0 => {
+            info!("Purged {} old booking location points", result.deleted_count);
+        }
+        Ok(_) => {}
+        Err(e) => error!("Location trail purge failed: {}", e),
+    }
+}
@@ -4,6 +4,7 @@ use rocket::http::Status;
 use rocket::response::{self, Responder, Response};
 use rocket::Request;
 use std::io::Cursor;
+use mongodb::error::{Error as MongoError, ErrorKind, WriteFailure};
 use rocket_okapi::okapi::schemars::JsonSchema;
 use rocket_okapi::response::OpenApiResponderInner;
 use rocket_okapi::r#gen::OpenApiGenerator;
@@ -72,6 +73,13 @@ impl ApiError {
         }
     }
 
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        ApiError {
+            status: Status::Forbidden,
+            message: message.into(),
+        }
+    }
+
     pub fn not_found(message: impl Into<String>) -> Self {
         ApiError {
             status: Status::NotFound,
@@ -94,6 +102,15 @@ impl ApiError {
     }
 }
 
+/// Whether a MongoDB insert or update hit a unique index
+pub fn is_duplicate_key(error: &MongoError) -> bool {
+    match *error.kind {
+        ErrorKind::Command(ref e) => e.code == 11000,
+        ErrorKind::Write(WriteFailure::WriteError(ref e)) => e.code == 11000,
+        _ => false,
+    }
+}
+
 /// -----------------------------
 /// Rocket Responder
 /// -----------------------------
@@ -131,6 +148,7 @@ impl OpenApiResponderInner for ApiError {
         for (code, description) in [
             ("400", "Bad request"),
             ("401", "Unauthorized"),
+            ("403", "Forbidden"),
             ("404", "Not found"),
             ("429", "Too many requests"),
             ("500", "Internal server error"),
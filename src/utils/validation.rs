@@ -15,6 +15,16 @@ pub fn validate_pincode(pincode: &str) -> bool {
     re.is_match(pincode)
 }
 
+pub fn validate_gstin(gstin: &str) -> bool {
+    let re = Regex::new(r"^\d{2}[A-Z]{5}\d{4}[A-Z][1-9A-Z]Z[0-9A-Z]$").unwrap();
+    re.is_match(gstin)
+}
+
+pub fn validate_cin(cin: &str) -> bool {
+    let re = Regex::new(r"^[LU]\d{5}[A-Z]{2}\d{4}[A-Z]{3}\d{6}$").unwrap();
+    re.is_match(cin)
+}
+
 pub fn generate_otp() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();